@@ -0,0 +1,101 @@
+// compares the pooled Database against opening a connection per call, as db.rs did before
+// run with `cargo bench --bench list_entries`
+use moodjourney_lib::db::{Database, Entry};
+use rusqlite::{Connection, OptionalExtension};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+const ENTRY_COUNTS: [usize; 3] = [1_000, 5_000, 10_000];
+const LIST_ITERATIONS: u32 = 20;
+const LOOKUPS: u32 = 2_000;
+
+fn bench_dir() -> PathBuf {
+    let mut path = std::env::temp_dir();
+    path.push("moodjourney_benches");
+    let _ = fs::remove_dir_all(&path);
+    fs::create_dir_all(&path).expect("Failed to create benchmark directory");
+    path
+}
+
+fn populate(db: &Database, count: usize) -> Vec<String> {
+    let start = chrono::NaiveDate::from_ymd_opt(1990, 1, 1).unwrap();
+    let mut conn = db.conn().expect("conn failed");
+    let tx = conn.transaction().expect("transaction failed");
+    let mut dates = Vec::with_capacity(count);
+    for day in 0..count {
+        let date = (start + chrono::Duration::days(day as i64)).format("%Y-%m-%d").to_string();
+        tx.execute(
+            "INSERT INTO entries (date, title, content) VALUES (?1, ?2, ?3)",
+            rusqlite::params![date, format!("Entry {}", day), "A few sentences about the day.\n\n🧠 Emotion: joy".repeat(4)],
+        ).expect("insert failed");
+        dates.push(date);
+    }
+    tx.commit().expect("commit failed");
+    dates
+}
+
+fn read_entry(row: &rusqlite::Row) -> rusqlite::Result<Entry> {
+    Ok(Entry {
+        date: row.get(0)?,
+        title: row.get(1)?,
+        content: row.get(2)?,
+        password: row.get(3)?,
+        image: row.get(4)?,
+    })
+}
+
+fn list_opening_per_call(db_file_path: &Path) -> Vec<Entry> {
+    let conn = Connection::open(db_file_path).expect("open failed");
+    let mut stmt = conn.prepare("SELECT date, title, content, password, image FROM entries ORDER BY date DESC").expect("prepare failed");
+    let rows = stmt.query_map([], read_entry).expect("query failed");
+    rows.collect::<rusqlite::Result<Vec<_>>>().expect("read failed")
+}
+
+fn get_opening_per_call(db_file_path: &Path, date: &str) -> Option<Entry> {
+    let conn = Connection::open(db_file_path).expect("open failed");
+    let mut stmt = conn.prepare("SELECT date, title, content, password, image FROM entries WHERE date = ?1").expect("prepare failed");
+    stmt.query_row([date], read_entry).optional().expect("query failed")
+}
+
+fn time<F: FnMut()>(iterations: u32, mut run: F) -> Duration {
+    let started = Instant::now();
+    for _ in 0..iterations {
+        run();
+    }
+    started.elapsed() / iterations
+}
+
+fn report(label: &str, before: Duration, after: Duration) {
+    println!(
+        "{:<34} {:>12.3?} {:>12.3?} {:>8.1}x",
+        label,
+        before,
+        after,
+        before.as_secs_f64() / after.as_secs_f64().max(f64::EPSILON)
+    );
+}
+
+fn main() {
+    let dir = bench_dir();
+    println!("{:<34} {:>12} {:>12} {:>9}", "", "per call", "pooled", "speedup");
+    for count in ENTRY_COUNTS {
+        let db_file_path = dir.join(format!("entries_{}.db", count));
+        let db = Database::open(&db_file_path).expect("open failed");
+        let dates = populate(&db, count);
+
+        let expected = db.get_entries().expect("list failed").len();
+        assert_eq!(list_opening_per_call(&db_file_path).len(), expected);
+
+        let before = time(LIST_ITERATIONS, || { list_opening_per_call(&db_file_path); });
+        let after = time(LIST_ITERATIONS, || { db.get_entries().expect("list failed"); });
+        report(&format!("list {} entries", count), before, after);
+
+        let mut next = dates.iter().cycle();
+        let before = time(LOOKUPS, || { get_opening_per_call(&db_file_path, next.next().unwrap()); });
+        let mut next = dates.iter().cycle();
+        let after = time(LOOKUPS, || { db.get_entry_by_date(next.next().unwrap()).expect("get failed"); });
+        report(&format!("get by date ({} entries)", count), before, after);
+    }
+    let _ = fs::remove_dir_all(dir);
+}
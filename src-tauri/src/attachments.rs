@@ -0,0 +1,297 @@
+use crate::db::{Database, NewAttachment};
+use crate::error::AppError;
+use anyhow::{Result, anyhow};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use uuid::Uuid;
+
+pub const IMAGES_DIR_NAME: &str = "journal_images";
+pub const THUMBNAILS_DIR_NAME: &str = "thumbnails";
+
+pub fn guess_mime(file_name: &str) -> Option<&'static str> {
+    let extension = Path::new(file_name)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|s| s.to_lowercase())?;
+
+    let mime = match extension.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "bmp" => "image/bmp",
+        "heic" => "image/heic",
+        "svg" => "image/svg+xml",
+        "mp3" => "audio/mpeg",
+        "wav" => "audio/wav",
+        "m4a" => "audio/mp4",
+        "ogg" => "audio/ogg",
+        "mp4" => "video/mp4",
+        "mov" => "video/quicktime",
+        "pdf" => "application/pdf",
+        "txt" => "text/plain",
+        "md" => "text/markdown",
+        _ => return None,
+    };
+    Some(mime)
+}
+
+pub fn kind_for_mime(mime: Option<&str>) -> &'static str {
+    match mime {
+        Some(m) if m.starts_with("image/") => "image",
+        Some(m) if m.starts_with("audio/") => "audio",
+        Some(m) if m.starts_with("video/") => "video",
+        _ => "file",
+    }
+}
+
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(bytes);
+    format!("{:x}", hasher.finalize())
+}
+
+// stored files are referenced as journal_images/<name> relative to the app local data dir. Paths from the
+// frontend, imports or an old database are checked before anything is read or deleted, so an absolute
+// path or one with `..` cannot reach files outside the images directory.
+pub fn validate_stored_path(relative_path: &str) -> Result<()> {
+    let mut components = Path::new(relative_path).components();
+    let in_images_dir = components.next() == Some(Component::Normal(IMAGES_DIR_NAME.as_ref()));
+    let rest: Vec<Component> = components.collect();
+    if !in_images_dir || rest.is_empty() || !rest.iter().all(|c| matches!(c, Component::Normal(_))) {
+        return Err(AppError::InvalidInput(format!("'{}' is not a file in {}.", relative_path, IMAGES_DIR_NAME)).into());
+    }
+    Ok(())
+}
+
+pub fn stored_file_path(base_dir: &Path, relative_path: &str) -> Result<PathBuf> {
+    validate_stored_path(relative_path)?;
+    Ok(base_dir.join(relative_path))
+}
+
+// reads a stored file relative to the app local data dir and builds its attachment row
+pub fn describe_file(base_dir: &Path, relative_path: &str) -> Result<NewAttachment> {
+    let full_path = stored_file_path(base_dir, relative_path)?;
+    let bytes = fs::read(&full_path)
+        .map_err(|e| anyhow!("Failed to read attachment file {:?}: {}", full_path, e))?;
+    let mime = guess_mime(relative_path);
+
+    Ok(NewAttachment {
+        kind: kind_for_mime(mime).to_string(),
+        relative_path: relative_path.to_string(),
+        mime: mime.map(|m| m.to_string()),
+        size: bytes.len() as i64,
+        checksum: Some(sha256_hex(&bytes)),
+    })
+}
+
+// writes the bytes under journal_images with a fresh name and returns the relative path
+pub fn store_file(base_dir: &Path, bytes: &[u8], original_file_name: &str, default_extension: &str) -> Result<String> {
+    let images_dir = base_dir.join(IMAGES_DIR_NAME);
+    if !images_dir.exists() {
+        fs::create_dir_all(&images_dir)
+            .map_err(|e| anyhow!("Failed to create images directory at {:?}: {}", images_dir, e))?;
+    }
+
+    let extension = PathBuf::from(original_file_name)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|s| s.to_lowercase())
+        .filter(|s| !s.is_empty() && s.chars().all(|c| c.is_alphanumeric()))
+        .unwrap_or_else(|| default_extension.to_string());
+
+    let new_file_name = format!("{}.{}", Uuid::new_v4(), extension);
+    let file_path = images_dir.join(&new_file_name);
+    fs::write(&file_path, bytes)
+        .map_err(|e| anyhow!("Failed to write file data to {:?}: {}", file_path, e))?;
+
+    Ok(format!("{}/{}", IMAGES_DIR_NAME, new_file_name))
+}
+
+// thumbnails live next to the originals as journal_images/thumbnails/<name>.jpg
+pub fn thumbnail_relative_path(relative_path: &str) -> String {
+    let stem = Path::new(relative_path)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or(relative_path);
+    format!("{}/{}/{}.jpg", IMAGES_DIR_NAME, THUMBNAILS_DIR_NAME, stem)
+}
+
+pub fn store_thumbnail(base_dir: &Path, relative_path: &str, thumbnail_bytes: &[u8]) -> Result<String> {
+    let thumbnail_path = thumbnail_relative_path(relative_path);
+    let full_path = base_dir.join(&thumbnail_path);
+    if let Some(parent_dir) = full_path.parent() {
+        fs::create_dir_all(parent_dir)
+            .map_err(|e| anyhow!("Failed to create thumbnails directory at {:?}: {}", parent_dir, e))?;
+    }
+    fs::write(&full_path, thumbnail_bytes)
+        .map_err(|e| anyhow!("Failed to write thumbnail to {:?}: {}", full_path, e))?;
+    Ok(thumbnail_path)
+}
+
+pub fn remove_stored_file(base_dir: &Path, relative_path: &str) -> Result<()> {
+    if relative_path.trim().is_empty() {
+        return Ok(());
+    }
+    let full_path = stored_file_path(base_dir, relative_path)?;
+    if full_path.exists() {
+        fs::remove_file(&full_path)
+            .map_err(|e| anyhow!("Failed to delete file {:?}: {}", full_path, e))?;
+    }
+    let thumbnail_path = base_dir.join(thumbnail_relative_path(relative_path));
+    if thumbnail_path.exists() {
+        fs::remove_file(&thumbnail_path)
+            .map_err(|e| anyhow!("Failed to delete thumbnail {:?}: {}", thumbnail_path, e))?;
+    }
+    Ok(())
+}
+
+// keeps the attachments table in step with the single legacy image column
+pub fn sync_legacy_image(db: &Database, base_dir: &Path, entry_date: &str, old_image: Option<&str>, new_image: Option<&str>) -> Result<()> {
+    let old_image = old_image.filter(|p| !p.trim().is_empty());
+    let new_image = new_image.filter(|p| !p.trim().is_empty());
+    if old_image == new_image {
+        return Ok(());
+    }
+
+    if let Some(old_path) = old_image {
+        db.delete_attachments_by_path(entry_date, old_path)?;
+    }
+    if let Some(new_path) = new_image {
+        let existing = db.get_attachments_for_entry(entry_date)?;
+        if !existing.iter().any(|a| a.relative_path == new_path) {
+            let attachment = describe_file(base_dir, new_path)?;
+            db.add_attachment(entry_date, &attachment)?;
+        }
+    }
+    Ok(())
+}
+
+// copies entries.image values that have no attachment row yet into the attachments table; the
+// query only returns what is left to migrate, so running it on every launch stays cheap
+pub fn migrate_legacy_images(db: &Database, base_dir: &Path) -> Result<usize> {
+    let mut migrated = 0;
+    for (entry_date, image_path) in db.get_unattached_entry_images()? {
+        let image_path = image_path.as_str();
+        let attachment = match describe_file(base_dir, image_path) {
+            Ok(attachment) => attachment,
+            Err(e) => {
+                log::warn!("[attachments] Migrating missing image for {}: {}", entry_date, e);
+                let mime = guess_mime(image_path);
+                NewAttachment {
+                    kind: kind_for_mime(mime).to_string(),
+                    relative_path: image_path.to_string(),
+                    mime: mime.map(|m| m.to_string()),
+                    size: 0,
+                    checksum: None,
+                }
+            }
+        };
+        db.add_attachment(&entry_date, &attachment)?;
+        migrated += 1;
+    }
+    Ok(migrated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{Database, Entry};
+
+    fn get_test_dir(test_name: &str) -> PathBuf {
+        let mut path = std::env::temp_dir();
+        path.push("moodjourney_attachment_tests");
+        path.push(test_name);
+        if path.exists() {
+            let _ = fs::remove_dir_all(&path);
+        }
+        fs::create_dir_all(&path).expect("Failed to create temporary test directory for attachments");
+        path
+    }
+
+    #[test]
+    fn test_guess_mime_and_kind() {
+        assert_eq!(guess_mime("photo.JPG"), Some("image/jpeg"));
+        assert_eq!(guess_mime("voice.m4a"), Some("audio/mp4"));
+        assert_eq!(guess_mime("no_extension"), None);
+        assert_eq!(kind_for_mime(Some("image/png")), "image");
+        assert_eq!(kind_for_mime(Some("application/pdf")), "file");
+        assert_eq!(kind_for_mime(None), "file");
+    }
+
+    #[test]
+    fn test_stored_paths_must_stay_in_the_images_directory() {
+        assert!(validate_stored_path("journal_images/photo.png").is_ok());
+        assert!(validate_stored_path("journal_images/thumbnails/photo.jpg").is_ok());
+        for path in ["journal_images", "journal_images/../entries.db", "../journal_images/photo.png", "/etc/passwd", "other/photo.png", ""] {
+            let err = validate_stored_path(path).expect_err(&format!("'{}' should be rejected.", path));
+            assert_eq!(AppError::code_of(&err), "INVALID_INPUT");
+        }
+
+        let base_dir = get_test_dir("outside_paths");
+        let outside = base_dir.parent().expect("test dir has a parent").join("outside_paths_keep.txt");
+        fs::write(&outside, b"keep").expect("Failed to write file");
+        assert!(remove_stored_file(&base_dir, "journal_images/../../outside_paths_keep.txt").is_err());
+        assert!(describe_file(&base_dir, outside.to_str().expect("utf-8 path")).is_err());
+        assert!(outside.exists(), "A file outside the images directory must not be deleted.");
+        let _ = fs::remove_file(outside);
+        let _ = fs::remove_dir_all(base_dir);
+    }
+
+    #[test]
+    fn test_store_and_describe_file() {
+        let base_dir = get_test_dir("store_and_describe");
+        let relative_path = store_file(&base_dir, b"hello", "notes.txt", "bin").expect("store failed");
+        assert!(relative_path.starts_with("journal_images/"));
+        assert!(relative_path.ends_with(".txt"));
+
+        let described = describe_file(&base_dir, &relative_path).expect("describe failed");
+        assert_eq!(described.size, 5);
+        assert_eq!(described.kind, "file");
+        assert_eq!(described.checksum.as_deref(), Some(sha256_hex(b"hello").as_str()));
+
+        let thumbnail_path = store_thumbnail(&base_dir, &relative_path, b"thumb").expect("thumbnail store failed");
+        assert_eq!(thumbnail_path, thumbnail_relative_path(&relative_path));
+        assert!(thumbnail_path.starts_with("journal_images/thumbnails/"));
+
+        remove_stored_file(&base_dir, &relative_path).expect("remove failed");
+        assert!(!base_dir.join(&relative_path).exists());
+        assert!(!base_dir.join(&thumbnail_path).exists(), "Thumbnail should be removed with its image.");
+
+        let _ = fs::remove_dir_all(base_dir);
+    }
+
+    #[test]
+    fn test_migrate_legacy_images_is_idempotent() {
+        let base_dir = get_test_dir("migrate_legacy_images");
+        let db = Database::open(&base_dir.join("entries.db")).expect("init failed");
+
+        let relative_path = store_file(&base_dir, b"fake png bytes", "cover.png", "png").expect("store failed");
+        db.add_entry(Entry {
+            date: "2025-05-01".to_string(),
+            title: Some("With image".to_string()),
+            content: None,
+            password: None,
+            image: Some(relative_path.clone()),
+        }).expect("add failed");
+        db.add_entry(Entry {
+            date: "2025-05-02".to_string(),
+            title: Some("Without image".to_string()),
+            content: None,
+            password: None,
+            image: None,
+        }).expect("add failed");
+
+        assert_eq!(migrate_legacy_images(&db, &base_dir).expect("migration failed"), 1);
+        assert_eq!(migrate_legacy_images(&db, &base_dir).expect("second migration failed"), 0);
+
+        let attachments = db.get_attachments_for_entry("2025-05-01").expect("list failed");
+        assert_eq!(attachments.len(), 1);
+        assert_eq!(attachments[0].relative_path, relative_path);
+        assert_eq!(attachments[0].kind, "image");
+        assert_eq!(attachments[0].size, 14);
+
+        let _ = fs::remove_dir_all(base_dir);
+    }
+}
@@ -0,0 +1,528 @@
+use crate::attachments::{sha256_hex, IMAGES_DIR_NAME};
+use crate::db;
+use crate::error::AppError;
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use anyhow::{Context, Result, anyhow};
+use argon2::Argon2;
+use chrono::Utc;
+use rusqlite::{Connection, DatabaseName};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{Cursor, Read, Write};
+use std::path::{Component, Path, PathBuf};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+pub const BACKUP_FORMAT_VERSION: u32 = 1;
+pub const BACKUP_FILE_EXTENSION: &str = "mjbackup";
+
+const MANIFEST_NAME: &str = "manifest.json";
+const DB_ARCHIVE_NAME: &str = "entries.db";
+const SETTINGS_ARCHIVE_NAME: &str = "settings.json";
+
+// encrypted archives are MAGIC | salt | nonce | AES-256-GCM(zip bytes)
+const ENCRYPTED_MAGIC: &[u8; 8] = b"MJBKENC1";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupFileRecord {
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupManifest {
+    pub format_version: u32,
+    pub schema_version: i64,
+    pub app_version: String,
+    pub created_at: String,
+    pub encrypted: bool,
+    pub entry_count: usize,
+    pub files: Vec<BackupFileRecord>,
+}
+
+// where the live data the archive is built from (and restored into) lives
+#[derive(Debug, Clone)]
+pub struct BackupPaths {
+    pub db_file_path: PathBuf,
+    pub app_local_data_dir: PathBuf,
+    pub settings_file_path: Option<PathBuf>,
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<[u8; 32]> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| anyhow!("Failed to derive backup key: {}", e))?;
+    Ok(key)
+}
+
+fn encrypt_archive(plain: &[u8], passphrase: &str) -> Result<Vec<u8>> {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+
+    let key = derive_key(passphrase, &salt)?;
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plain)
+        .map_err(|_| anyhow!("Failed to encrypt backup archive."))?;
+
+    let mut out = Vec::with_capacity(ENCRYPTED_MAGIC.len() + SALT_LEN + NONCE_LEN + ciphertext.len());
+    out.extend_from_slice(ENCRYPTED_MAGIC);
+    out.extend_from_slice(&salt);
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&ciphertext);
+    Ok(out)
+}
+
+fn decrypt_archive(data: &[u8], passphrase: Option<&str>) -> Result<Vec<u8>> {
+    if !data.starts_with(ENCRYPTED_MAGIC) {
+        return Ok(data.to_vec());
+    }
+    let passphrase = passphrase
+        .filter(|p| !p.is_empty())
+        .ok_or_else(|| AppError::PassphraseRequired("This backup is encrypted. Please provide its passphrase.".to_string()))?;
+
+    let header_len = ENCRYPTED_MAGIC.len() + SALT_LEN + NONCE_LEN;
+    if data.len() <= header_len {
+        return Err(anyhow!("Backup archive is truncated."));
+    }
+    let salt = &data[ENCRYPTED_MAGIC.len()..ENCRYPTED_MAGIC.len() + SALT_LEN];
+    let nonce = Nonce::from_slice(&data[ENCRYPTED_MAGIC.len() + SALT_LEN..header_len]);
+
+    let key = derive_key(passphrase, salt)?;
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+    cipher
+        .decrypt(nonce, &data[header_len..])
+        .map_err(|_| AppError::WrongPassword("Wrong passphrase or corrupted backup.".to_string()).into())
+}
+
+fn collect_image_files(dir: &Path, base_dir: &Path, files: &mut Vec<(String, PathBuf)>) -> Result<()> {
+    for dir_entry in fs::read_dir(dir).map_err(|e| anyhow!("Failed to read directory {:?}: {}", dir, e))? {
+        let path = dir_entry.map_err(|e| anyhow!("Failed to read directory entry in {:?}: {}", dir, e))?.path();
+        if path.is_dir() {
+            collect_image_files(&path, base_dir, files)?;
+        } else if let Ok(relative) = path.strip_prefix(base_dir) {
+            let archive_name = relative.components()
+                .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                .collect::<Vec<_>>()
+                .join("/");
+            files.push((archive_name, path));
+        }
+    }
+    Ok(())
+}
+
+// uses the SQLite online backup API so a consistent copy is taken even while the app is running
+fn snapshot_database(db_file_path: &Path, snapshot_path: &Path) -> Result<()> {
+    let conn = Connection::open(db_file_path)?;
+    conn.backup(DatabaseName::Main, snapshot_path, None)
+        .map_err(|e| anyhow!("Failed to snapshot database {:?}: {}", db_file_path, e))
+}
+
+fn temp_work_dir(label: &str) -> Result<PathBuf> {
+    let dir = std::env::temp_dir().join(format!("moodjourney_{}_{}", label, uuid::Uuid::new_v4()));
+    fs::create_dir_all(&dir).map_err(|e| anyhow!("Failed to create temporary directory {:?}: {}", dir, e))?;
+    Ok(dir)
+}
+
+pub fn build_backup_archive(paths: &BackupPaths, passphrase: Option<&str>) -> Result<(Vec<u8>, BackupManifest)> {
+    let work_dir = temp_work_dir("backup")?;
+    let result = build_backup_archive_in(paths, passphrase, &work_dir);
+    let _ = fs::remove_dir_all(&work_dir);
+    result
+}
+
+fn build_backup_archive_in(paths: &BackupPaths, passphrase: Option<&str>, work_dir: &Path) -> Result<(Vec<u8>, BackupManifest)> {
+    let snapshot_path = work_dir.join(DB_ARCHIVE_NAME);
+    snapshot_database(&paths.db_file_path, &snapshot_path)?;
+
+    let mut contents: Vec<(String, Vec<u8>)> = Vec::new();
+    contents.push((DB_ARCHIVE_NAME.to_string(), fs::read(&snapshot_path)?));
+
+    let images_dir = paths.app_local_data_dir.join(IMAGES_DIR_NAME);
+    if images_dir.exists() {
+        let mut image_files = Vec::new();
+        collect_image_files(&images_dir, &paths.app_local_data_dir, &mut image_files)?;
+        image_files.sort();
+        for (archive_name, full_path) in image_files {
+            let bytes = fs::read(&full_path).map_err(|e| anyhow!("Failed to read {:?}: {}", full_path, e))?;
+            contents.push((archive_name, bytes));
+        }
+    }
+
+    if let Some(settings_path) = paths.settings_file_path.as_ref().filter(|p| p.exists()) {
+        contents.push((SETTINGS_ARCHIVE_NAME.to_string(), fs::read(settings_path)?));
+    }
+
+    let snapshot = Connection::open(&snapshot_path)?;
+    let manifest = BackupManifest {
+        format_version: BACKUP_FORMAT_VERSION,
+        schema_version: snapshot.query_row("PRAGMA user_version", [], |row| row.get(0))?,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: Utc::now().to_rfc3339(),
+        encrypted: passphrase.is_some_and(|p| !p.is_empty()),
+        entry_count: snapshot.query_row("SELECT COUNT(*) FROM entries", [], |row| row.get::<_, i64>(0))? as usize,
+        files: contents.iter().map(|(name, bytes)| BackupFileRecord {
+            path: name.clone(),
+            size: bytes.len() as u64,
+            sha256: sha256_hex(bytes),
+        }).collect(),
+    };
+
+    let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    writer.start_file(MANIFEST_NAME, options)?;
+    writer.write_all(serde_json::to_string_pretty(&manifest)?.as_bytes())?;
+    for (name, bytes) in &contents {
+        writer.start_file(name.as_str(), options)?;
+        writer.write_all(bytes)?;
+    }
+    let zip_bytes = writer.finish()?.into_inner();
+
+    let archive_bytes = match passphrase.filter(|p| !p.is_empty()) {
+        Some(passphrase) => encrypt_archive(&zip_bytes, passphrase)?,
+        None => zip_bytes,
+    };
+    Ok((archive_bytes, manifest))
+}
+
+// writes to a temporary sibling first so a failed export never leaves a half-written archive
+pub fn export_backup(paths: &BackupPaths, archive_path: &Path, passphrase: Option<&str>) -> Result<BackupManifest> {
+    let (archive_bytes, manifest) = build_backup_archive(paths, passphrase)?;
+
+    if let Some(parent_dir) = archive_path.parent() {
+        fs::create_dir_all(parent_dir).map_err(|e| anyhow!("Failed to create backup directory {:?}: {}", parent_dir, e))?;
+    }
+    let partial_path = archive_path.with_extension("partial");
+    fs::write(&partial_path, &archive_bytes).map_err(|e| anyhow!("Failed to write backup to {:?}: {}", partial_path, e))?;
+    fs::rename(&partial_path, archive_path).map_err(|e| anyhow!("Failed to finalize backup at {:?}: {}", archive_path, e))?;
+
+    log::info!("[backup] Exported {} file(s) to {:?}.", manifest.files.len(), archive_path);
+    Ok(manifest)
+}
+
+fn is_safe_archive_path(name: &str) -> bool {
+    let path = Path::new(name);
+    !name.is_empty() && path.components().all(|c| matches!(c, Component::Normal(_)))
+}
+
+struct ValidatedArchive {
+    manifest: BackupManifest,
+    files: Vec<(String, Vec<u8>)>,
+}
+
+fn read_and_validate(archive_bytes: &[u8], passphrase: Option<&str>) -> Result<ValidatedArchive> {
+    let zip_bytes = decrypt_archive(archive_bytes, passphrase)?;
+    let mut archive = ZipArchive::new(Cursor::new(zip_bytes))
+        .map_err(|e| AppError::InvalidFile(format!("Not a MoodJourney backup archive: {}", e)))?;
+
+    let manifest: BackupManifest = {
+        let mut manifest_file = archive.by_name(MANIFEST_NAME)
+            .map_err(|_| AppError::InvalidFile("Backup archive has no manifest.".to_string()))?;
+        let mut manifest_json = String::new();
+        manifest_file.read_to_string(&mut manifest_json)?;
+        serde_json::from_str(&manifest_json)
+            .map_err(|e| AppError::InvalidFile(format!("Backup manifest is malformed: {}", e)))?
+    };
+
+    if manifest.format_version > BACKUP_FORMAT_VERSION {
+        return Err(AppError::UnsupportedVersion(format!(
+            "Backup format version {} is newer than this app supports ({}).",
+            manifest.format_version,
+            BACKUP_FORMAT_VERSION
+        )).into());
+    }
+    if manifest.schema_version > db::SCHEMA_VERSION {
+        return Err(AppError::UnsupportedVersion(format!(
+            "Backup database schema version {} is newer than this app supports ({}).",
+            manifest.schema_version,
+            db::SCHEMA_VERSION
+        )).into());
+    }
+    if !manifest.files.iter().any(|f| f.path == DB_ARCHIVE_NAME) {
+        return Err(AppError::InvalidFile("Backup does not contain a database.".to_string()).into());
+    }
+
+    let mut files = Vec::with_capacity(manifest.files.len());
+    for record in &manifest.files {
+        if !is_safe_archive_path(&record.path) {
+            return Err(AppError::InvalidFile(format!("Backup contains an unsafe path: {}", record.path)).into());
+        }
+        let mut bytes = Vec::new();
+        archive.by_name(&record.path)
+            .map_err(|_| AppError::InvalidFile(format!("Backup is missing {}.", record.path)))?
+            .read_to_end(&mut bytes)?;
+        if bytes.len() as u64 != record.size || sha256_hex(&bytes) != record.sha256 {
+            return Err(AppError::Corrupted(format!("Checksum mismatch for {}.", record.path)).into());
+        }
+        files.push((record.path.clone(), bytes));
+    }
+
+    Ok(ValidatedArchive { manifest, files })
+}
+
+pub fn read_backup_manifest(archive_path: &Path, passphrase: Option<&str>) -> Result<BackupManifest> {
+    let archive_bytes = fs::read(archive_path).with_context(|| format!("Failed to read backup {:?}", archive_path))?;
+    Ok(read_and_validate(&archive_bytes, passphrase)?.manifest)
+}
+
+// nothing on disk is touched until the whole archive has been validated
+pub fn import_backup(paths: &BackupPaths, archive_path: &Path, passphrase: Option<&str>) -> Result<BackupManifest> {
+    let archive_bytes = fs::read(archive_path).with_context(|| format!("Failed to read backup {:?}", archive_path))?;
+    let validated = read_and_validate(&archive_bytes, passphrase)?;
+
+    let work_dir = temp_work_dir("restore")?;
+    let result = restore_validated(paths, validated, &work_dir);
+    let _ = fs::remove_dir_all(&work_dir);
+    result
+}
+
+fn restore_validated(paths: &BackupPaths, validated: ValidatedArchive, work_dir: &Path) -> Result<BackupManifest> {
+    let staged_images_dir = work_dir.join(IMAGES_DIR_NAME);
+    fs::create_dir_all(&staged_images_dir)?;
+    let mut staged_db_path: Option<PathBuf> = None;
+    let mut settings_bytes: Option<Vec<u8>> = None;
+
+    for (name, bytes) in validated.files {
+        if name == DB_ARCHIVE_NAME {
+            let path = work_dir.join(DB_ARCHIVE_NAME);
+            fs::write(&path, &bytes)?;
+            staged_db_path = Some(path);
+        } else if name == SETTINGS_ARCHIVE_NAME {
+            settings_bytes = Some(bytes);
+        } else if name.starts_with(&format!("{}/", IMAGES_DIR_NAME)) {
+            let path = work_dir.join(&name);
+            if let Some(parent_dir) = path.parent() {
+                fs::create_dir_all(parent_dir)?;
+            }
+            fs::write(&path, &bytes)?;
+        }
+    }
+
+    let staged_db_path = staged_db_path.ok_or_else(|| AppError::InvalidFile("Backup does not contain a database.".to_string()))?;
+    {
+        let staged = Connection::open(&staged_db_path)?;
+        let integrity: String = staged.query_row("PRAGMA integrity_check", [], |row| row.get(0))?;
+        if integrity != "ok" {
+            return Err(AppError::Corrupted(format!("Backup database failed its integrity check: {}", integrity)).into());
+        }
+    }
+
+    // 1. Database: restore through the backup API so open handles see the new content
+    if let Some(parent_dir) = paths.db_file_path.parent() {
+        fs::create_dir_all(parent_dir)?;
+    }
+    let mut live = Connection::open(&paths.db_file_path)?;
+    live.restore(DatabaseName::Main, &staged_db_path, None::<fn(rusqlite::backup::Progress)>)
+        .map_err(|e| anyhow!("Failed to restore database: {}", e))?;
+    drop(live);
+    // brings an older restored schema up to date
+    db::Database::open(&paths.db_file_path)?;
+
+    // 2. Images: swap the staged directory in, keeping the old one until the swap succeeded
+    fs::create_dir_all(&paths.app_local_data_dir)?;
+    let live_images_dir = paths.app_local_data_dir.join(IMAGES_DIR_NAME);
+    let previous_images_dir = paths.app_local_data_dir.join(format!("{}.before-restore", IMAGES_DIR_NAME));
+    if previous_images_dir.exists() {
+        fs::remove_dir_all(&previous_images_dir)?;
+    }
+    if live_images_dir.exists() {
+        fs::rename(&live_images_dir, &previous_images_dir)?;
+    }
+    if let Err(e) = move_dir(&staged_images_dir, &live_images_dir) {
+        if previous_images_dir.exists() {
+            let _ = fs::remove_dir_all(&live_images_dir);
+            let _ = fs::rename(&previous_images_dir, &live_images_dir);
+        }
+        return Err(anyhow!("Failed to restore images: {}", e));
+    }
+    if previous_images_dir.exists() {
+        let _ = fs::remove_dir_all(&previous_images_dir);
+    }
+
+    // 3. Settings
+    if let (Some(settings_path), Some(bytes)) = (paths.settings_file_path.as_ref(), settings_bytes) {
+        if let Some(parent_dir) = settings_path.parent() {
+            fs::create_dir_all(parent_dir)?;
+        }
+        fs::write(settings_path, bytes)?;
+    }
+
+    log::info!("[backup] Restored backup created at {} ({} entries).", validated.manifest.created_at, validated.manifest.entry_count);
+    Ok(validated.manifest)
+}
+
+// rename when possible, copy when the temp dir lives on another filesystem
+fn move_dir(from: &Path, to: &Path) -> Result<()> {
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    copy_dir(from, to)
+}
+
+fn copy_dir(from: &Path, to: &Path) -> Result<()> {
+    fs::create_dir_all(to)?;
+    for dir_entry in fs::read_dir(from)? {
+        let path = dir_entry?.path();
+        let target = to.join(path.file_name().ok_or_else(|| anyhow!("Invalid file name in {:?}", path))?);
+        if path.is_dir() {
+            copy_dir(&path, &target)?;
+        } else {
+            fs::copy(&path, &target)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{Database, Entry};
+
+    fn get_test_dir(test_name: &str) -> PathBuf {
+        let mut path = std::env::temp_dir();
+        path.push("moodjourney_backup_tests");
+        path.push(test_name);
+        if path.exists() {
+            let _ = fs::remove_dir_all(&path);
+        }
+        fs::create_dir_all(&path).expect("Failed to create temporary test directory for backups");
+        path
+    }
+
+    fn setup_paths(root: &Path, name: &str) -> BackupPaths {
+        let data_dir = root.join(name);
+        fs::create_dir_all(&data_dir).expect("Failed to create data dir");
+        BackupPaths {
+            db_file_path: data_dir.join("entries.db"),
+            app_local_data_dir: data_dir.clone(),
+            settings_file_path: Some(data_dir.join("settings.json")),
+        }
+    }
+
+    fn populate(paths: &BackupPaths) {
+        let db = Database::open(&paths.db_file_path).expect("init failed");
+        db.add_entry(Entry {
+            date: "2025-07-01".to_string(),
+            title: Some("Backed up".to_string()),
+            content: Some("Content".to_string()),
+            password: None,
+            image: Some("journal_images/photo.png".to_string()),
+        }).expect("add failed");
+        fs::create_dir_all(paths.app_local_data_dir.join("journal_images/thumbnails")).unwrap();
+        fs::write(paths.app_local_data_dir.join("journal_images/photo.png"), b"png bytes").unwrap();
+        fs::write(paths.app_local_data_dir.join("journal_images/thumbnails/photo.jpg"), b"thumb").unwrap();
+        fs::write(paths.settings_file_path.as_ref().unwrap(), br#"{"thumbnailSize": 111}"#).unwrap();
+    }
+
+    #[test]
+    fn test_plain_backup_round_trip() {
+        let root = get_test_dir("plain_round_trip");
+        let source = setup_paths(&root, "source");
+        populate(&source);
+
+        let archive_path = root.join("backup.mjbackup");
+        let manifest = export_backup(&source, &archive_path, None).expect("export failed");
+        assert!(!manifest.encrypted);
+        assert_eq!(manifest.entry_count, 1);
+        assert_eq!(manifest.schema_version, db::SCHEMA_VERSION);
+        assert_eq!(manifest.files.len(), 4);
+
+        let target = setup_paths(&root, "target");
+        let target_db = Database::open(&target.db_file_path).expect("init failed");
+        fs::create_dir_all(target.app_local_data_dir.join("journal_images")).unwrap();
+        fs::write(target.app_local_data_dir.join("journal_images/stale.png"), b"stale").unwrap();
+
+        import_backup(&target, &archive_path, None).expect("import failed");
+        let entries = target_db.get_entries().expect("list failed");
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].title.as_deref(), Some("Backed up"));
+        assert_eq!(fs::read(target.app_local_data_dir.join("journal_images/photo.png")).unwrap(), b"png bytes");
+        assert!(target.app_local_data_dir.join("journal_images/thumbnails/photo.jpg").exists());
+        assert!(!target.app_local_data_dir.join("journal_images/stale.png").exists(), "Restore should replace the images directory.");
+        assert_eq!(fs::read_to_string(target.settings_file_path.as_ref().unwrap()).unwrap(), r#"{"thumbnailSize": 111}"#);
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn test_encrypted_backup_requires_correct_passphrase() {
+        let root = get_test_dir("encrypted");
+        let source = setup_paths(&root, "source");
+        populate(&source);
+
+        let archive_path = root.join("backup.mjbackup");
+        let manifest = export_backup(&source, &archive_path, Some("correct horse")).expect("export failed");
+        assert!(manifest.encrypted);
+
+        let raw = fs::read(&archive_path).unwrap();
+        assert!(raw.starts_with(ENCRYPTED_MAGIC));
+
+        let missing = read_backup_manifest(&archive_path, None);
+        assert_eq!(AppError::code_of(&missing.err().unwrap()), "PASSPHRASE_REQUIRED");
+        let wrong = read_backup_manifest(&archive_path, Some("wrong"));
+        assert_eq!(AppError::code_of(&wrong.err().unwrap()), "WRONG_PASSWORD");
+
+        let target = setup_paths(&root, "target");
+        import_backup(&target, &archive_path, Some("correct horse")).expect("import failed");
+        let target_db = Database::open(&target.db_file_path).expect("open failed");
+        assert_eq!(target_db.get_entries().expect("list failed").len(), 1);
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    fn rewrite_archive(archive_bytes: Vec<u8>, manifest: &BackupManifest, tampered_path: Option<&str>) -> Vec<u8> {
+        let mut archive = ZipArchive::new(Cursor::new(archive_bytes)).unwrap();
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default();
+        writer.start_file(MANIFEST_NAME, options).unwrap();
+        writer.write_all(serde_json::to_string(manifest).unwrap().as_bytes()).unwrap();
+        for record in &manifest.files {
+            let mut bytes = Vec::new();
+            archive.by_name(&record.path).unwrap().read_to_end(&mut bytes).unwrap();
+            if tampered_path == Some(record.path.as_str()) {
+                bytes = b"tampered".to_vec();
+            }
+            writer.start_file(record.path.as_str(), options).unwrap();
+            writer.write_all(&bytes).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_tampered_or_newer_backup_is_rejected_without_touching_data() {
+        let root = get_test_dir("tampered");
+        let source = setup_paths(&root, "source");
+        populate(&source);
+        let (archive_bytes, manifest) = build_backup_archive(&source, None).expect("build failed");
+
+        let mut future_manifest = manifest.clone();
+        future_manifest.schema_version = db::SCHEMA_VERSION + 1;
+        let future_path = root.join("future.mjbackup");
+        fs::write(&future_path, rewrite_archive(archive_bytes.clone(), &future_manifest, None)).unwrap();
+
+        let tampered_path = root.join("tampered.mjbackup");
+        fs::write(&tampered_path, rewrite_archive(archive_bytes, &manifest, Some("journal_images/photo.png"))).unwrap();
+
+        let target = setup_paths(&root, "target");
+        let target_db = Database::open(&target.db_file_path).expect("init failed");
+
+        let future_result = import_backup(&target, &future_path, None);
+        assert_eq!(AppError::code_of(&future_result.err().unwrap()), "UNSUPPORTED_VERSION");
+        let tampered_result = import_backup(&target, &tampered_path, None);
+        assert_eq!(AppError::code_of(&tampered_result.err().unwrap()), "CORRUPTED");
+
+        assert!(target_db.get_entries().expect("list failed").is_empty(), "Rejected restore must not modify data.");
+        assert!(!target.app_local_data_dir.join("journal_images/photo.png").exists());
+
+        let _ = fs::remove_dir_all(root);
+    }
+}
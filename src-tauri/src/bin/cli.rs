@@ -1,148 +1,1860 @@
-use moodjourney_lib::db::{
-    Entry, init_db, add_entry, create_entry_with_now, get_entries, get_entry_by_date, update_entry_by_date, delete_entry_by_date,
-};
-use moodjourney_lib::password::{ 
-    self,
-    PasswordState,
-};
-use std::env;
-use std::io::{self, Write};
-use std::path::PathBuf;
-use std::sync::Mutex;
+use clap::{Args, Parser, Subcommand};
+use moodjourney_lib::attachments;
+use moodjourney_lib::checkins::{CheckinInput, CheckinQuery};
+use moodjourney_lib::context::{self as app_context, AppContext};
+use moodjourney_lib::dataset::{self, DatasetFileFormat, DatasetImportOptions};
+use moodjourney_lib::db::{ChatMessage, ChatSession, Checkin, Database, Draft, Entry, EntryQuery, SortOrder};
+use moodjourney_lib::dictation::{self, DictationModel, DictationOptions};
+use moodjourney_lib::drafts;
+use moodjourney_lib::emotion::EmotionModel;
+use moodjourney_lib::error::AppError;
+use moodjourney_lib::export::{self, ExportFormat, ExportOptions, EMOTION_MARKER};
+use moodjourney_lib::importers::{ConflictPolicy, ImportOptions, ImportSource};
+use moodjourney_lib::listing;
+use moodjourney_lib::services;
+use moodjourney_lib::settings::AppSettings;
+use moodjourney_lib::suggestion::{self, ChatCompletionResponse};
+use serde::Serialize;
+use serde_json::{json, Value};
 use std::fs;
+use std::io::{self, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{self, ExitCode};
 
+const APP_IDENTIFIER: &str = "com.moodjourney.app";
 
-fn main() {
-    let args: Vec<String> = env::args().collect();
+// what the app saves an entry without a title as
+const DEFAULT_ENTRY_TITLE: &str = "Journal Entry";
 
-    let password_file_path: PathBuf = match dirs::config_dir() {
-        Some(mut config_path) => {
-            config_path.push("com.moodjourney.app");
-            if !config_path.exists() {
-                if let Err(_e) = fs::create_dir_all(&config_path) {
-                    eprintln!("Failed to create directory. Password functionality will fail.");
-                    PathBuf::new()
+// exit codes are part of the CLI contract, scripts match on them
+const EXIT_FAILURE: u8 = 1;
+const EXIT_USAGE: u8 = 2;
+const EXIT_NOT_FOUND: u8 = 3;
+const EXIT_CONFLICT: u8 = 4;
+const EXIT_DENIED: u8 = 5;
+
+#[derive(Parser)]
+#[command(name = "cli", version, about = "Manage the MoodJourney journal from the command line")]
+struct Cli {
+    #[arg(long, global = true, value_name = "PATH", help = "Database file to use instead of the app's entries.db")]
+    db: Option<PathBuf>,
+
+    #[arg(long, global = true, value_name = "DIR", help = "Directory holding journal_images, defaults to the app local data directory")]
+    data_dir: Option<PathBuf>,
+
+    #[arg(long, global = true, value_name = "DIR", help = "Directory holding settings.json and password.json, defaults to the app config directory")]
+    config_dir: Option<PathBuf>,
+
+    #[arg(long, global = true, value_name = "DIR", help = "Directory holding the emotion and dictation models, defaults to the models bundled with the app")]
+    models_dir: Option<PathBuf>,
+
+    #[arg(long, global = true, help = "Print results as JSON")]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    #[command(about = "Create or migrate the database")]
+    Init,
+    #[command(subcommand, about = "Read and write journal entries")]
+    Entries(EntriesCommand),
+    #[command(subcommand, about = "Inspect and delete assistant chat sessions")]
+    Sessions(SessionsCommand),
+    #[command(subcommand, about = "List, restore and purge deleted entries and chat sessions")]
+    Trash(TrashCommand),
+    #[command(subcommand, about = "Inspect, save or discard autosaved drafts")]
+    Drafts(DraftsCommand),
+    #[command(subcommand, about = "Tag entries and manage tags")]
+    Tags(TagsCommand),
+    #[command(subcommand, about = "Record mood check-ins and compare them with the emotion model")]
+    Checkins(CheckinsCommand),
+    #[command(subcommand, about = "Manage the app lock PIN")]
+    Password(PasswordCommand),
+    #[command(subcommand, about = "Show and change app settings")]
+    Settings(SettingsCommand),
+    #[command(about = "Write today's entry in $EDITOR, or from stdin when it is piped")]
+    Write {
+        #[arg(long, value_name = "YYYY-MM-DD")]
+        date: Option<String>,
+        #[arg(long, default_value = "Journal Entry")]
+        title: String,
+        #[arg(long)]
+        password: Option<String>,
+        #[arg(long, help = "Read the entry text from stdin instead of opening an editor")]
+        stdin: bool,
+        #[command(flatten)]
+        analysis: AnalysisArgs,
+    },
+    #[command(about = "Edit an existing entry in $EDITOR")]
+    Edit {
+        date: String,
+        #[arg(long, help = "Password of a protected entry")]
+        password: Option<String>,
+        #[command(flatten)]
+        analysis: AnalysisArgs,
+    },
+    #[command(about = "Transcribe a 16 kHz mono WAV file with the dictation model")]
+    Transcribe(TranscribeArgs),
+    #[command(about = "Classify the emotion of some text, or of the entry for a date")]
+    Classify {
+        #[arg(help = "Text to classify, a YYYY-MM-DD entry date, or - to read stdin")]
+        input: String,
+        #[arg(long, help = "Password of a protected entry")]
+        password: Option<String>,
+        #[arg(long, help = "Store the emotion in the entry when classifying a date")]
+        save: bool,
+    },
+    #[command(about = "Chat with the MoodJourney assistant")]
+    Chat {
+        #[arg(long, value_name = "ID", help = "Continue this session instead of starting a new one")]
+        session: Option<String>,
+        #[arg(short, long, help = "Send one message, print the reply and exit")]
+        message: Option<String>,
+    },
+    #[command(about = "Generate AI suggestions for an entry")]
+    Suggest {
+        date: String,
+        #[arg(long, help = "Password of a protected entry")]
+        password: Option<String>,
+        #[arg(long, help = "Store the suggestions in the entry, replacing the previous ones")]
+        save: bool,
+    },
+    #[command(about = "Find orphaned images and references to missing files")]
+    Gc {
+        #[arg(long, help = "Delete orphaned files and clear dangling references")]
+        apply: bool,
+    },
+    #[command(about = "Check the database for corruption, orphaned rows and invalid dates")]
+    Check {
+        #[arg(long, help = "Delete chat messages and attachments whose session or entry is gone")]
+        repair: bool,
+    },
+    #[command(about = "Write a backup archive of the database, images and settings")]
+    Backup {
+        archive: PathBuf,
+        #[arg(long, help = "Prompt for a passphrase and encrypt the archive")]
+        encrypt: bool,
+    },
+    #[command(about = "Replace the current data with a backup archive")]
+    Restore { archive: PathBuf },
+    #[command(about = "Export the journal as markdown, html, pdf or ics")]
+    Export(ExportArgs),
+    #[command(about = "Import entries from Day One, Journey or a markdown folder")]
+    Import(ImportArgs),
+    #[command(about = "Export every record as JSON or entries as CSV; use - for stdout")]
+    ExportData {
+        output: String,
+        #[arg(long)]
+        csv: bool,
+    },
+    #[command(about = "Import a JSON or CSV dataset")]
+    ImportData {
+        input: PathBuf,
+        #[arg(long, help = "Update records that already exist instead of skipping them")]
+        overwrite: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum EntriesCommand {
+    #[command(about = "List entries, newest first")]
+    List {
+        #[arg(long, value_name = "YYYY-MM-DD")]
+        from: Option<String>,
+        #[arg(long, value_name = "YYYY-MM-DD")]
+        to: Option<String>,
+        #[arg(long = "emotion", value_name = "EMOTION", help = "Only entries with this emotion; repeat to allow several")]
+        emotions: Vec<String>,
+        #[arg(long = "tag", value_name = "TAG", help = "Only entries with this tag; repeat to require several")]
+        tags: Vec<String>,
+        #[arg(long, conflicts_with = "without_image", help = "Only entries with a cover image or image attachment")]
+        with_image: bool,
+        #[arg(long)]
+        without_image: bool,
+        #[arg(long)]
+        oldest_first: bool,
+        #[arg(long, help = "Show one page of at most this many entries instead of all of them")]
+        limit: Option<usize>,
+        #[arg(long, value_name = "YYYY-MM-DD", help = "Continue after this date, the cursor printed with the previous page")]
+        after: Option<String>,
+    },
+    #[command(about = "Show one entry")]
+    Show {
+        date: String,
+        #[arg(long, help = "Password of a protected entry")]
+        password: Option<String>,
+    },
+    #[command(about = "Add an entry, for today unless --date is given")]
+    Add {
+        #[arg(long, value_name = "YYYY-MM-DD")]
+        date: Option<String>,
+        #[arg(long, default_value = "Journal Entry")]
+        title: String,
+        #[arg(long, help = "Entry text; use - to read it from stdin")]
+        content: Option<String>,
+        #[arg(long)]
+        password: Option<String>,
+        #[arg(long, value_name = "FILE", help = "Image file to attach as the cover image")]
+        image: Option<PathBuf>,
+    },
+    #[command(about = "Change an entry; fields that are not given keep their value")]
+    Update {
+        date: String,
+        #[arg(long)]
+        title: Option<String>,
+        #[arg(long, help = "Entry text; use - to read it from stdin")]
+        content: Option<String>,
+        #[arg(long, conflicts_with = "remove_password", help = "New password for the entry")]
+        password: Option<String>,
+        #[arg(long)]
+        remove_password: bool,
+        #[arg(long, value_name = "FILE", conflicts_with = "remove_image")]
+        image: Option<PathBuf>,
+        #[arg(long)]
+        remove_image: bool,
+        #[arg(long, help = "Password of a protected entry")]
+        current_password: Option<String>,
+    },
+    #[command(about = "Move an entry to the trash")]
+    Delete {
+        date: String,
+        #[arg(long, help = "Delete the entry and its images for good instead")]
+        permanent: bool,
+    },
+    #[command(about = "List the earlier versions of an entry, newest first")]
+    History { date: String },
+    #[command(about = "Show what changed between a revision and a later revision or the current entry")]
+    Diff {
+        date: String,
+        #[arg(help = "Revision id from `entries history`")]
+        from: i64,
+        #[arg(long, help = "Compare with this revision instead of the current entry")]
+        to: Option<i64>,
+        #[arg(long, help = "Password of a protected entry")]
+        password: Option<String>,
+    },
+    #[command(about = "Put the title and text of a revision back; the current version is kept as a revision")]
+    Revert {
+        date: String,
+        #[arg(help = "Revision id from `entries history`")]
+        revision: i64,
+        #[arg(long, help = "Password of a protected entry")]
+        password: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum SessionsCommand {
+    #[command(about = "List chat sessions, most recently used first")]
+    List,
+    #[command(about = "Print the messages of a session")]
+    Show { id: String },
+    #[command(about = "Move a session to the trash")]
+    Delete {
+        id: String,
+        #[arg(long, help = "Delete the session and its messages for good instead")]
+        permanent: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum TrashCommand {
+    #[command(about = "List deleted entries and chat sessions, most recently deleted first")]
+    List,
+    #[command(about = "Put a deleted entry back into the journal")]
+    Restore { date: String },
+    #[command(about = "Put a deleted chat session back")]
+    RestoreSession { id: String },
+    #[command(about = "Delete an entry in the trash for good, with its images")]
+    Purge { date: String },
+    #[command(about = "Delete a chat session in the trash for good")]
+    PurgeSession { id: String },
+    #[command(about = "Delete everything in the trash for good")]
+    Empty,
+}
+
+#[derive(Subcommand)]
+enum DraftsCommand {
+    #[command(about = "List unsaved drafts, most recently edited first")]
+    List,
+    #[command(about = "Print a draft; the slot is 'new' or the date of the entry being edited")]
+    Show { slot: String },
+    #[command(about = "Save a draft into its entry; the 'new' draft becomes today's entry")]
+    Promote {
+        slot: String,
+        #[arg(long, help = "Password of the entry, if it is protected")]
+        password: Option<String>,
+    },
+    #[command(about = "Delete a draft without saving it")]
+    Discard { slot: String },
+}
+
+#[derive(Subcommand)]
+enum TagsCommand {
+    #[command(about = "List tags with the number of entries carrying each")]
+    List,
+    #[command(about = "Add tags to an entry")]
+    Add {
+        date: String,
+        #[arg(required = true)]
+        tags: Vec<String>,
+    },
+    #[command(about = "Remove tags from an entry")]
+    Remove {
+        date: String,
+        #[arg(required = true)]
+        tags: Vec<String>,
+    },
+    #[command(about = "Rename a tag on every entry")]
+    Rename { name: String, new_name: String },
+    #[command(about = "Move the entries of the source tags onto the target tag and delete the sources")]
+    Merge {
+        target: String,
+        #[arg(required = true)]
+        sources: Vec<String>,
+    },
+    #[command(about = "Delete a tag from every entry")]
+    Delete { name: String },
+    #[command(about = "Show how the entries of each tag were classified")]
+    Stats,
+}
+
+#[derive(Subcommand)]
+enum CheckinsCommand {
+    #[command(about = "Record a check-in, now unless --at is given")]
+    Add {
+        #[arg(long, help = "Mood from 1 (worst) to 10 (best)")]
+        mood: u8,
+        #[arg(long = "emotion", value_name = "EMOTION", help = "An emotion you feel; repeat for several")]
+        emotions: Vec<String>,
+        #[arg(long, help = "Energy from 1 to 10")]
+        energy: Option<u8>,
+        #[arg(long, value_name = "HOURS", help = "Hours slept last night")]
+        sleep: Option<f64>,
+        #[arg(long)]
+        note: Option<String>,
+        #[arg(long, value_name = "RFC3339", help = "When the check-in was made, e.g. 2025-06-01T08:30:00+02:00")]
+        at: Option<String>,
+    },
+    #[command(about = "List check-ins, newest first")]
+    List {
+        #[arg(long, value_name = "YYYY-MM-DD")]
+        from: Option<String>,
+        #[arg(long, value_name = "YYYY-MM-DD")]
+        to: Option<String>,
+        #[arg(long)]
+        limit: Option<usize>,
+    },
+    #[command(about = "Delete a check-in")]
+    Delete { id: i64 },
+    #[command(about = "Compare check-ins with the emotions the model found in the entries of the same day")]
+    Compare {
+        #[arg(long, value_name = "YYYY-MM-DD")]
+        from: Option<String>,
+        #[arg(long, value_name = "YYYY-MM-DD")]
+        to: Option<String>,
+        #[arg(long, help = "Load the emotion model to classify entries without a saved emotion and check-in notes")]
+        classify: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum PasswordCommand {
+    #[command(about = "Show whether a PIN is set and the app is locked")]
+    Status,
+    #[command(about = "Set a new PIN, read from stdin")]
+    Set,
+    #[command(about = "Remove the PIN")]
+    Clear,
+    #[command(about = "Lock the app")]
+    Lock,
+    #[command(about = "Unlock the app with the PIN, read from stdin")]
+    Unlock,
+}
+
+#[derive(Subcommand)]
+enum SettingsCommand {
+    #[command(about = "Print all settings")]
+    Show,
+    #[command(about = "Print one setting, e.g. autoBackup.frequency")]
+    Get { key: String },
+    #[command(about = "Change one setting; the value is parsed as JSON when possible")]
+    Set { key: String, value: String },
+    #[command(about = "Restore the default settings")]
+    Reset,
+}
+
+#[derive(Args)]
+struct AnalysisArgs {
+    #[arg(long, help = "Classify the emotion of the text and store it with the entry")]
+    classify: bool,
+    #[arg(long, help = "Generate AI suggestions and store them with the entry")]
+    suggest: bool,
+}
+
+#[derive(Args)]
+struct TranscribeArgs {
+    file: PathBuf,
+    #[arg(long, default_value = dictation::DEFAULT_DICTATION_MODEL, help = "Model file name inside <models-dir>/dictation")]
+    model: String,
+    #[arg(long, help = "Spoken language, or auto to detect it")]
+    language: Option<String>,
+    #[arg(long, help = "Translate the speech to English")]
+    translate: bool,
+    #[arg(long, help = "Use beam search with this many beams")]
+    beam_size: Option<i32>,
+    #[arg(long, help = "Words and names to bias the transcription towards")]
+    prompt: Option<String>,
+    #[arg(long, help = "Transcribe the whole file instead of only the voiced parts")]
+    no_vad: bool,
+}
+
+#[derive(Args)]
+struct ExportArgs {
+    #[arg(help = "markdown, html, pdf or ics")]
+    format: String,
+    output: PathBuf,
+    #[arg(long, value_name = "YYYY-MM-DD")]
+    from: Option<String>,
+    #[arg(long, value_name = "YYYY-MM-DD")]
+    to: Option<String>,
+    #[arg(long = "emotion", value_name = "NAME", help = "Only export entries with this emotion; repeatable")]
+    emotions: Vec<String>,
+    #[arg(long)]
+    include_protected: bool,
+}
+
+#[derive(Args)]
+struct ImportArgs {
+    #[arg(help = "dayone, journey or markdown")]
+    source: String,
+    path: PathBuf,
+    #[arg(long, default_value = "skip", help = "What to do with dates that already have an entry: skip, merge or rename")]
+    policy: String,
+    #[arg(long)]
+    dry_run: bool,
+}
+
+// the library error, reported with the exit code its kind maps to
+struct CliError(AppError);
+
+impl CliError {
+    fn failure(context: &str, error: impl Into<AppError>) -> Self {
+        let error = error.into();
+        Self(error.with_message(format!("{}: {}", context, error)))
+    }
+
+    fn exit_code(&self) -> u8 {
+        match self.0 {
+            AppError::NotFound(_) => EXIT_NOT_FOUND,
+            AppError::Duplicate(_) => EXIT_CONFLICT,
+            AppError::InvalidDate(_) | AppError::InvalidInput(_) => EXIT_USAGE,
+            AppError::Locked(_) | AppError::WrongPassword(_) | AppError::PassphraseRequired(_) => EXIT_DENIED,
+            _ => EXIT_FAILURE,
+        }
+    }
+}
+
+impl From<AppError> for CliError {
+    fn from(error: AppError) -> Self {
+        Self(error)
+    }
+}
+
+type CliResult = Result<(), CliError>;
+
+// one per run: the shared service functions all go through the same AppContext
+struct Context {
+    app: AppContext,
+    // whether password.json had the app locked when the CLI started; the CLI itself runs unlocked
+    pin_locked: bool,
+    models_dir: Option<PathBuf>,
+    json: bool,
+}
+
+impl Context {
+    // same locations the Tauri app resolves with app_data_dir, app_local_data_dir and app_config_dir;
+    // the schema is created before the first query, like the app does on launch
+    fn open(cli: &Cli) -> Result<Self, CliError> {
+        let unresolved = || AppError::Io("Failed to resolve the app data directories.".to_string());
+        let db_path = match &cli.db {
+            Some(path) => path.clone(),
+            None => dirs::data_dir().ok_or_else(unresolved)?.join(APP_IDENTIFIER).join(app_context::DB_FILE_NAME),
+        };
+        let data_dir = match &cli.data_dir {
+            Some(dir) => dir.clone(),
+            None => dirs::data_local_dir().ok_or_else(unresolved)?.join(APP_IDENTIFIER),
+        };
+        let config_dir = match &cli.config_dir {
+            Some(dir) => dir.clone(),
+            None => dirs::config_dir().ok_or_else(unresolved)?.join(APP_IDENTIFIER),
+        };
+        if let Some(parent) = db_path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)
+                .map_err(|e| CliError::failure(&format!("Failed to create {}", parent.display()), e))?;
+        }
+        let db = Database::open(&db_path).map_err(|e| CliError::failure("Failed to open the database", e))?;
+
+        // the PIN locks the app's window; the CLI reads the database directly, so it is unlocked here
+        // without touching password.json
+        let app = AppContext::new(db, data_dir, config_dir);
+        let pin_locked = services::is_locked(&app);
+        app.password.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).password_data.locked = false;
+        Ok(Self { app, pin_locked, models_dir: cli.models_dir.clone(), json: cli.json })
+    }
+
+    // bundled models sit next to the executable, like the app's resource directory in dev builds
+    fn models_dir(&self) -> PathBuf {
+        if let Some(dir) = &self.models_dir {
+            return dir.clone();
+        }
+        let beside_executable = std::env::current_exe()
+            .ok()
+            .and_then(|exe| exe.parent().map(|dir| dir.join("models")))
+            .filter(|dir| dir.is_dir());
+        beside_executable.unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("models"))
+    }
+
+    // a model that fails to load is reported and left out; the service that needs it then
+    // returns MODEL_UNAVAILABLE
+    fn with_emotion_model(self) -> Self {
+        eprintln!("Loading the emotion model...");
+        match EmotionModel::new(self.models_dir().join("emotion")) {
+            Ok(model) => Self { app: self.app.with_emotion_model(model), ..self },
+            Err(e) => {
+                eprintln!("Warning: the emotion model could not be loaded: {}", e);
+                self
+            }
+        }
+    }
+
+    fn with_dictation_model(self, model_name: &str) -> Result<Self, CliError> {
+        let model_path = dictation::find_model_file(&self.models_dir(), model_name)
+            .map_err(|e| CliError::failure("Dictation model unavailable", e))?;
+        eprintln!("Loading {}...", model_path.display());
+        let model = DictationModel::new(&model_path).map_err(|e| CliError::failure("Dictation model unavailable", e))?;
+        Ok(Self { app: self.app.with_dictation_model(model), ..self })
+    }
+
+    // prints the value as JSON in --json mode, the text rendering otherwise
+    fn print<T: Serialize>(&self, value: &T, text: impl FnOnce() -> String) {
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(value).unwrap_or_default());
+        } else {
+            let text = text();
+            if !text.is_empty() {
+                println!("{}", text);
+            }
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct EntryView {
+    date: String,
+    title: Option<String>,
+    protected: bool,
+    // None while a protected entry has not been unlocked
+    content: Option<String>,
+    emotion: Option<String>,
+    suggestions: Vec<String>,
+    image: Option<String>,
+}
+
+fn is_protected(entry: &Entry) -> bool {
+    entry.password.as_deref().is_some_and(|p| !p.is_empty())
+}
+
+fn prompt_line(label: &str) -> String {
+    eprint!("{}", label);
+    let _ = io::stderr().flush();
+    let mut line = String::new();
+    let _ = io::stdin().read_line(&mut line);
+    line.trim().to_string()
+}
+
+// "-" reads the whole of stdin so multi-line text can be piped in
+fn read_content_arg(content: Option<String>) -> Result<Option<String>, CliError> {
+    read_content_from(content, io::stdin())
+}
+
+fn read_content_from(content: Option<String>, mut input: impl Read) -> Result<Option<String>, CliError> {
+    match content.as_deref() {
+        Some("-") => {
+            let mut text = String::new();
+            input.read_to_string(&mut text)
+                .map_err(|e| CliError::failure("Failed to read stdin", e))?;
+            Ok(Some(text.trim_end().to_string()))
+        }
+        _ => Ok(content),
+    }
+}
+
+fn store_image_file(ctx: &Context, image_path: &Path) -> Result<String, CliError> {
+    let bytes = fs::read(image_path)
+        .map_err(|e| CliError::failure(&format!("Failed to read {}", image_path.display()), e))?;
+    let file_name = image_path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+    services::upload_image(&ctx.app, &bytes, &file_name).map_err(|e| CliError::failure("Failed to store image", e))
+}
+
+fn find_entry(ctx: &Context, date: &str) -> Result<Entry, CliError> {
+    services::get_entry(&ctx.app, date)
+        .map_err(|e| CliError::failure("Failed to read entry", e))?
+        .ok_or_else(|| AppError::NotFound(format!("No entry for {}.", date)).into())
+}
+
+// Ok(false) when a protected entry is read without its password
+fn check_entry_password(entry: &Entry, password: Option<&str>) -> Result<bool, CliError> {
+    match password {
+        _ if !is_protected(entry) => Ok(true),
+        Some(attempt) if Some(attempt) == entry.password.as_deref() => Ok(true),
+        Some(_) => Err(AppError::WrongPassword("Incorrect entry password.".to_string()).into()),
+        None => Ok(false),
+    }
+}
+
+fn async_runtime() -> Result<tokio::runtime::Runtime, CliError> {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|e| CliError::failure("Failed to start the async runtime", e))
+}
+
+fn run_entries(ctx: &Context, command: EntriesCommand) -> CliResult {
+    match command {
+        EntriesCommand::List { from, to, emotions, tags, with_image, without_image, oldest_first, limit, after } => {
+            let mut query = EntryQuery {
+                cursor: after,
+                limit: Some(limit.unwrap_or(listing::MAX_PAGE_SIZE)),
+                from_date: from,
+                to_date: to,
+                emotions,
+                has_image: if with_image { Some(true) } else if without_image { Some(false) } else { None },
+                tags,
+                order: if oldest_first { SortOrder::OldestFirst } else { SortOrder::NewestFirst },
+            };
+            let mut page = services::list_entries(&ctx.app, &query).map_err(|e| CliError::failure("Failed to list entries", e))?;
+            // without --limit every page is fetched
+            while limit.is_none() && page.next_cursor.is_some() {
+                query.cursor = page.next_cursor.take();
+                let next = services::list_entries(&ctx.app, &query).map_err(|e| CliError::failure("Failed to list entries", e))?;
+                page.entries.extend(next.entries);
+                page.next_cursor = next.next_cursor;
+            }
+            ctx.print(&page, || {
+                let mut lines: Vec<String> = page.entries.iter().map(|s| {
+                    let mut line = format!("{}  {}", s.date, s.title);
+                    if let Some(emotion) = &s.emotion {
+                        line.push_str(&format!("  ({})", emotion));
+                    }
+                    if !s.tags.is_empty() {
+                        line.push_str(&format!("  #{}", s.tags.join(" #")));
+                    }
+                    if s.protected {
+                        line.push_str("  [protected]");
+                    }
+                    line
+                }).collect();
+                if let Some(cursor) = &page.next_cursor {
+                    lines.push(format!("More entries follow; continue with --after {}", cursor));
+                }
+                lines.join("\n")
+            });
+        }
+        EntriesCommand::Show { date, password } => {
+            let entry = find_entry(ctx, &date)?;
+            let protected = is_protected(&entry);
+            let unlocked = check_entry_password(&entry, password.as_deref())?;
+            let (body, emotion, suggestions) = export::parse_entry_content(entry.content.as_deref().unwrap_or(""));
+            let view = EntryView {
+                date: entry.date.clone(),
+                title: entry.title.clone(),
+                protected,
+                content: unlocked.then_some(body),
+                emotion: if unlocked { emotion } else { None },
+                suggestions: if unlocked { suggestions } else { Vec::new() },
+                image: entry.image.clone(),
+            };
+            ctx.print(&view, || {
+                let mut text = format!("{}  {}", view.date, view.title.as_deref().unwrap_or(""));
+                match &view.content {
+                    Some(content) => text.push_str(&format!("\n\n{}", content)),
+                    None => text.push_str("\n\nThis entry is password protected. Use --password to show it."),
+                }
+                if let Some(emotion) = &view.emotion {
+                    text.push_str(&format!("\n\nEmotion: {}", emotion));
+                }
+                for suggestion in &view.suggestions {
+                    text.push_str(&format!("\nSuggestion: {}", suggestion));
+                }
+                if let Some(image) = &view.image {
+                    text.push_str(&format!("\nImage: {}", ctx.app.local_data_dir.join(image).display()));
+                }
+                text
+            });
+        }
+        EntriesCommand::Add { date, title, content, password, image } => {
+            let date = date.unwrap_or_else(|| chrono::Local::now().format("%Y-%m-%d").to_string());
+            let content = read_content_arg(content)?;
+            let image = image.map(|path| store_image_file(ctx, &path)).transpose()?;
+            let password = password.filter(|p| !p.is_empty());
+            if let Err(e) = services::create_entry_for_date(&ctx.app, &date, &title, content.as_deref(), password.as_deref(), image.as_deref()) {
+                if let Some(stored) = &image {
+                    let _ = attachments::remove_stored_file(&ctx.app.local_data_dir, stored);
                 }
-                else {
-                    config_path.push("password.json");
-                    config_path
+                return Err(CliError::failure("Failed to add entry", e));
+            }
+            ctx.print(&json!({ "date": date }), || format!("Entry added for {}.", date));
+        }
+        EntriesCommand::Update { date, title, content, password, remove_password, image, remove_image, current_password } => {
+            let current = find_entry(ctx, &date)?;
+            if !check_entry_password(&current, current_password.as_deref())? {
+                return Err(AppError::Locked("This entry is password protected. Use --current-password to change it.".to_string()).into());
+            }
+            let content = read_content_arg(content)?.or(current.content.clone());
+            let title = title.or(current.title.clone());
+            let password = if remove_password { None } else { password.or(current.password.clone()) };
+            let stored_image = image.map(|path| store_image_file(ctx, &path)).transpose()?;
+            let new_image = match (&stored_image, remove_image) {
+                (Some(stored), _) => Some(stored.clone()),
+                (None, true) => None,
+                (None, false) => current.image.clone(),
+            };
+
+            // like a save in the app: the replaced image is deleted and old revisions are pruned
+            let title = title.as_deref().unwrap_or(DEFAULT_ENTRY_TITLE);
+            if let Err(e) = services::update_entry(&ctx.app, &date, title, content.as_deref(), password.as_deref(), new_image.as_deref()) {
+                if let Some(stored) = &stored_image {
+                    let _ = attachments::remove_stored_file(&ctx.app.local_data_dir, stored);
                 }
+                return Err(CliError::failure("Failed to update entry", e));
             }
-            else {
-                config_path.push("password.json");
-                config_path
+            ctx.print(&json!({ "date": date }), || format!("Entry for {} updated.", date));
+        }
+        EntriesCommand::Delete { date, permanent } => {
+            find_entry(ctx, &date)?;
+            services::delete_entry(&ctx.app, &date).map_err(|e| CliError::failure("Failed to delete entry", e))?;
+            if permanent {
+                let removed_files = services::purge_entry(&ctx.app, &date)
+                    .map_err(|e| CliError::failure("Failed to delete entry", e))?;
+                ctx.print(&json!({ "date": date, "purged": true, "removedFiles": removed_files.len() }), || format!("Entry for {} deleted.", date));
+            } else {
+                ctx.print(&json!({ "date": date, "purged": false }), || format!("Entry for {} moved to the trash.", date));
             }
         }
-        None => {
-            eprintln!("Failed to create directory. Password functionality will fail.");
-            PathBuf::new()
+        EntriesCommand::History { date } => {
+            find_entry(ctx, &date)?;
+            let revisions = services::list_entry_revisions(&ctx.app, &date).map_err(|e| CliError::failure("Failed to read revisions", e))?;
+            // titles only; the text of a protected entry is shown by `entries diff --password`
+            let listed: Vec<Value> = revisions.iter()
+                .map(|r| json!({ "id": r.id, "createdAt": r.created_at, "title": r.title }))
+                .collect();
+            ctx.print(&listed, || {
+                if revisions.is_empty() {
+                    return format!("No earlier versions of {}.", date);
+                }
+                revisions.iter()
+                    .map(|r| format!("{}  {}  {}", r.id, r.created_at, r.title.as_deref().unwrap_or("")))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            });
         }
+        EntriesCommand::Diff { date, from, to, password } => {
+            let entry = find_entry(ctx, &date)?;
+            if !check_entry_password(&entry, password.as_deref())? {
+                return Err(AppError::Locked("This entry is password protected. Use --password to show it.".to_string()).into());
+            }
+            let diff = services::diff_entry_revisions(&ctx.app, &date, from, to).map_err(|e| CliError::failure("Failed to compare revisions", e))?;
+            ctx.print(&diff, || {
+                let mut text = String::new();
+                if diff.old_title != diff.new_title {
+                    text.push_str(&format!(
+                        "Title: {} -> {}\n",
+                        diff.old_title.as_deref().unwrap_or(""),
+                        diff.new_title.as_deref().unwrap_or("")
+                    ));
+                }
+                if diff.unified.is_empty() {
+                    text.push_str("The text is the same.");
+                } else {
+                    text.push_str(diff.unified.trim_end());
+                }
+                text
+            });
+        }
+        EntriesCommand::Revert { date, revision, password } => {
+            let entry = find_entry(ctx, &date)?;
+            if !check_entry_password(&entry, password.as_deref())? {
+                return Err(AppError::Locked("This entry is password protected. Use --password to change it.".to_string()).into());
+            }
+            services::restore_entry_revision(&ctx.app, &date, revision)
+                .map_err(|e| CliError::failure("Failed to restore revision", e))?;
+            ctx.print(&json!({ "date": date, "revision": revision }), || format!("Entry for {} restored to revision {}.", date, revision));
+        }
+    }
+    Ok(())
+}
+
+fn run_sessions(ctx: &Context, command: SessionsCommand) -> CliResult {
+    let find_session = |id: &str| -> Result<ChatSession, CliError> {
+        services::list_chat_sessions(&ctx.app)
+            .map_err(|e| CliError::failure("Failed to read chat sessions", e))?
+            .into_iter()
+            .find(|s| s.id == id)
+            .ok_or_else(|| AppError::NotFound(format!("No chat session {}.", id)).into())
     };
+    match command {
+        SessionsCommand::List => {
+            let sessions = services::list_chat_sessions(&ctx.app)
+                .map_err(|e| CliError::failure("Failed to read chat sessions", e))?;
+            ctx.print(&sessions, || {
+                sessions.iter()
+                    .map(|s| format!("{}  {}  {}", s.id, s.last_modified_at, s.title.as_deref().unwrap_or("(untitled)")))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            });
+        }
+        SessionsCommand::Show { id } => {
+            let session = find_session(&id)?;
+            let messages: Vec<ChatMessage> = services::list_chat_messages(&ctx.app, &id)
+                .map_err(|e| CliError::failure("Failed to read messages", e))?;
+            ctx.print(&json!({ "session": session, "messages": messages }), || {
+                let mut text = session.title.clone().unwrap_or_else(|| "(untitled)".to_string());
+                for message in &messages {
+                    text.push_str(&format!("\n\n[{}] {}:\n{}", message.timestamp, message.sender, message.content));
+                }
+                text
+            });
+        }
+        SessionsCommand::Delete { id, permanent } => {
+            find_session(&id)?;
+            services::delete_chat_session(&ctx.app, &id).map_err(|e| CliError::failure("Failed to delete chat session", e))?;
+            if permanent {
+                services::purge_chat_session(&ctx.app, &id).map_err(|e| CliError::failure("Failed to delete chat session", e))?;
+                ctx.print(&json!({ "id": id, "purged": true }), || format!("Chat session {} deleted.", id));
+            } else {
+                ctx.print(&json!({ "id": id, "purged": false }), || format!("Chat session {} moved to the trash.", id));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn run_trash(ctx: &Context, command: TrashCommand) -> CliResult {
+    match command {
+        TrashCommand::List => {
+            let contents = services::list_trash(&ctx.app).map_err(|e| CliError::failure("Failed to read the trash", e))?;
+            ctx.print(&contents, || {
+                if contents.entries.is_empty() && contents.chat_sessions.is_empty() {
+                    return "The trash is empty.".to_string();
+                }
+                let entries = contents.entries.iter()
+                    .map(|e| format!("entry    {}  deleted {}  {}", e.date, e.deleted_at, e.title.as_deref().unwrap_or("")));
+                let sessions = contents.chat_sessions.iter()
+                    .map(|s| format!("session  {}  deleted {}  {}", s.id, s.deleted_at, s.title.as_deref().unwrap_or("(untitled)")));
+                entries.chain(sessions).collect::<Vec<_>>().join("\n")
+            });
+        }
+        TrashCommand::Restore { date } => {
+            services::restore_entry(&ctx.app, &date).map_err(|e| CliError::failure("Failed to restore entry", e))?;
+            ctx.print(&json!({ "date": date }), || format!("Entry for {} restored.", date));
+        }
+        TrashCommand::RestoreSession { id } => {
+            services::restore_chat_session(&ctx.app, &id).map_err(|e| CliError::failure("Failed to restore chat session", e))?;
+            ctx.print(&json!({ "id": id }), || format!("Chat session {} restored.", id));
+        }
+        TrashCommand::Purge { date } => {
+            let removed_files = services::purge_entry(&ctx.app, &date)
+                .map_err(|e| CliError::failure("Failed to purge entry", e))?;
+            ctx.print(&json!({ "date": date, "removedFiles": removed_files.len() }), || format!("Entry for {} deleted for good.", date));
+        }
+        TrashCommand::PurgeSession { id } => {
+            services::purge_chat_session(&ctx.app, &id).map_err(|e| CliError::failure("Failed to purge chat session", e))?;
+            ctx.print(&json!({ "id": id }), || format!("Chat session {} deleted for good.", id));
+        }
+        TrashCommand::Empty => {
+            let report = services::empty_trash(&ctx.app).map_err(|e| CliError::failure("Failed to empty the trash", e))?;
+            ctx.print(&report, || format!(
+                "Deleted {} entry(s), {} chat session(s) and {} file(s) for good.",
+                report.purged_entries.len(), report.purged_chat_sessions.len(), report.removed_files.len()
+            ));
+        }
+    }
+    Ok(())
+}
 
-    if password_file_path.as_os_str().is_empty() {
-        eprintln!("Failed to proceed with password operations due to config path issues.");
+fn find_draft(ctx: &Context, slot: &str) -> Result<Draft, CliError> {
+    services::load_draft(&ctx.app, slot)
+        .map_err(|e| CliError::failure("Failed to read draft", e))?
+        .ok_or_else(|| AppError::NotFound(format!("There is no draft for {}.", slot)).into())
+}
+
+fn run_drafts(ctx: &Context, command: DraftsCommand) -> CliResult {
+    match command {
+        DraftsCommand::List => {
+            let all_drafts = services::list_drafts(&ctx.app).map_err(|e| CliError::failure("Failed to list drafts", e))?;
+            ctx.print(&all_drafts, || {
+                if all_drafts.is_empty() {
+                    return "No unsaved drafts.".to_string();
+                }
+                all_drafts.iter()
+                    .map(|d| format!("{:<10}  edited {}  {}", d.slot, d.updated_at, export::excerpt(&d.content, 60)))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            });
+        }
+        DraftsCommand::Show { slot } => {
+            let draft = find_draft(ctx, &slot)?;
+            ctx.print(&draft, || match &draft.title {
+                Some(title) => format!("{}\n\n{}", title, draft.content),
+                None => draft.content.clone(),
+            });
+        }
+        DraftsCommand::Promote { slot, password } => {
+            find_draft(ctx, &slot)?;
+            let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+            let date = if slot == drafts::NEW_ENTRY_SLOT { today.clone() } else { slot.clone() };
+            if let Some(entry) = services::get_entry(&ctx.app, &date).map_err(|e| CliError::failure("Failed to read entry", e))? {
+                if !check_entry_password(&entry, password.as_deref())? {
+                    return Err(AppError::Locked("This entry is password protected. Use --password to change it.".to_string()).into());
+                }
+            }
+            let date = services::promote_draft(&ctx.app, &slot).map_err(|e| CliError::failure("Failed to save draft", e))?;
+            ctx.print(&json!({ "slot": slot, "date": date }), || format!("Draft saved to the entry for {}.", date));
+        }
+        DraftsCommand::Discard { slot } => {
+            find_draft(ctx, &slot)?;
+            services::discard_draft(&ctx.app, &slot).map_err(|e| CliError::failure("Failed to discard draft", e))?;
+            ctx.print(&json!({ "slot": slot }), || format!("Draft for {} discarded.", slot));
+        }
     }
+    Ok(())
+}
 
-    let password_state_instance = PasswordState::load_from_path(password_file_path.clone());
-    let password_state_mutex = Mutex::new(password_state_instance);
+fn run_tags(ctx: &Context, command: TagsCommand) -> CliResult {
+    match command {
+        TagsCommand::List => {
+            let all_tags = services::list_tags(&ctx.app).map_err(|e| CliError::failure("Failed to list tags", e))?;
+            ctx.print(&all_tags, || {
+                if all_tags.is_empty() {
+                    return "No tags yet.".to_string();
+                }
+                all_tags.iter().map(|t| format!("#{}  ({})", t.name, t.entry_count)).collect::<Vec<_>>().join("\n")
+            });
+        }
+        TagsCommand::Add { date, tags: names } => {
+            let mut entry_tags = Vec::new();
+            for name in &names {
+                entry_tags = services::add_entry_tag(&ctx.app, &date, name).map_err(|e| CliError::failure("Failed to add tag", e))?;
+            }
+            ctx.print(&json!({ "date": date, "tags": entry_tags }), || format!("Entry for {} is tagged: {}", date, entry_tags.join(", ")));
+        }
+        TagsCommand::Remove { date, tags: names } => {
+            let mut entry_tags = Vec::new();
+            for name in &names {
+                entry_tags = services::remove_entry_tag(&ctx.app, &date, name).map_err(|e| CliError::failure("Failed to remove tag", e))?;
+            }
+            ctx.print(&json!({ "date": date, "tags": entry_tags }), || {
+                if entry_tags.is_empty() {
+                    format!("Entry for {} has no tags left.", date)
+                } else {
+                    format!("Entry for {} is tagged: {}", date, entry_tags.join(", "))
+                }
+            });
+        }
+        TagsCommand::Rename { name, new_name } => {
+            let new_name = services::rename_tag(&ctx.app, &name, &new_name).map_err(|e| CliError::failure("Failed to rename tag", e))?;
+            ctx.print(&json!({ "name": name, "newName": new_name }), || format!("Tag '{}' renamed to '{}'.", name, new_name));
+        }
+        TagsCommand::Merge { target, sources } => {
+            let report = services::merge_tags(&ctx.app, &sources, &target).map_err(|e| CliError::failure("Failed to merge tags", e))?;
+            ctx.print(&report, || format!(
+                "Merged {} into '{}'; {} entry(s) gained the tag.",
+                report.merged_tags.join(", "), report.target, report.entries_retagged
+            ));
+        }
+        TagsCommand::Delete { name } => {
+            services::delete_tag(&ctx.app, &name).map_err(|e| CliError::failure("Failed to delete tag", e))?;
+            ctx.print(&json!({ "name": name }), || format!("Tag '{}' deleted.", name));
+        }
+        TagsCommand::Stats => {
+            let stats = services::tag_emotion_stats(&ctx.app).map_err(|e| CliError::failure("Failed to compute tag statistics", e))?;
+            ctx.print(&stats, || {
+                if stats.is_empty() {
+                    return "No tagged entries yet.".to_string();
+                }
+                stats.iter().map(|s| {
+                    let emotions = s.emotions.iter().map(|e| format!("{} {}", e.emotion, e.count)).collect::<Vec<_>>().join(", ");
+                    format!("#{}  {} entry(s)  {}", s.tag, s.entry_count, emotions)
+                }).collect::<Vec<_>>().join("\n")
+            });
+        }
+    }
+    Ok(())
+}
 
-    if args.len() < 2 {
-        eprintln!("Usage: cli <command> [args]");
-        return;
+fn format_checkin(checkin: &Checkin) -> String {
+    let mut line = format!("[{}] {}  mood {}/10", checkin.id, checkin.recorded_at, checkin.mood);
+    if let Some(energy) = checkin.energy {
+        line.push_str(&format!("  energy {}/10", energy));
+    }
+    if let Some(hours) = checkin.sleep_hours {
+        line.push_str(&format!("  slept {}h", hours));
+    }
+    if !checkin.emotions.is_empty() {
+        line.push_str(&format!("  {}", checkin.emotions.join(", ")));
     }
+    if let Some(note) = &checkin.note {
+        line.push_str(&format!("\n    {}", export::excerpt(note, 100)));
+    }
+    line
+}
 
-    let command = args[1].as_str();
+fn format_share(share: Option<f64>) -> String {
+    share.map_or_else(|| "n/a".to_string(), |share| format!("{:.0}%", share * 100.0))
+}
 
+fn run_checkins(ctx: &Context, command: CheckinsCommand) -> CliResult {
     match command {
-        "init" => {
-            init_db().expect("Failed to init DB");
-            println!("Database initialized.");
-        }
-        "add" => {
-            let date = args.get(2).expect("Need date");
-            let title = args.get(3).expect("Need title");
-            let content = args.get(4);
-            let password = args.get(5);
-            let entry = Entry {
-                date: date.to_string(),
-                title: Some(title.to_string()),
-                content: content.map(|s| s.to_string()),
-                password: password.map(|s| s.to_string()),
-                image: None,
-            };
-            match add_entry(entry) {
-                Ok(_) => println!("Entry added for date: {}", date),
-                Err(e) => eprintln!("Failed to add entry: {}", e),
-            }
-        }
-        "create" => {
-            let title = args.get(2).expect("Need title");
-            let content = args.get(3).map(|s| s.as_str());
-            let password = args.get(4).map(|s| s.as_str());
-
-            create_entry_with_now(title, content, password, None).expect("Failed to create");
-            println!("Entry added.");
-        }
-        "get" => {
-            let date = args.get(2).expect("Need date");
-            match get_entry_by_date(date).expect("Failed to get") {
-                Some(entry) => println!("{:#?}", entry),
-                None => println!("No entry found."),
-            }
-        }
-        "update" => {
-            let date = args.get(2).expect("Need date");
-            let title = args.get(3).expect("Need title");
-            let content = args.get(4).map(|s| s.as_str());
-            let password = args.get(5).map(|s| s.as_str());
-
-            update_entry_by_date(date, Some(title), content, password, None).expect("Failed to update");
-            println!("Entry updated.");
-        }
-        "delete" => {
-            let date = args.get(2).expect("Need date");
-            delete_entry_by_date(date).expect("Failed to delete");
-            println!("Entry deleted.");
-        }
-        "list" => {
-            let entries = get_entries().expect("Failed to list");
-            for entry in entries {
-                println!("{:#?}", entry);
-            }
-        }
-        "newpass" => {
-            print!("New Password: ");
-            io::stdout().flush().unwrap();
-            let mut pwd = String::new();
-            io::stdin().read_line(&mut pwd).unwrap();
-            password::set_password(&password_state_mutex, pwd.trim());
-            if password::is_locked(&password_state_mutex) {
-                println!("Password set and locked.");
-            } else {
-                println!("Password cleared.");
+        CheckinsCommand::Add { mood, emotions, energy, sleep, note, at } => {
+            let input = CheckinInput { recorded_at: at, mood, emotions, energy, sleep_hours: sleep, note };
+            let checkin = services::record_checkin(&ctx.app, &input)
+                .map_err(|e| CliError::failure("Failed to record check-in", e))?;
+            ctx.print(&checkin, || format!("Check-in recorded:\n{}", format_checkin(&checkin)));
+        }
+        CheckinsCommand::List { from, to, limit } => {
+            let query = CheckinQuery { from_date: from, to_date: to, limit };
+            let all_checkins = services::list_checkins(&ctx.app, &query).map_err(|e| CliError::failure("Failed to list check-ins", e))?;
+            ctx.print(&all_checkins, || {
+                if all_checkins.is_empty() {
+                    return "No check-ins yet.".to_string();
+                }
+                all_checkins.iter().map(format_checkin).collect::<Vec<_>>().join("\n")
+            });
+        }
+        CheckinsCommand::Delete { id } => {
+            services::delete_checkin(&ctx.app, id).map_err(|e| CliError::failure("Failed to delete check-in", e))?;
+            ctx.print(&json!({ "id": id }), || format!("Check-in {} deleted.", id));
+        }
+        CheckinsCommand::Compare { from, to, classify } => {
+            let query = CheckinQuery { from_date: from, to_date: to, limit: None };
+            // run() loads the emotion model for --classify
+            if classify {
+                ctx.app.emotion_model().map_err(|e| CliError::failure("Emotion model unavailable", e))?;
+            }
+            let report = services::compare_checkins(&ctx.app, &query)
+                .map_err(|e| CliError::failure("Failed to compare check-ins", e))?;
+            for warning in &report.warnings {
+                eprintln!("Warning: {}", warning);
             }
+            ctx.print(&report, || {
+                let mut lines = vec![
+                    format!("{} check-in(s), {} with a prediction", report.checkins, report.with_prediction),
+                    format!("Chosen emotions include the prediction: {}", format_share(report.emotion_agreement)),
+                    format!("Mood leans the same way as the prediction: {}", format_share(report.valence_agreement)),
+                ];
+                for mood in &report.mood_by_predicted_emotion {
+                    lines.push(format!("  {}: average mood {:.1} over {} check-in(s)", mood.emotion, mood.average_mood, mood.checkins));
+                }
+                for comparison in &report.comparisons {
+                    let predicted = comparison.predicted_emotion.as_deref().unwrap_or("-");
+                    let chosen = if comparison.emotions.is_empty() { "-".to_string() } else { comparison.emotions.join(", ") };
+                    lines.push(format!("{}  mood {}/10  chose {}  predicted {}", comparison.date, comparison.mood, chosen, predicted));
+                }
+                lines.join("\n")
+            });
         }
-        "auth" => {
-            print!("Enter Password: ");
-            io::stdout().flush().unwrap();
-            let mut pwd = String::new();
-            io::stdin().read_line(&mut pwd).unwrap();
-            if password::check_password(&password_state_mutex, pwd.trim()) {
-                println!("Unlocked.");
-            } else {
-                println!("Incorrect password.");
+    }
+    Ok(())
+}
+
+fn run_password(ctx: &Context, command: PasswordCommand) -> CliResult {
+    match command {
+        PasswordCommand::Status => {}
+        PasswordCommand::Set => {
+            let new_password = prompt_line("New PIN: ");
+            if new_password.is_empty() {
+                return Err(AppError::InvalidInput("The PIN cannot be empty; use `password clear` to remove it.".to_string()).into());
+            }
+            services::set_new_password(&ctx.app, &new_password);
+        }
+        PasswordCommand::Clear => services::delete_pin(&ctx.app),
+        PasswordCommand::Lock => {
+            if !services::is_pin_set(&ctx.app) {
+                return Err(AppError::InvalidInput("No PIN is set, so the app cannot be locked.".to_string()).into());
+            }
+            services::set_locked(&ctx.app, true);
+        }
+        PasswordCommand::Unlock => {
+            if !services::check_password_attempt(&ctx.app, &prompt_line("PIN: ")) {
+                return Err(AppError::WrongPassword("Incorrect PIN.".to_string()).into());
             }
         }
-        "lock" => {
-            password::set_locked(&password_state_mutex, true);
-            println!("Locked.");
+    }
+    let pin_set = services::is_pin_set(&ctx.app);
+    // the in-memory state was unlocked for the CLI, so status reports what password.json held
+    let locked = match command {
+        PasswordCommand::Status => ctx.pin_locked,
+        _ => services::is_locked(&ctx.app),
+    };
+    ctx.print(&json!({ "pinSet": pin_set, "locked": locked }), || {
+        format!("PIN set: {}\nLocked: {}", if pin_set { "yes" } else { "no" }, if locked { "yes" } else { "no" })
+    });
+    Ok(())
+}
+
+// follows a dotted camelCase path such as autoBackup.keepLast through the settings JSON
+fn settings_value_mut<'a>(root: &'a mut Value, key: &str) -> Option<&'a mut Value> {
+    key.split('.').try_fold(root, |value, part| value.get_mut(part))
+}
+
+fn run_settings(ctx: &Context, command: SettingsCommand) -> CliResult {
+    let current = services::get_settings(&ctx.app);
+    let mut current_json = serde_json::to_value(&current)
+        .map_err(|e| CliError::failure("Failed to read settings", AppError::Internal(e.to_string())))?;
+    match command {
+        SettingsCommand::Show => {
+            ctx.print(&current_json, || serde_json::to_string_pretty(&current_json).unwrap_or_default());
+        }
+        SettingsCommand::Get { key } => {
+            let value = settings_value_mut(&mut current_json, &key)
+                .ok_or_else(|| AppError::NotFound(format!("Unknown setting {}.", key)))?
+                .clone();
+            ctx.print(&value, || match &value {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            });
+        }
+        SettingsCommand::Set { key, value } => {
+            let slot = settings_value_mut(&mut current_json, &key)
+                .ok_or_else(|| AppError::NotFound(format!("Unknown setting {}.", key)))?;
+            *slot = serde_json::from_str(&value).unwrap_or(Value::String(value));
+            let new_settings: AppSettings = serde_json::from_value(current_json)
+                .map_err(|e| AppError::InvalidInput(format!("Invalid value for {}: {}", key, e)))?;
+            let saved = services::update_settings(&ctx.app, new_settings)
+                .map_err(|e| CliError::failure("Failed to save settings", e))?;
+            ctx.print(&saved, || format!("{} updated.", key));
+        }
+        SettingsCommand::Reset => {
+            let saved = services::update_settings(&ctx.app, AppSettings::default())
+                .map_err(|e| CliError::failure("Failed to save settings", e))?;
+            ctx.print(&saved, || "Settings restored to defaults.".to_string());
+        }
+    }
+    Ok(())
+}
+
+fn print_chat_reply(ctx: &Context, reply: &ChatCompletionResponse) {
+    ctx.print(reply, || format!("\n{}\n", reply.assistant_response.trim()));
+}
+
+// a prefix of its own, so markdown headings and lines starting with a #hashtag are kept
+const EDITOR_COMMENT_PREFIX: &str = "#~";
+const EDITOR_HELP: &str = "\
+#~ The first line is the title, the rest is the entry.
+#~ Lines starting with #~ are ignored. Save an empty entry to cancel.
+";
+
+// splits the editor buffer back into title and text
+fn parse_editor_buffer(buffer: &str) -> (Option<String>, String) {
+    let mut lines = buffer.lines().filter(|line| !line.starts_with(EDITOR_COMMENT_PREFIX));
+    let title = lines.by_ref().find(|line| !line.trim().is_empty()).map(|line| line.trim().to_string());
+    let body = lines.collect::<Vec<_>>().join("\n").trim().to_string();
+    (title, body)
+}
+
+fn edit_in_editor(initial_text: &str) -> Result<String, CliError> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| if cfg!(windows) { "notepad".to_string() } else { "vi".to_string() });
+    let mut editor_parts = editor.split_whitespace();
+    let program = editor_parts.next().unwrap_or("vi");
+
+    let buffer_path = std::env::temp_dir().join(format!("moodjourney-entry-{}.txt", uuid::Uuid::new_v4()));
+    fs::write(&buffer_path, initial_text).map_err(|e| CliError::failure("Failed to create the editor file", e))?;
+    let status = process::Command::new(program).args(editor_parts).arg(&buffer_path).status();
+    let edited = fs::read_to_string(&buffer_path);
+    let _ = fs::remove_file(&buffer_path);
+
+    match status {
+        Ok(status) if status.success() => edited.map_err(|e| CliError::failure("Failed to read the editor file", e)),
+        Ok(status) => Err(AppError::Internal(format!("{} exited with {}; nothing was saved.", program, status)).into()),
+        Err(e) => Err(CliError::failure(&format!("Failed to start {} (set $EDITOR)", program), e)),
+    }
+}
+
+// runs the optional emotion classification and suggestion generation for new text;
+// failures are reported and leave that part out, like the app's save button. run() loads the
+// emotion model for --classify
+fn analyze_entry_text(ctx: &Context, title: &str, body: &str, analysis: &AnalysisArgs) -> (Option<String>, Option<String>) {
+    let emotion = if analysis.classify {
+        eprintln!("Classifying emotion...");
+        match services::classify_emotion(&ctx.app, body) {
+            Ok(emotion) => Some(emotion),
+            Err(e) => {
+                eprintln!("Warning: emotion classification failed: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+    let suggestion = if analysis.suggest {
+        eprintln!("Generating suggestions...");
+        let generated = async_runtime()
+            .map_err(|e| e.0)
+            .and_then(|runtime| runtime.block_on(services::generate_suggestion(Some(title), Some(body))));
+        match generated {
+            Ok(text) => Some(text),
+            Err(e) => {
+                eprintln!("Warning: suggestion generation failed: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+    (emotion, suggestion)
+}
+
+// the raw suggestion text as generated, without the marker
+fn stored_suggestion(full_content: &str) -> Option<String> {
+    full_content.find(export::SUGGESTION_MARKER)
+        .map(|index| full_content[index + export::SUGGESTION_MARKER.len()..].trim().to_string())
+}
+
+fn compose_entry_content(body: &str, emotion: Option<&str>, suggestion_text: Option<&str>) -> String {
+    let mut content = body.trim().to_string();
+    if let Some(emotion) = emotion {
+        content.push_str(&format!("{} {}", EMOTION_MARKER, emotion));
+    }
+    match suggestion_text {
+        Some(text) => suggestion::replace_suggestion(&content, text),
+        None => content,
+    }
+}
+
+fn run_write(ctx: &Context, date: Option<String>, title: String, password: Option<String>, from_stdin: bool, analysis: AnalysisArgs) -> CliResult {
+    let date = date.unwrap_or_else(|| chrono::Local::now().format("%Y-%m-%d").to_string());
+    if services::get_entry(&ctx.app, &date).map_err(|e| CliError::failure("Failed to read entry", e))?.is_some() {
+        return Err(AppError::Duplicate(format!("An entry for {} already exists; use `cli edit {}`.", date, date)).into());
+    }
+
+    let (title, body) = if from_stdin || !io::stdin().is_terminal() {
+        let body = read_content_arg(Some("-".to_string()))?.unwrap_or_default();
+        (title, body.trim().to_string())
+    } else {
+        let edited = edit_in_editor(&format!("{}\n\n\n{}", title, EDITOR_HELP))?;
+        let (edited_title, body) = parse_editor_buffer(&edited);
+        (edited_title.unwrap_or(title), body)
+    };
+    if body.is_empty() {
+        return Err(AppError::InvalidInput("The entry is empty; nothing was saved.".to_string()).into());
+    }
+
+    let (emotion, suggestion_text) = analyze_entry_text(ctx, &title, &body, &analysis);
+    let content = compose_entry_content(&body, emotion.as_deref(), suggestion_text.as_deref());
+    let password = password.filter(|p| !p.is_empty());
+    services::create_entry_for_date(&ctx.app, &date, &title, Some(&content), password.as_deref(), None)
+        .map_err(|e| CliError::failure("Failed to save entry", e))?;
+    ctx.print(&json!({ "date": date, "emotion": emotion, "suggestion": suggestion_text }), || {
+        match &emotion {
+            Some(emotion) => format!("Entry saved for {}. Detected emotion: {}.", date, emotion),
+            None => format!("Entry saved for {}.", date),
+        }
+    });
+    Ok(())
+}
+
+fn run_edit(ctx: &Context, date: &str, password: Option<&str>, analysis: AnalysisArgs) -> CliResult {
+    let entry = find_entry(ctx, date)?;
+    if !check_entry_password(&entry, password)? {
+        return Err(AppError::Locked("This entry is password protected. Use --password to unlock it.".to_string()).into());
+    }
+    let full_content = entry.content.clone().unwrap_or_default();
+    let (old_body, old_emotion, _) = export::parse_entry_content(&full_content);
+    let old_title = entry.title.clone().unwrap_or_else(|| DEFAULT_ENTRY_TITLE.to_string());
+
+    let edited = edit_in_editor(&format!("{}\n\n{}\n\n{}", old_title, old_body, EDITOR_HELP))?;
+    let (new_title, new_body) = parse_editor_buffer(&edited);
+    let new_title = new_title.unwrap_or(old_title.clone());
+    if new_body.is_empty() {
+        return Err(AppError::InvalidInput("The entry is empty; nothing was saved. Use `cli entries delete` to remove it.".to_string()).into());
+    }
+    if new_title == old_title && new_body == old_body && !analysis.classify && !analysis.suggest {
+        ctx.print(&json!({ "date": date, "changed": false }), || "No changes.".to_string());
+        return Ok(());
+    }
+
+    // the stored emotion and suggestions are kept unless they are regenerated
+    let (emotion, suggestion_text) = analyze_entry_text(ctx, &new_title, &new_body, &analysis);
+    let emotion = emotion.or(old_emotion);
+    let old_suggestion = stored_suggestion(&full_content);
+    let new_content = compose_entry_content(&new_body, emotion.as_deref(), suggestion_text.as_deref().or(old_suggestion.as_deref()));
+
+    services::update_entry(&ctx.app, date, &new_title, Some(&new_content), entry.password.as_deref(), entry.image.as_deref())
+        .map_err(|e| CliError::failure("Failed to save entry", e))?;
+    ctx.print(&json!({ "date": date, "changed": true, "emotion": emotion }), || format!("Entry for {} updated.", date));
+    Ok(())
+}
+
+fn run_transcribe(ctx: &Context, args: TranscribeArgs) -> CliResult {
+    let defaults = DictationOptions::default();
+    let language = match args.language.as_deref() {
+        Some("auto") => None,
+        Some(language) => Some(language.to_string()),
+        None => defaults.language.clone(),
+    };
+    let mut options = DictationOptions {
+        beam_size: args.beam_size,
+        initial_prompt: args.prompt,
+        translate: args.translate,
+        language,
+        ..defaults
+    };
+    options.vad.enabled = !args.no_vad;
+
+    // run() loads the model named by --model
+    let file = args.file.to_string_lossy();
+    let report = services::transcribe_with_report(&ctx.app, &file, &options)
+        .map_err(|e| CliError::failure("Transcription failed", e))?;
+    ctx.print(&report, || report.text.clone());
+    Ok(())
+}
+
+fn run_classify(ctx: &Context, input: &str, password: Option<&str>, save: bool) -> CliResult {
+    let is_date = chrono::NaiveDate::parse_from_str(input, "%Y-%m-%d").is_ok();
+    let entry = if is_date { Some(find_entry(ctx, input)?) } else { None };
+    if save && entry.is_none() {
+        return Err(AppError::InvalidInput("--save needs an entry date to store the emotion in.".to_string()).into());
+    }
+
+    let text = match &entry {
+        Some(entry) => {
+            if !check_entry_password(entry, password)? {
+                return Err(AppError::Locked("This entry is password protected. Use --password to unlock it.".to_string()).into());
+            }
+            export::parse_entry_content(entry.content.as_deref().unwrap_or("")).0
         }
-        "lockstatus" => {
-            let locked = password::is_locked(&password_state_mutex);
-            println!("Locked: {}", locked);
+        None => read_content_arg(Some(input.to_string()))?.unwrap_or_default(),
+    };
+    if text.trim().is_empty() {
+        return Err(AppError::InvalidInput("There is no text to classify.".to_string()).into());
+    }
+
+    // run() loads the emotion model
+    let emotion = services::classify_emotion(&ctx.app, &text).map_err(|e| CliError::failure("Classification failed", e))?;
+
+    if let (Some(entry), true) = (&entry, save) {
+        let full_content = entry.content.clone().unwrap_or_default();
+        let old_suggestion = stored_suggestion(&full_content);
+        let new_content = compose_entry_content(&text, Some(&emotion), old_suggestion.as_deref());
+        let title = entry.title.as_deref().unwrap_or(DEFAULT_ENTRY_TITLE);
+        services::update_entry(&ctx.app, &entry.date, title, Some(&new_content), entry.password.as_deref(), entry.image.as_deref())
+            .map_err(|e| CliError::failure("Failed to save the emotion", e))?;
+    }
+    ctx.print(&json!({ "emotion": emotion, "date": entry.as_ref().map(|e| &e.date), "saved": save }), || emotion.clone());
+    Ok(())
+}
+
+fn run_chat(ctx: &Context, session: Option<String>, message: Option<String>) -> CliResult {
+    let mut session_id = session;
+    if let Some(id) = &session_id {
+        if !services::list_chat_sessions(&ctx.app)
+            .map_err(|e| CliError::failure("Failed to read chat sessions", e))?
+            .iter()
+            .any(|s| &s.id == id)
+        {
+            return Err(AppError::NotFound(format!("No chat session {}.", id)).into());
         }
-        _ => {
-            eprintln!("Unknown command.");
+    }
+
+    if let Some(message) = message {
+        let reply = async_runtime()?.block_on(services::chat(&ctx.app, &message, session_id))
+            .map_err(|e| CliError::failure("Chat failed", e))?;
+        print_chat_reply(ctx, &reply);
+        return Ok(());
+    }
+
+    if let (Some(id), false) = (&session_id, ctx.json) {
+        let history = services::list_chat_messages(&ctx.app, id)
+            .map_err(|e| CliError::failure("Failed to read messages", e))?;
+        for past_message in &history {
+            let speaker = if past_message.sender == "user" { "You" } else { "MoodJourney" };
+            println!("{}: {}\n", speaker, past_message.content.trim());
         }
     }
+    eprintln!("Type a message and press Enter. Type /exit or press Ctrl-D to leave.");
+
+    let runtime = async_runtime()?;
+    loop {
+        eprint!("> ");
+        let _ = io::stderr().flush();
+        let mut line = String::new();
+        match io::stdin().read_line(&mut line) {
+            Ok(0) => break,
+            Ok(_) => {}
+            Err(e) => return Err(CliError::failure("Failed to read stdin", e)),
+        }
+        let user_message = line.trim();
+        if user_message.is_empty() {
+            continue;
+        }
+        if user_message == "/exit" || user_message == "/quit" {
+            break;
+        }
+        // a failed turn keeps the REPL open so the user can retry
+        match runtime.block_on(services::chat(&ctx.app, user_message, session_id.clone())) {
+            Ok(reply) => {
+                if session_id.is_none() {
+                    eprintln!("Started chat session {}.", reply.session_id);
+                }
+                session_id = Some(reply.session_id.clone());
+                print_chat_reply(ctx, &reply);
+            }
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+    Ok(())
+}
+
+fn run_suggest(ctx: &Context, date: &str, password: Option<&str>, save: bool) -> CliResult {
+    let entry = find_entry(ctx, date)?;
+    if !check_entry_password(&entry, password)? {
+        return Err(AppError::Locked("This entry is password protected. Use --password to unlock it.".to_string()).into());
+    }
+    let full_content = entry.content.clone().unwrap_or_default();
+    let (body, _, _) = export::parse_entry_content(&full_content);
+    let generated = async_runtime()?.block_on(services::generate_suggestion(entry.title.as_deref(), Some(&body)))
+        .map_err(|e| CliError::failure("Suggestion failed", e))?;
+
+    if save {
+        let new_content = suggestion::replace_suggestion(&full_content, &generated);
+        let title = entry.title.as_deref().unwrap_or(DEFAULT_ENTRY_TITLE);
+        services::update_entry(&ctx.app, date, title, Some(&new_content), entry.password.as_deref(), entry.image.as_deref())
+            .map_err(|e| CliError::failure("Failed to save the suggestions", e))?;
+    }
+    ctx.print(&json!({ "date": date, "suggestion": generated, "saved": save }), || generated.clone());
+    Ok(())
+}
+
+fn run_gc(ctx: &Context, apply: bool) -> CliResult {
+    let report = services::reconcile_images(&ctx.app, apply)
+        .map_err(|e| CliError::failure("Failed to check images", e))?;
+    ctx.print(&report, || {
+        let mut lines = vec![
+            format!("Scanned {} file(s), {} referenced path(s).", report.scanned_files, report.referenced_paths),
+            format!("Orphaned files: {} ({} bytes)", report.orphaned_files.len(), report.orphaned_bytes),
+        ];
+        lines.extend(report.orphaned_files.iter().map(|path| format!("  {}", path)));
+        lines.push(format!("Dangling references: {}", report.dangling_references.len()));
+        lines.extend(report.dangling_references.iter()
+            .map(|d| format!("  {} -> {} ({})", d.entry_date, d.relative_path, d.source)));
+        if apply {
+            lines.push(format!("Removed {} file(s), cleared {} reference(s).", report.removed_files, report.cleared_references));
+        } else if !report.is_clean() {
+            lines.push("Run `cli gc --apply` to clean up.".to_string());
+        }
+        lines.join("\n")
+    });
+    Ok(())
+}
+
+fn run_check(ctx: &Context, repair: bool) -> CliResult {
+    let report = services::check_database(&ctx.app, repair)
+        .map_err(|e| CliError::failure("Failed to check the database", e))?;
+    ctx.print(&report, || {
+        let mut lines = vec![format!("Integrity check: {}", report.integrity_check.join("; "))];
+        lines.push(format!("Orphaned chat messages: {}", report.orphaned_chat_messages));
+        lines.push(format!("Orphaned attachments: {}", report.orphaned_attachments));
+        lines.push(format!("Invalid entry dates: {}", report.invalid_dates.len()));
+        lines.extend(report.invalid_dates.iter().map(|date| format!("  {}", date)));
+        if repair {
+            lines.push(format!("Removed {} message(s) and {} attachment(s).", report.removed_chat_messages, report.removed_attachments));
+        } else if report.orphaned_chat_messages + report.orphaned_attachments > 0 {
+            lines.push("Run `cli check --repair` to remove orphaned rows.".to_string());
+        }
+        lines.join("\n")
+    });
+    Ok(())
+}
+
+fn run_backup(ctx: &Context, archive: &Path, encrypt: bool) -> CliResult {
+    let passphrase = encrypt.then(|| prompt_line("Backup passphrase: "));
+    let manifest = services::export_backup(&ctx.app, archive, passphrase.as_deref())
+        .map_err(|e| CliError::failure("Failed to create backup", e))?;
+    ctx.print(&manifest, || {
+        format!("Backup of {} entries and {} file(s) written to {}.", manifest.entry_count, manifest.files.len(), archive.display())
+    });
+    Ok(())
+}
+
+fn run_restore(ctx: &Context, archive: &Path) -> CliResult {
+    restore_backup(ctx, archive, || prompt_line("Backup passphrase: "))
+}
+
+// the passphrase is only asked for once the manifest shows the archive is encrypted
+fn restore_backup(ctx: &Context, archive: &Path, ask_passphrase: impl FnOnce() -> String) -> CliResult {
+    let passphrase = match services::read_backup_manifest(archive, None) {
+        Ok(_) => None,
+        Err(e) if e.code() == "PASSPHRASE_REQUIRED" => Some(ask_passphrase()),
+        Err(e) => return Err(CliError::failure("Invalid backup", e)),
+    };
+    let manifest = services::import_backup(&ctx.app, archive, passphrase.as_deref())
+        .map_err(|e| CliError::failure("Failed to restore backup", e))?;
+    ctx.print(&manifest, || format!("Restored {} entries from backup created at {}.", manifest.entry_count, manifest.created_at));
+    Ok(())
+}
+
+fn run_export(ctx: &Context, args: ExportArgs) -> CliResult {
+    let format = ExportFormat::parse(&args.format)
+        .ok_or_else(|| AppError::InvalidInput(format!("Unknown export format {}; use markdown, html, pdf or ics.", args.format)))?;
+    let options = ExportOptions {
+        from_date: args.from,
+        to_date: args.to,
+        emotions: args.emotions,
+        include_protected: args.include_protected,
+    };
+    let report = services::export_journal(&ctx.app, format, &args.output, &options)
+        .map_err(|e| CliError::failure("Failed to export journal", e))?;
+    ctx.print(&report, || {
+        let mut lines = vec![format!("Exported {} entries and {} image(s) to {}.", report.entry_count, report.image_count, args.output.display())];
+        if report.skipped_protected > 0 {
+            lines.push(format!("Skipped {} password-protected entries (use --include-protected).", report.skipped_protected));
+        }
+        lines.extend(report.missing_images.iter().map(|missing| format!("  Missing image: {}", missing)));
+        lines.join("\n")
+    });
+    Ok(())
+}
+
+fn run_import(ctx: &Context, args: ImportArgs) -> CliResult {
+    let source = ImportSource::parse(&args.source)
+        .ok_or_else(|| AppError::InvalidInput(format!("Unknown import source {}; use dayone, journey or markdown.", args.source)))?;
+    let policy = ConflictPolicy::parse(&args.policy)
+        .ok_or_else(|| AppError::InvalidInput(format!("Unknown conflict policy {}; use skip, merge or rename.", args.policy)))?;
+    let options = ImportOptions { policy, dry_run: args.dry_run, ..ImportOptions::default() };
+    let report = services::import_journal(&ctx.app, source, &args.path, &options)
+        .map_err(|e| CliError::failure("Failed to import", e))?;
+    ctx.print(&report, || {
+        let mut lines: Vec<String> = report.items.iter().map(|item| {
+            let moved = if item.target_date != item.date { format!(" -> {}", item.target_date) } else { String::new() };
+            format!("{:?} {}{} {} ({} image(s))", item.action, item.date, moved, item.title.as_deref().unwrap_or(""), item.image_count)
+        }).collect();
+        lines.extend(report.warnings.iter().map(|warning| format!("Warning: {}", warning)));
+        lines.push(format!(
+            "{}{} created, {} merged, {} renamed, {} skipped.",
+            if report.dry_run { "Dry run: " } else { "" },
+            report.created, report.merged, report.renamed, report.skipped
+        ));
+        lines.join("\n")
+    });
+    Ok(())
+}
+
+fn run_export_data(ctx: &Context, output: &str, csv: bool) -> CliResult {
+    let format = if csv { DatasetFileFormat::Csv } else { DatasetFileFormat::from_path(Path::new(output)) };
+    if output == "-" {
+        let data = services::read_dataset(&ctx.app).map_err(|e| CliError::failure("Failed to export data", e))?;
+        if format == DatasetFileFormat::Csv {
+            print!("{}", dataset::entries_to_csv(&data.entries));
+        } else {
+            println!("{}", serde_json::to_string_pretty(&data).unwrap_or_default());
+        }
+        return Ok(());
+    }
+    let data = services::export_dataset(&ctx.app, Path::new(output), format)
+        .map_err(|e| CliError::failure("Failed to export data", e))?;
+    let counts = json!({
+        "output": output,
+        "entries": data.entries.len(),
+        "chatSessions": data.chat_sessions.len(),
+        "chatMessages": data.chat_messages.len(),
+    });
+    ctx.print(&counts, || format!(
+        "Exported {} entries, {} chat sessions and {} messages to {}.",
+        data.entries.len(), data.chat_sessions.len(), data.chat_messages.len(), output
+    ));
+    Ok(())
+}
+
+fn run_import_data(ctx: &Context, input: &Path, overwrite: bool) -> CliResult {
+    let options = DatasetImportOptions { overwrite };
+    let report = services::import_dataset(&ctx.app, input, &options)
+        .map_err(|e| CliError::failure("Failed to import data", e))?;
+    ctx.print(&report, || {
+        let mut lines: Vec<String> = report.warnings.iter().map(|warning| format!("Warning: {}", warning)).collect();
+        lines.push(format!(
+            "Imported {} entries, {} chat sessions and {} messages; skipped {}, {} and {}.",
+            report.entries_imported, report.sessions_imported, report.messages_imported,
+            report.entries_skipped, report.sessions_skipped, report.messages_skipped
+        ));
+        lines.join("\n")
+    });
+    Ok(())
+}
+
+fn run(cli: Cli) -> CliResult {
+    let ctx = Context::open(&cli)?;
+    // the models a command needs are loaded into the one context before it runs
+    let ctx = match &cli.command {
+        Command::Classify { .. } | Command::Checkins(CheckinsCommand::Compare { classify: true, .. }) => ctx.with_emotion_model(),
+        Command::Write { analysis, .. } | Command::Edit { analysis, .. } if analysis.classify => ctx.with_emotion_model(),
+        Command::Transcribe(args) => ctx.with_dictation_model(&args.model)?,
+        _ => ctx,
+    };
+    match cli.command {
+        Command::Init => {
+            let db = &ctx.app.db;
+            let version = db.schema_version().map_err(|e| CliError::failure("Failed to read the schema version", e))?;
+            ctx.print(&json!({ "db": db.path(), "schemaVersion": version }), || {
+                format!("Database at {} is ready (schema version {}).", db.path().display(), version)
+            });
+            Ok(())
+        }
+        Command::Entries(command) => run_entries(&ctx, command),
+        Command::Sessions(command) => run_sessions(&ctx, command),
+        Command::Trash(command) => run_trash(&ctx, command),
+        Command::Drafts(command) => run_drafts(&ctx, command),
+        Command::Tags(command) => run_tags(&ctx, command),
+        Command::Checkins(command) => run_checkins(&ctx, command),
+        Command::Password(command) => run_password(&ctx, command),
+        Command::Settings(command) => run_settings(&ctx, command),
+        Command::Write { date, title, password, stdin, analysis } => run_write(&ctx, date, title, password, stdin, analysis),
+        Command::Edit { date, password, analysis } => run_edit(&ctx, &date, password.as_deref(), analysis),
+        Command::Transcribe(args) => run_transcribe(&ctx, args),
+        Command::Classify { input, password, save } => run_classify(&ctx, &input, password.as_deref(), save),
+        Command::Chat { session, message } => run_chat(&ctx, session, message),
+        Command::Suggest { date, password, save } => run_suggest(&ctx, &date, password.as_deref(), save),
+        Command::Gc { apply } => run_gc(&ctx, apply),
+        Command::Check { repair } => run_check(&ctx, repair),
+        Command::Backup { archive, encrypt } => run_backup(&ctx, &archive, encrypt),
+        Command::Restore { archive } => run_restore(&ctx, &archive),
+        Command::Export(args) => run_export(&ctx, args),
+        Command::Import(args) => run_import(&ctx, args),
+        Command::ExportData { output, csv } => run_export_data(&ctx, &output, csv),
+        Command::ImportData { input, overwrite } => run_import_data(&ctx, &input, overwrite),
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let json_output = cli.json;
+    match run(cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            if json_output {
+                eprintln!("{}", json!({ "error": { "exitCode": error.exit_code(), "code": error.0.code(), "message": error.0.message() } }));
+            } else {
+                eprintln!("{}", error.0);
+            }
+            ExitCode::from(error.exit_code())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_dir(test_name: &str) -> PathBuf {
+        let mut dir = std::env::temp_dir();
+        dir.push("moodjourney_cli_tests");
+        dir.push(test_name);
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).expect("Failed to create test directory");
+        dir
+    }
+
+    fn run_args(dir: &Path, args: &[&str]) -> CliResult {
+        let db = dir.join("entries.db");
+        let data_dir = dir.join("data");
+        let config_dir = dir.join("config");
+        let mut full_args: Vec<&str> = vec!["cli", "--db", db.to_str().unwrap(), "--data-dir", data_dir.to_str().unwrap(), "--config-dir", config_dir.to_str().unwrap()];
+        full_args.extend_from_slice(args);
+        run(Cli::try_parse_from(full_args).expect("Failed to parse arguments"))
+    }
+
+    fn test_context(dir: &Path) -> Context {
+        let db = dir.join("entries.db");
+        let data_dir = dir.join("data");
+        let config_dir = dir.join("config");
+        let cli = Cli::try_parse_from(["cli", "--db", db.to_str().unwrap(), "--data-dir", data_dir.to_str().unwrap(), "--config-dir", config_dir.to_str().unwrap(), "init"])
+            .expect("Failed to parse arguments");
+        Context::open(&cli).unwrap_or_else(|e| panic!("Failed to open the context: {}", e.0))
+    }
+
+    #[test]
+    fn test_exit_codes_follow_the_error_kind() {
+        let cases = [
+            (AppError::NotFound(String::new()), EXIT_NOT_FOUND),
+            (AppError::Duplicate(String::new()), EXIT_CONFLICT),
+            (AppError::InvalidDate(String::new()), EXIT_USAGE),
+            (AppError::InvalidInput(String::new()), EXIT_USAGE),
+            (AppError::Locked(String::new()), EXIT_DENIED),
+            (AppError::WrongPassword(String::new()), EXIT_DENIED),
+            (AppError::PassphraseRequired(String::new()), EXIT_DENIED),
+            (AppError::NotConfigured(String::new()), EXIT_FAILURE),
+            (AppError::ModelUnavailable(String::new()), EXIT_FAILURE),
+            (AppError::ApiKeyMissing(String::new()), EXIT_FAILURE),
+            (AppError::Network(String::new()), EXIT_FAILURE),
+            (AppError::RateLimited(String::new()), EXIT_FAILURE),
+            (AppError::TooLarge(String::new()), EXIT_FAILURE),
+            (AppError::UnsupportedImage(String::new()), EXIT_FAILURE),
+            (AppError::InvalidFile(String::new()), EXIT_FAILURE),
+            (AppError::UnsupportedVersion(String::new()), EXIT_FAILURE),
+            (AppError::Corrupted(String::new()), EXIT_FAILURE),
+            (AppError::Io(String::new()), EXIT_FAILURE),
+            (AppError::Database(String::new()), EXIT_FAILURE),
+            (AppError::Internal(String::new()), EXIT_FAILURE),
+        ];
+        for (error, expected) in cases {
+            let code = error.code();
+            assert_eq!(CliError::from(error).exit_code(), expected, "Unexpected exit code for {}.", code);
+        }
+
+        let failure = CliError::failure("Failed to read entry", AppError::NotFound("No entry.".to_string()));
+        assert_eq!(failure.exit_code(), EXIT_NOT_FOUND, "Adding context should keep the error kind.");
+        assert_eq!(failure.0.message(), "Failed to read entry: No entry.");
+    }
+
+    #[test]
+    fn test_settings_value_mut_follows_key_paths() {
+        let mut settings = serde_json::to_value(AppSettings::default()).expect("Failed to serialize settings");
+        assert_eq!(settings_value_mut(&mut settings, "thumbnailSize"), Some(&mut json!(320)));
+        *settings_value_mut(&mut settings, "autoBackup.keepLast").expect("Nested keys should resolve.") = json!(3);
+        assert_eq!(settings["autoBackup"]["keepLast"], json!(3));
+        assert!(settings_value_mut(&mut settings, "autoBackup.missing").is_none());
+        assert!(settings_value_mut(&mut settings, "thumbnailSize.width").is_none(), "A number has no fields.");
+        assert!(settings_value_mut(&mut settings, "").is_none());
+    }
+
+    #[test]
+    fn test_read_content_arg() {
+        assert_eq!(read_content_from(None, io::empty()).ok(), Some(None));
+        assert_eq!(read_content_from(Some("Inline text".to_string()), io::empty()).ok(), Some(Some("Inline text".to_string())));
+        let piped = read_content_from(Some("-".to_string()), "First line\nSecond line\n\n".as_bytes()).ok();
+        assert_eq!(piped, Some(Some("First line\nSecond line".to_string())), "Piped text should lose only its trailing whitespace.");
+    }
+
+    #[test]
+    fn test_editor_buffer_keeps_headings_and_hashtags() {
+        let body = "# Heading\nA calm morning.\n\n#garden was full of bees.";
+        let buffer = format!("Saturday\n\n{}\n\n{}", body, EDITOR_HELP);
+        let (title, parsed_body) = parse_editor_buffer(&buffer);
+        assert_eq!(title.as_deref(), Some("Saturday"));
+        assert_eq!(parsed_body, body, "Only the help lines should be dropped.");
+
+        let (title, parsed_body) = parse_editor_buffer(EDITOR_HELP);
+        assert!(title.is_none() && parsed_body.is_empty(), "An untouched buffer should cancel.");
+    }
+
+    #[test]
+    fn test_compose_entry_content_and_stored_suggestion() {
+        assert_eq!(compose_entry_content("  Text\n", None, None), "Text");
+        let content = compose_entry_content("# Heading\n#tag day", Some("joy"), Some("- Take a walk\n"));
+        assert_eq!(content, "# Heading\n#tag day\n\n🧠 Emotion: joy\n\n💡 Suggestion: - Take a walk");
+        assert_eq!(stored_suggestion(&content).as_deref(), Some("- Take a walk"));
+        assert_eq!(stored_suggestion("No analysis yet."), None);
+
+        let (body, emotion, _) = export::parse_entry_content(&content);
+        let buffer = format!("Title\n\n{}\n\n{}", body, EDITOR_HELP);
+        let (_, edited_body) = parse_editor_buffer(&buffer);
+        let resaved = compose_entry_content(&edited_body, emotion.as_deref(), stored_suggestion(&content).as_deref());
+        assert_eq!(resaved, content, "Editing without changes should save the same content.");
+    }
+
+    #[test]
+    fn test_run_against_a_temporary_journal() {
+        let dir = test_dir("run");
+        run_args(&dir, &["init"]).unwrap_or_else(|e| panic!("init failed: {}", e.0));
+        run_args(&dir, &["entries", "add", "--date", "2025-06-01", "--title", "Sunday", "--content", "A walk by the river."])
+            .unwrap_or_else(|e| panic!("add failed: {}", e.0));
+
+        let db = Database::open(&dir.join("entries.db")).expect("Failed to open database");
+        let entry = db.get_entry_by_date("2025-06-01").expect("get failed").expect("The entry should be saved.");
+        assert_eq!(entry.title.as_deref(), Some("Sunday"));
+        assert_eq!(entry.content.as_deref(), Some("A walk by the river."));
+
+        run_args(&dir, &["entries", "update", "2025-06-01", "--content", "A long walk by the river."])
+            .unwrap_or_else(|e| panic!("update failed: {}", e.0));
+        let entry = db.get_entry_by_date("2025-06-01").expect("get failed").expect("The entry should be saved.");
+        assert_eq!(entry.title.as_deref(), Some("Sunday"));
+        assert_eq!(entry.content.as_deref(), Some("A long walk by the river."));
+        assert_eq!(db.get_entry_revisions("2025-06-01").expect("Failed to read revisions").len(), 1, "The update should keep the old text as a revision.");
+
+        run_args(&dir, &["entries", "update", "2025-06-01", "--password", "otter"]).unwrap_or_else(|e| panic!("update failed: {}", e.0));
+        let locked = run_args(&dir, &["entries", "update", "2025-06-01", "--content", "Overwritten."]).err().expect("A protected entry should need its password.");
+        assert_eq!(locked.exit_code(), EXIT_DENIED);
+        let wrong = run_args(&dir, &["entries", "update", "2025-06-01", "--content", "Overwritten.", "--current-password", "seal"]).err().expect("A wrong password should fail.");
+        assert_eq!(wrong.exit_code(), EXIT_DENIED);
+        run_args(&dir, &["entries", "update", "2025-06-01", "--remove-password", "--current-password", "otter"])
+            .unwrap_or_else(|e| panic!("update failed: {}", e.0));
+        let entry = db.get_entry_by_date("2025-06-01").expect("get failed").expect("The entry should be saved.");
+        assert_eq!(entry.content.as_deref(), Some("A long walk by the river."));
+        assert_eq!(entry.password, None);
+
+        let duplicate = run_args(&dir, &["entries", "add", "--date", "2025-06-01", "--content", "Again"]).err().expect("A second entry for the date should fail.");
+        assert_eq!(duplicate.exit_code(), EXIT_CONFLICT);
+        let missing = run_args(&dir, &["entries", "show", "2025-06-02"]).err().expect("Showing a missing entry should fail.");
+        assert_eq!(missing.exit_code(), EXIT_NOT_FOUND);
+        let bad_date = run_args(&dir, &["entries", "add", "--date", "June 3rd", "--content", "Text"]).err().expect("A bad date should fail.");
+        assert_eq!(bad_date.exit_code(), EXIT_USAGE);
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_restore_asks_for_the_passphrase_of_an_encrypted_backup() {
+        let dir = test_dir("restore_encrypted");
+        run_args(&dir, &["init"]).unwrap_or_else(|e| panic!("init failed: {}", e.0));
+        run_args(&dir, &["entries", "add", "--date", "2025-06-01", "--title", "Sunday", "--content", "A walk by the river."])
+            .unwrap_or_else(|e| panic!("add failed: {}", e.0));
+        let ctx = test_context(&dir);
+        let archive = dir.join("journal.mjbackup");
+        services::export_backup(&ctx.app, &archive, Some("river")).expect("export_backup failed");
+
+        run_args(&dir, &["entries", "update", "2025-06-01", "--content", "Changed after the backup."])
+            .unwrap_or_else(|e| panic!("update failed: {}", e.0));
+        let mut asked = false;
+        restore_backup(&ctx, &archive, || {
+            asked = true;
+            "river".to_string()
+        })
+        .unwrap_or_else(|e| panic!("restore failed: {}", e.0));
+        assert!(asked, "An encrypted backup should ask for its passphrase.");
+
+        let db = Database::open(&dir.join("entries.db")).expect("Failed to open database");
+        let entry = db.get_entry_by_date("2025-06-01").expect("get failed").expect("The entry should be restored.");
+        assert_eq!(entry.content.as_deref(), Some("A walk by the river."));
+
+        let wrong = restore_backup(&ctx, &archive, || "sea".to_string()).err().expect("A wrong passphrase should fail.");
+        assert_ne!(wrong.exit_code(), 0);
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_trash_commands_share_the_services() {
+        let dir = test_dir("trash");
+        run_args(&dir, &["entries", "add", "--date", "2025-06-01", "--content", "A walk by the river."])
+            .unwrap_or_else(|e| panic!("add failed: {}", e.0));
+        run_args(&dir, &["tags", "add", "2025-06-01", "outdoors"]).unwrap_or_else(|e| panic!("tags add failed: {}", e.0));
+
+        run_args(&dir, &["entries", "delete", "2025-06-01"]).unwrap_or_else(|e| panic!("delete failed: {}", e.0));
+        let trashed = run_args(&dir, &["entries", "add", "--date", "2025-06-01", "--content", "Again"]).err().expect("A trashed entry should still hold its date.");
+        assert_eq!(trashed.exit_code(), EXIT_CONFLICT);
+        run_args(&dir, &["trash", "restore", "2025-06-01"]).unwrap_or_else(|e| panic!("restore failed: {}", e.0));
+
+        let ctx = test_context(&dir);
+        assert_eq!(services::get_entry_tags(&ctx.app, "2025-06-01").expect("Failed to read tags"), vec!["outdoors".to_string()]);
+        drop(ctx);
+
+        run_args(&dir, &["entries", "delete", "2025-06-01", "--permanent"]).unwrap_or_else(|e| panic!("delete failed: {}", e.0));
+        let missing = run_args(&dir, &["trash", "restore", "2025-06-01"]).err().expect("A purged entry cannot be restored.");
+        assert_eq!(missing.exit_code(), EXIT_NOT_FOUND);
+        let _ = fs::remove_dir_all(dir);
+    }
 }
@@ -0,0 +1,126 @@
+use crate::export::{self, ExportedEntry};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+
+const PRODUCT_ID: &str = "-//MoodJourney//Journal Export//EN";
+const UID_DOMAIN: &str = "com.moodjourney.app";
+const DESCRIPTION_EXCERPT_CHARS: usize = 280;
+// RFC 5545 content lines are folded at 75 octets
+const MAX_LINE_OCTETS: usize = 75;
+
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+fn fold_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + line.len() / MAX_LINE_OCTETS * 3);
+    let mut line_octets = 0;
+    for c in line.chars() {
+        // continuation lines start with a space, which counts towards their length
+        if line_octets + c.len_utf8() > MAX_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            line_octets = 1;
+        }
+        folded.push(c);
+        line_octets += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+pub fn event_summary(entry: &ExportedEntry) -> String {
+    match &entry.emotion {
+        Some(emotion) => format!("{} ({})", entry.title, emotion),
+        None => entry.title.clone(),
+    }
+}
+
+// one all-day event per entry; UIDs are derived from the date so re-imports update instead of duplicating
+pub fn render_ics(entries: &[ExportedEntry], generated_at: DateTime<Utc>) -> String {
+    let dtstamp = generated_at.format("%Y%m%dT%H%M%SZ").to_string();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:{}", PRODUCT_ID),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        "X-WR-CALNAME:MoodJourney Journal".to_string(),
+    ];
+
+    for entry in entries {
+        let date = match NaiveDate::parse_from_str(&entry.date, "%Y-%m-%d") {
+            Ok(date) => date,
+            Err(_) => continue,
+        };
+        let end_date = date + Duration::days(1);
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:entry-{}@{}", date.format("%Y%m%d"), UID_DOMAIN));
+        lines.push(format!("DTSTAMP:{}", dtstamp));
+        lines.push(format!("DTSTART;VALUE=DATE:{}", date.format("%Y%m%d")));
+        lines.push(format!("DTEND;VALUE=DATE:{}", end_date.format("%Y%m%d")));
+        lines.push(format!("SUMMARY:{}", escape_text(&event_summary(entry))));
+        let excerpt = export::excerpt(&entry.body, DESCRIPTION_EXCERPT_CHARS);
+        if !excerpt.is_empty() {
+            lines.push(format!("DESCRIPTION:{}", escape_text(&excerpt)));
+        }
+        if let Some(emotion) = &entry.emotion {
+            lines.push(format!("CATEGORIES:{}", escape_text(emotion)));
+        }
+        lines.push("TRANSP:TRANSPARENT".to_string());
+        lines.push("END:VEVENT".to_string());
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+    lines.iter().map(|line| fold_line(line)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn entry(date: &str, title: &str, body: &str, emotion: Option<&str>) -> ExportedEntry {
+        ExportedEntry {
+            date: date.to_string(),
+            title: title.to_string(),
+            body: body.to_string(),
+            emotion: emotion.map(|e| e.to_string()),
+            suggestions: Vec::new(),
+            images: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_render_ics_all_day_events() {
+        let generated_at = Utc.with_ymd_and_hms(2025, 3, 1, 8, 30, 0).unwrap();
+        let ics = render_ics(&[
+            entry("2025-02-28", "Month end", "Closed the books; finally, done.\nTime to rest.", Some("joy")),
+            entry("2025-03-01", "Quiet", "", None),
+        ], generated_at);
+
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 2);
+        assert!(ics.contains("UID:entry-20250228@com.moodjourney.app\r\n"));
+        assert!(ics.contains("DTSTAMP:20250301T083000Z\r\n"));
+        assert!(ics.contains("DTSTART;VALUE=DATE:20250228\r\nDTEND;VALUE=DATE:20250301\r\n"), "All-day events end on the following day.");
+        assert!(ics.contains("SUMMARY:Month end (joy)\r\n"));
+        assert!(ics.contains("DESCRIPTION:Closed the books\\; finally\\, done. Time to rest.\r\n"));
+        assert!(ics.contains("SUMMARY:Quiet\r\n"));
+        assert_eq!(ics.matches("DESCRIPTION:").count(), 1, "Empty bodies get no description.");
+    }
+
+    #[test]
+    fn test_long_lines_are_folded_on_char_boundaries() {
+        let line = format!("DESCRIPTION:{}", "é".repeat(100));
+        let folded = fold_line(&line);
+        for physical_line in folded.split("\r\n").filter(|l| !l.is_empty()) {
+            assert!(physical_line.len() <= MAX_LINE_OCTETS, "Line exceeds 75 octets: {}", physical_line.len());
+        }
+        let unfolded = folded.replace("\r\n ", "");
+        assert_eq!(unfolded.trim_end(), line);
+    }
+}
@@ -0,0 +1,406 @@
+use crate::db::{Checkin, Database, NewCheckin};
+use crate::error::AppError;
+use crate::export;
+use crate::importers::EmotionClassifier;
+use anyhow::Result;
+use chrono::{DateTime, Local, NaiveDate, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+// the labels the emotion model predicts; a check-in picks its emotions from the same list so the two can be compared
+pub const EMOTION_LABELS: [&str; 7] = ["anger", "disgust", "fear", "joy", "neutral", "sadness", "surprise"];
+// mood and energy are rated on the same scale
+pub const MIN_SCORE: u8 = 1;
+pub const MAX_SCORE: u8 = 10;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CheckinInput {
+    // RFC 3339 with an offset; the current time when left out
+    pub recorded_at: Option<String>,
+    pub mood: u8,
+    pub emotions: Vec<String>,
+    pub energy: Option<u8>,
+    pub sleep_hours: Option<f64>,
+    pub note: Option<String>,
+}
+
+// dates are inclusive YYYY-MM-DD days; without a limit every matching check-in is returned
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CheckinQuery {
+    pub from_date: Option<String>,
+    pub to_date: Option<String>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Valence {
+    Negative,
+    Neutral,
+    Positive,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PredictionSource {
+    // the emotion saved with the entry of that day
+    Entry,
+    // the text of that day's entry, classified because it had no emotion saved
+    EntryText,
+    // the check-in note, classified when there is no entry to compare with
+    Note,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckinComparison {
+    pub checkin_id: i64,
+    pub recorded_at: String,
+    pub date: String,
+    pub mood: u8,
+    pub emotions: Vec<String>,
+    pub predicted_emotion: Option<String>,
+    pub prediction_source: Option<PredictionSource>,
+    // whether the prediction is one of the chosen emotions; None without a prediction or chosen emotions
+    pub emotion_match: Option<bool>,
+    // whether the mood score leans the same way as the prediction; None without a prediction
+    pub valence_match: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PredictedEmotionMood {
+    pub emotion: String,
+    pub checkins: usize,
+    pub average_mood: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MoodComparisonReport {
+    pub checkins: usize,
+    pub with_prediction: usize,
+    // shares between 0 and 1 of the check-ins the match could be decided for
+    pub emotion_agreement: Option<f64>,
+    pub valence_agreement: Option<f64>,
+    // the self-reported mood behind each predicted emotion, most frequent first
+    pub mood_by_predicted_emotion: Vec<PredictedEmotionMood>,
+    pub comparisons: Vec<CheckinComparison>,
+    pub warnings: Vec<String>,
+}
+
+fn validate_score(value: u8, label: &str) -> Result<u8> {
+    if !(MIN_SCORE..=MAX_SCORE).contains(&value) {
+        return Err(AppError::InvalidInput(format!("The {} must be between {} and {}, got {}.", label, MIN_SCORE, MAX_SCORE, value)).into());
+    }
+    Ok(value)
+}
+
+fn validate_date(value: Option<&str>, label: &str) -> Result<Option<String>> {
+    match value.map(str::trim).filter(|v| !v.is_empty()) {
+        Some(v) => NaiveDate::parse_from_str(v, "%Y-%m-%d")
+            .map(|_| Some(v.to_string()))
+            .map_err(|_| AppError::InvalidDate(format!("The {} date must be in YYYY-MM-DD format, got '{}'.", label, v)).into()),
+        None => Ok(None),
+    }
+}
+
+// lowercased, in the order given and without repeats
+pub fn normalize_emotions(emotions: &[String]) -> Result<Vec<String>> {
+    let mut normalized: Vec<String> = Vec::new();
+    for emotion in emotions {
+        let emotion = emotion.trim().to_lowercase();
+        if !EMOTION_LABELS.contains(&emotion.as_str()) {
+            return Err(AppError::InvalidInput(format!("Unknown emotion '{}'. Choose from {}.", emotion, EMOTION_LABELS.join(", "))).into());
+        }
+        if !normalized.contains(&emotion) {
+            normalized.push(emotion);
+        }
+    }
+    Ok(normalized)
+}
+
+pub fn record_checkin(db: &Database, input: &CheckinInput, now: DateTime<Local>) -> Result<Checkin> {
+    let recorded_at = match input.recorded_at.as_deref().map(str::trim).filter(|v| !v.is_empty()) {
+        Some(value) => DateTime::parse_from_rfc3339(value).map_err(|_| {
+            AppError::InvalidDate(format!("The check-in time must be an RFC 3339 timestamp such as 2025-06-01T08:30:00+02:00, got '{}'.", value))
+        })?,
+        None => now.fixed_offset(),
+    };
+    let sleep_hours = match input.sleep_hours {
+        Some(hours) if !hours.is_finite() || !(0.0..=24.0).contains(&hours) => {
+            return Err(AppError::InvalidInput(format!("Sleep hours must be between 0 and 24, got {}.", hours)).into());
+        }
+        other => other,
+    };
+
+    Ok(db.add_checkin(&NewCheckin {
+        recorded_at: recorded_at.with_timezone(&Utc).to_rfc3339_opts(SecondsFormat::Secs, true),
+        date: recorded_at.date_naive().format("%Y-%m-%d").to_string(),
+        mood: validate_score(input.mood, "mood")?,
+        emotions: normalize_emotions(&input.emotions)?,
+        energy: input.energy.map(|energy| validate_score(energy, "energy")).transpose()?,
+        sleep_hours,
+        note: input.note.as_deref().map(str::trim).filter(|n| !n.is_empty()).map(str::to_string),
+    })?)
+}
+
+pub fn list_checkins(db: &Database, query: &CheckinQuery) -> Result<Vec<Checkin>> {
+    let from_date = validate_date(query.from_date.as_deref(), "start")?;
+    let to_date = validate_date(query.to_date.as_deref(), "end")?;
+    if let (Some(from), Some(to)) = (&from_date, &to_date) {
+        if from > to {
+            return Err(AppError::InvalidDate(format!("The start date {} is after the end date {}.", from, to)).into());
+        }
+    }
+    Ok(db.get_checkins(from_date.as_deref(), to_date.as_deref(), query.limit)?)
+}
+
+pub fn delete_checkin(db: &Database, id: i64) -> Result<()> {
+    if !db.delete_checkin(id)? {
+        return Err(AppError::NotFound(format!("There is no check-in with id {}.", id)).into());
+    }
+    Ok(())
+}
+
+pub fn emotion_valence(emotion: &str) -> Option<Valence> {
+    match emotion {
+        "joy" | "surprise" => Some(Valence::Positive),
+        "neutral" => Some(Valence::Neutral),
+        "anger" | "disgust" | "fear" | "sadness" => Some(Valence::Negative),
+        _ => None,
+    }
+}
+
+pub fn mood_valence(mood: u8) -> Valence {
+    match mood {
+        0..=4 => Valence::Negative,
+        5..=6 => Valence::Neutral,
+        _ => Valence::Positive,
+    }
+}
+
+// the prediction for a day's entry; protected entries are not read
+fn entry_prediction(db: &Database, date: &str, classify: Option<&EmotionClassifier>, warnings: &mut Vec<String>) -> Result<Option<(String, PredictionSource)>> {
+    let Some(entry) = db.get_entry_by_date(date)? else {
+        return Ok(None);
+    };
+    if entry.password.as_deref().is_some_and(|p| !p.is_empty()) {
+        return Ok(None);
+    }
+    let (body, emotion, _) = export::parse_entry_content(entry.content.as_deref().unwrap_or(""));
+    if let Some(emotion) = emotion {
+        return Ok(Some((emotion.to_lowercase(), PredictionSource::Entry)));
+    }
+    match classify.filter(|_| !body.is_empty()) {
+        Some(classify) => match classify(&body) {
+            Ok(emotion) => Ok(Some((emotion.to_lowercase(), PredictionSource::EntryText))),
+            Err(e) => {
+                warnings.push(format!("{}: emotion classification failed: {}", date, e));
+                Ok(None)
+            }
+        },
+        None => Ok(None),
+    }
+}
+
+fn share(matches: impl Iterator<Item = Option<bool>>) -> Option<f64> {
+    let decided: Vec<bool> = matches.flatten().collect();
+    if decided.is_empty() {
+        return None;
+    }
+    Some(decided.iter().filter(|m| **m).count() as f64 / decided.len() as f64)
+}
+
+// compares each check-in with what the emotion model says about the same day. Without a classifier only
+// the emotions already saved with the entries are used.
+pub fn compare_with_predictions(db: &Database, query: &CheckinQuery, classify: Option<&EmotionClassifier>) -> Result<MoodComparisonReport> {
+    let checkins = list_checkins(db, query)?;
+    let mut report = MoodComparisonReport { checkins: checkins.len(), ..Default::default() };
+    let mut entry_predictions: HashMap<String, Option<(String, PredictionSource)>> = HashMap::new();
+
+    for checkin in checkins {
+        let mut prediction = match entry_predictions.get(&checkin.date) {
+            Some(prediction) => prediction.clone(),
+            None => {
+                let prediction = entry_prediction(db, &checkin.date, classify, &mut report.warnings)?;
+                entry_predictions.insert(checkin.date.clone(), prediction.clone());
+                prediction
+            }
+        };
+        if prediction.is_none() {
+            if let (Some(classify), Some(note)) = (classify, checkin.note.as_deref()) {
+                match classify(note) {
+                    Ok(emotion) => prediction = Some((emotion.to_lowercase(), PredictionSource::Note)),
+                    Err(e) => report.warnings.push(format!("check-in {}: emotion classification failed: {}", checkin.id, e)),
+                }
+            }
+        }
+
+        let (predicted_emotion, prediction_source) = prediction.unzip();
+        let emotion_match = predicted_emotion.as_ref()
+            .filter(|_| !checkin.emotions.is_empty())
+            .map(|predicted| checkin.emotions.contains(predicted));
+        let valence_match = predicted_emotion.as_deref()
+            .and_then(emotion_valence)
+            .map(|valence| valence == mood_valence(checkin.mood));
+        report.comparisons.push(CheckinComparison {
+            checkin_id: checkin.id,
+            recorded_at: checkin.recorded_at,
+            date: checkin.date,
+            mood: checkin.mood,
+            emotions: checkin.emotions,
+            predicted_emotion,
+            prediction_source,
+            emotion_match,
+            valence_match,
+        });
+    }
+
+    report.with_prediction = report.comparisons.iter().filter(|c| c.predicted_emotion.is_some()).count();
+    report.emotion_agreement = share(report.comparisons.iter().map(|c| c.emotion_match));
+    report.valence_agreement = share(report.comparisons.iter().map(|c| c.valence_match));
+
+    let mut moods: HashMap<&str, Vec<u8>> = HashMap::new();
+    for comparison in &report.comparisons {
+        if let Some(emotion) = comparison.predicted_emotion.as_deref() {
+            moods.entry(emotion).or_default().push(comparison.mood);
+        }
+    }
+    let mut mood_by_predicted_emotion: Vec<PredictedEmotionMood> = moods.into_iter()
+        .map(|(emotion, moods)| PredictedEmotionMood {
+            emotion: emotion.to_string(),
+            checkins: moods.len(),
+            average_mood: moods.iter().map(|m| *m as f64).sum::<f64>() / moods.len() as f64,
+        })
+        .collect();
+    mood_by_predicted_emotion.sort_by(|a, b| b.checkins.cmp(&a.checkins).then_with(|| a.emotion.cmp(&b.emotion)));
+    report.mood_by_predicted_emotion = mood_by_predicted_emotion;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Entry;
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn setup(test_name: &str) -> (PathBuf, Database) {
+        let mut dir = std::env::temp_dir();
+        dir.push("moodjourney_checkin_tests");
+        dir.push(test_name);
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).expect("Failed to create test directory");
+        let db = Database::open(&dir.join("journal.db")).expect("Failed to open database");
+        (dir, db)
+    }
+
+    fn input(recorded_at: &str, mood: u8, emotions: &[&str], note: Option<&str>) -> CheckinInput {
+        CheckinInput {
+            recorded_at: Some(recorded_at.to_string()),
+            mood,
+            emotions: emotions.iter().map(|e| e.to_string()).collect(),
+            energy: Some(6),
+            sleep_hours: Some(7.5),
+            note: note.map(str::to_string),
+        }
+    }
+
+    fn add_entry(db: &Database, date: &str, content: &str, password: Option<&str>) {
+        db.add_entry(Entry {
+            date: date.to_string(),
+            title: Some("Day".to_string()),
+            content: Some(content.to_string()),
+            password: password.map(str::to_string),
+            image: None,
+        }).expect("Failed to add entry");
+    }
+
+    #[test]
+    fn test_record_validates_and_keys_by_timestamp() {
+        let (dir, db) = setup("record");
+        let checkin = record_checkin(&db, &input("2025-06-01T23:30:00-04:00", 7, &["Joy", "neutral", "joy"], Some("  ")), Local::now())
+            .expect("record failed");
+        assert_eq!(checkin.recorded_at, "2025-06-02T03:30:00Z");
+        assert_eq!(checkin.date, "2025-06-01", "The date should be the day where the check-in was recorded.");
+        assert_eq!(checkin.emotions, vec!["joy", "neutral"]);
+        assert_eq!(checkin.note, None, "A blank note should not be kept.");
+        assert_eq!(db.get_checkin(checkin.id).expect("get failed"), Some(checkin));
+
+        let err = record_checkin(&db, &input("2025-06-02T03:30:00Z", 3, &[], None), Local::now()).unwrap_err();
+        assert_eq!(AppError::code_of(&err), "DUPLICATE", "Only one check-in can be recorded per instant.");
+        for bad in [input("2025-06-03T08:00:00Z", 11, &[], None), input("2025-06-03T08:00:00Z", 5, &["bored"], None)] {
+            assert_eq!(AppError::code_of(&record_checkin(&db, &bad, Local::now()).unwrap_err()), "INVALID_INPUT");
+        }
+        let mut too_much_sleep = input("2025-06-03T08:00:00Z", 5, &[], None);
+        too_much_sleep.sleep_hours = Some(25.0);
+        assert_eq!(AppError::code_of(&record_checkin(&db, &too_much_sleep, Local::now()).unwrap_err()), "INVALID_INPUT");
+        let err = record_checkin(&db, &input("yesterday", 5, &[], None), Local::now()).unwrap_err();
+        assert_eq!(AppError::code_of(&err), "INVALID_DATE");
+
+        let now = record_checkin(&db, &CheckinInput { mood: 5, ..Default::default() }, Local::now()).expect("record failed");
+        assert_eq!(now.date, Local::now().format("%Y-%m-%d").to_string());
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_list_filters_by_date_and_delete() {
+        let (dir, db) = setup("list");
+        for (at, mood) in [("2025-06-01T08:00:00Z", 4), ("2025-06-01T20:00:00Z", 6), ("2025-06-03T08:00:00Z", 8)] {
+            record_checkin(&db, &input(at, mood, &[], None), Local::now()).expect("record failed");
+        }
+        let all = list_checkins(&db, &CheckinQuery::default()).expect("list failed");
+        assert_eq!(all.iter().map(|c| c.mood).collect::<Vec<_>>(), vec![8, 6, 4], "Check-ins should be listed newest first.");
+
+        let query = CheckinQuery { from_date: Some("2025-06-01".to_string()), to_date: Some("2025-06-02".to_string()), limit: Some(1) };
+        let page = list_checkins(&db, &query).expect("list failed");
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].mood, 6);
+        let backwards = CheckinQuery { from_date: Some("2025-06-03".to_string()), to_date: Some("2025-06-01".to_string()), limit: None };
+        assert_eq!(AppError::code_of(&list_checkins(&db, &backwards).unwrap_err()), "INVALID_DATE");
+
+        delete_checkin(&db, all[0].id).expect("delete failed");
+        assert_eq!(AppError::code_of(&delete_checkin(&db, all[0].id).unwrap_err()), "NOT_FOUND");
+        assert_eq!(list_checkins(&db, &CheckinQuery::default()).expect("list failed").len(), 2);
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_compare_with_predictions() {
+        let (dir, db) = setup("compare");
+        add_entry(&db, "2025-06-01", "A lovely picnic.\n\n🧠 Emotion: joy", None);
+        add_entry(&db, "2025-06-02", "Nothing went right.", None);
+        add_entry(&db, "2025-06-03", "Secret.\n\n🧠 Emotion: fear", Some("hash"));
+        record_checkin(&db, &input("2025-06-01T09:00:00Z", 8, &["joy"], None), Local::now()).expect("record failed");
+        record_checkin(&db, &input("2025-06-02T09:00:00Z", 7, &["neutral"], None), Local::now()).expect("record failed");
+        record_checkin(&db, &input("2025-06-03T09:00:00Z", 3, &[], Some("Tired and low")), Local::now()).expect("record failed");
+        record_checkin(&db, &input("2025-06-04T09:00:00Z", 5, &[], None), Local::now()).expect("record failed");
+
+        let without_model = compare_with_predictions(&db, &CheckinQuery::default(), None).expect("compare failed");
+        assert_eq!(without_model.checkins, 4);
+        assert_eq!(without_model.with_prediction, 1, "Only the saved emotion can be used without a model.");
+        assert_eq!(without_model.emotion_agreement, Some(1.0));
+
+        let classify = |text: &str| -> Result<String> { Ok(if text.contains("right") { "Anger".to_string() } else { "sadness".to_string() }) };
+        let report = compare_with_predictions(&db, &CheckinQuery::default(), Some(&classify)).expect("compare failed");
+        let by_date: HashMap<&str, &CheckinComparison> = report.comparisons.iter().map(|c| (c.date.as_str(), c)).collect();
+        assert_eq!(by_date["2025-06-01"].prediction_source, Some(PredictionSource::Entry));
+        assert_eq!(by_date["2025-06-02"].predicted_emotion.as_deref(), Some("anger"));
+        assert_eq!(by_date["2025-06-02"].prediction_source, Some(PredictionSource::EntryText));
+        assert_eq!(by_date["2025-06-02"].valence_match, Some(false));
+        assert_eq!(by_date["2025-06-03"].prediction_source, Some(PredictionSource::Note), "A protected entry should not be read.");
+        assert_eq!(by_date["2025-06-03"].emotion_match, None);
+        assert_eq!(by_date["2025-06-03"].valence_match, Some(true));
+        assert_eq!(by_date["2025-06-04"].predicted_emotion, None);
+
+        assert_eq!(report.with_prediction, 3);
+        assert_eq!(report.emotion_agreement, Some(0.5));
+        assert_eq!(report.valence_agreement, Some(2.0 / 3.0));
+        assert_eq!(report.mood_by_predicted_emotion.len(), 3);
+        assert_eq!(report.mood_by_predicted_emotion[0].emotion, "anger");
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,469 @@
+use crate::backup::BackupManifest;
+use crate::checkins::{CheckinInput, CheckinQuery, MoodComparisonReport};
+use crate::context::AppContext;
+use crate::dataset::{Dataset, DatasetFileFormat, DatasetImportOptions, DatasetImportReport};
+use crate::db::{Attachment, ChatMessage, ChatSession, Checkin, Draft, Entry, EntryQuery, EntryRevision, Tag};
+use crate::dictation::{DictationOptions, DictationReport};
+use crate::error::{AppError, Result};
+use crate::export::{ExportFormat, ExportOptions, ExportReport};
+use crate::importers::{ImportOptions, ImportReport, ImportSource};
+use crate::listing::EntryPage;
+use crate::maintenance::{ImageGcReport, IntegrityReport};
+use crate::revisions::RevisionDiff;
+use crate::services;
+use crate::settings::AppSettings;
+use crate::snapshots::SnapshotInfo;
+use crate::suggestion::ChatCompletionResponse;
+use crate::tags::{TagEmotionStats, TagMergeReport};
+use crate::trash::{Trash, TrashPurgeReport};
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64_STANDARD};
+use std::path::Path;
+use std::sync::Arc;
+use tauri::{command, State};
+
+type AppState<'a> = State<'a, Arc<AppContext>>;
+
+// runs a service off the async runtime; `task` names the work in join errors
+async fn run_blocking<T, F>(ctx: &AppState<'_>, task: &str, service: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce(&AppContext) -> Result<T> + Send + 'static,
+{
+    let ctx = ctx.inner().clone();
+    tokio::task::spawn_blocking(move || service(&ctx))
+        .await
+        .map_err(|e| AppError::Internal(format!("Task join error during {}: {}", task, e)))?
+}
+
+#[command]
+pub fn is_locked_cmd(ctx: AppState<'_>) -> bool {
+    services::is_locked(&ctx)
+}
+
+#[command]
+pub fn check_password_attempt_cmd(ctx: AppState<'_>, password_str: String) -> bool {
+    services::check_password_attempt(&ctx, &password_str)
+}
+
+#[command]
+pub fn set_new_password_cmd(ctx: AppState<'_>, password_str: String) {
+    services::set_new_password(&ctx, &password_str);
+}
+
+#[command]
+pub fn set_locked_explicit_cmd(ctx: AppState<'_>, locked: bool) {
+    services::set_locked(&ctx, locked);
+}
+
+#[command]
+pub fn is_pin_set_cmd(ctx: AppState<'_>) -> bool {
+    services::is_pin_set(&ctx)
+}
+
+#[command]
+pub fn delete_pin_cmd(ctx: AppState<'_>) {
+    services::delete_pin(&ctx);
+}
+
+#[command]
+pub fn get_settings_cmd(ctx: AppState<'_>) -> AppSettings {
+    services::get_settings(&ctx)
+}
+
+#[command]
+pub fn update_settings_cmd(ctx: AppState<'_>, new_settings: AppSettings) -> Result<AppSettings> {
+    services::update_settings(&ctx, new_settings)
+}
+
+#[command]
+pub fn create_entry(
+    ctx: AppState<'_>,
+    title: &str,
+    content: Option<&str>,
+    password: Option<&str>,
+    image: Option<&str>,
+) -> Result<()> {
+    services::create_entry(&ctx, title, content, password, image)
+}
+
+#[command]
+pub fn read_entries(ctx: AppState<'_>) -> Result<Vec<Entry>> {
+    services::read_entries(&ctx)
+}
+
+// one page of entry summaries; pass the returned nextCursor back as query.cursor for the next page
+#[command]
+pub fn list_entries(ctx: AppState<'_>, query: Option<EntryQuery>) -> Result<EntryPage> {
+    services::list_entries(&ctx, &query.unwrap_or_default())
+}
+
+#[command]
+pub fn get_entry(ctx: AppState<'_>, date: &str) -> Result<Option<Entry>> {
+    services::get_entry(&ctx, date)
+}
+
+#[command]
+pub fn update_entry(
+    ctx: AppState<'_>,
+    date: &str,
+    new_title: &str,
+    new_content: Option<&str>,
+    new_password: Option<&str>,
+    new_image: Option<&str>,
+) -> Result<()> {
+    services::update_entry(&ctx, date, new_title, new_content, new_password, new_image)
+}
+
+#[command]
+pub fn list_entry_revisions_cmd(ctx: AppState<'_>, date: &str) -> Result<Vec<EntryRevision>> {
+    services::list_entry_revisions(&ctx, date)
+}
+
+// leave toRevisionId out to compare against the current entry
+#[command]
+pub fn diff_entry_revisions_cmd(ctx: AppState<'_>, date: &str, from_revision_id: i64, to_revision_id: Option<i64>) -> Result<RevisionDiff> {
+    services::diff_entry_revisions(&ctx, date, from_revision_id, to_revision_id)
+}
+
+#[command]
+pub fn restore_entry_revision_cmd(ctx: AppState<'_>, date: &str, revision_id: i64) -> Result<()> {
+    services::restore_entry_revision(&ctx, date, revision_id)
+}
+
+#[command]
+pub fn list_tags_cmd(ctx: AppState<'_>) -> Result<Vec<Tag>> {
+    services::list_tags(&ctx)
+}
+
+#[command]
+pub fn get_entry_tags_cmd(ctx: AppState<'_>, date: &str) -> Result<Vec<String>> {
+    services::get_entry_tags(&ctx, date)
+}
+
+// resolves to the entry's tags after the change
+#[command]
+pub fn add_entry_tag_cmd(ctx: AppState<'_>, date: &str, name: &str) -> Result<Vec<String>> {
+    services::add_entry_tag(&ctx, date, name)
+}
+
+#[command]
+pub fn remove_entry_tag_cmd(ctx: AppState<'_>, date: &str, name: &str) -> Result<Vec<String>> {
+    services::remove_entry_tag(&ctx, date, name)
+}
+
+#[command]
+pub fn rename_tag_cmd(ctx: AppState<'_>, name: &str, new_name: &str) -> Result<String> {
+    services::rename_tag(&ctx, name, new_name)
+}
+
+#[command]
+pub fn merge_tags_cmd(ctx: AppState<'_>, sources: Vec<String>, target: &str) -> Result<TagMergeReport> {
+    services::merge_tags(&ctx, &sources, target)
+}
+
+#[command]
+pub fn delete_tag_cmd(ctx: AppState<'_>, name: &str) -> Result<()> {
+    services::delete_tag(&ctx, name)
+}
+
+#[command]
+pub fn tag_emotion_stats_cmd(ctx: AppState<'_>) -> Result<Vec<TagEmotionStats>> {
+    services::tag_emotion_stats(&ctx)
+}
+
+#[command]
+pub fn record_checkin_cmd(ctx: AppState<'_>, input: CheckinInput) -> Result<Checkin> {
+    services::record_checkin(&ctx, &input)
+}
+
+#[command]
+pub fn list_checkins_cmd(ctx: AppState<'_>, query: Option<CheckinQuery>) -> Result<Vec<Checkin>> {
+    services::list_checkins(&ctx, &query.unwrap_or_default())
+}
+
+#[command]
+pub fn delete_checkin_cmd(ctx: AppState<'_>, id: i64) -> Result<()> {
+    services::delete_checkin(&ctx, id)
+}
+
+// may load the emotion model, so it runs off the main thread
+#[command]
+pub async fn compare_checkins_cmd(ctx: AppState<'_>, query: Option<CheckinQuery>) -> Result<MoodComparisonReport> {
+    run_blocking(&ctx, "check-in comparison", move |ctx| services::compare_checkins(ctx, &query.unwrap_or_default())).await
+}
+
+// called on every edit; resolves to false when a newer save replaced this one
+#[command]
+pub async fn save_draft_cmd(ctx: AppState<'_>, slot: String, title: Option<String>, content: String) -> Result<bool> {
+    services::save_draft(&ctx, &slot, title.as_deref(), &content).await
+}
+
+#[command]
+pub fn load_draft_cmd(ctx: AppState<'_>, slot: &str) -> Result<Option<Draft>> {
+    services::load_draft(&ctx, slot)
+}
+
+#[command]
+pub fn discard_draft_cmd(ctx: AppState<'_>, slot: &str) -> Result<()> {
+    services::discard_draft(&ctx, slot)
+}
+
+// drafts left from before this launch, offered again after unlocking
+#[command]
+pub fn list_stale_drafts_cmd(ctx: AppState<'_>) -> Result<Vec<Draft>> {
+    services::list_stale_drafts(&ctx)
+}
+
+#[command]
+pub fn promote_draft_cmd(ctx: AppState<'_>, slot: &str) -> Result<String> {
+    services::promote_draft(&ctx, slot)
+}
+
+// moves the entry to the trash
+#[command]
+pub fn delete_entry(ctx: AppState<'_>, date: &str) -> Result<()> {
+    services::delete_entry(&ctx, date)
+}
+
+#[command]
+pub fn list_trash_cmd(ctx: AppState<'_>) -> Result<Trash> {
+    services::list_trash(&ctx)
+}
+
+#[command]
+pub fn restore_entry_cmd(ctx: AppState<'_>, date: &str) -> Result<()> {
+    services::restore_entry(&ctx, date)
+}
+
+#[command]
+pub fn purge_entry_cmd(ctx: AppState<'_>, date: &str) -> Result<Vec<String>> {
+    services::purge_entry(&ctx, date)
+}
+
+#[command]
+pub fn restore_chat_session_cmd(ctx: AppState<'_>, session_id: &str) -> Result<()> {
+    services::restore_chat_session(&ctx, session_id)
+}
+
+#[command]
+pub fn purge_chat_session_cmd(ctx: AppState<'_>, session_id: &str) -> Result<()> {
+    services::purge_chat_session(&ctx, session_id)
+}
+
+#[command]
+pub async fn empty_trash_cmd(ctx: AppState<'_>) -> Result<TrashPurgeReport> {
+    run_blocking(&ctx, "trash purge", services::empty_trash).await
+}
+
+#[command]
+pub fn list_attachments_cmd(ctx: AppState<'_>, date: &str) -> Result<Vec<Attachment>> {
+    services::list_attachments(&ctx, date)
+}
+
+#[command]
+pub async fn add_attachment_cmd(
+    ctx: AppState<'_>,
+    date: String,
+    file_data_base64: String,
+    original_file_name: String,
+) -> Result<Attachment> {
+    run_blocking(&ctx, "attachment upload", move |ctx| {
+        let file_bytes = BASE64_STANDARD.decode(&file_data_base64)
+            .map_err(|e| AppError::InvalidInput(format!("Invalid base64 file data: {}", e)))?;
+        services::add_attachment(ctx, &date, &file_bytes, &original_file_name)
+    }).await
+}
+
+#[command]
+pub fn remove_attachment_cmd(ctx: AppState<'_>, attachment_id: i64) -> Result<()> {
+    services::remove_attachment(&ctx, attachment_id)
+}
+
+// ordered_ids must contain every attachment id of the entry
+#[command]
+pub fn reorder_attachments_cmd(ctx: AppState<'_>, date: &str, ordered_ids: Vec<i64>) -> Result<()> {
+    services::reorder_attachments(&ctx, date, &ordered_ids)
+}
+
+#[command]
+pub async fn upload_image_file(
+    ctx: AppState<'_>,
+    file_data_base64: String,
+    original_file_name: String,
+) -> Result<String> {
+    run_blocking(&ctx, "image upload", move |ctx| {
+        let image_bytes = BASE64_STANDARD.decode(&file_data_base64)
+            .map_err(|e| AppError::InvalidInput(format!("Invalid base64 image data: {}", e)))?;
+        services::upload_image(ctx, &image_bytes, &original_file_name)
+    }).await
+}
+
+#[command]
+pub async fn get_image_thumbnail_cmd(ctx: AppState<'_>, relative_path: String) -> Result<String> {
+    run_blocking(&ctx, "thumbnail generation", move |ctx| services::get_image_thumbnail(ctx, &relative_path)).await
+}
+
+#[command]
+pub async fn reconcile_images_cmd(ctx: AppState<'_>, apply: bool) -> Result<ImageGcReport> {
+    run_blocking(&ctx, "image check", move |ctx| services::reconcile_images(ctx, apply)).await
+}
+
+#[command]
+pub async fn check_database_cmd(ctx: AppState<'_>, repair: bool) -> Result<IntegrityReport> {
+    run_blocking(&ctx, "database check", move |ctx| services::check_database(ctx, repair)).await
+}
+
+#[command]
+pub async fn export_backup_cmd(
+    ctx: AppState<'_>,
+    archive_path: String,
+    passphrase: Option<String>,
+) -> Result<BackupManifest> {
+    run_blocking(&ctx, "backup", move |ctx| {
+        services::export_backup(ctx, Path::new(&archive_path), passphrase.as_deref())
+    }).await
+}
+
+#[command]
+pub async fn read_backup_manifest_cmd(archive_path: String, passphrase: Option<String>) -> Result<BackupManifest> {
+    tokio::task::spawn_blocking(move || {
+        services::read_backup_manifest(Path::new(&archive_path), passphrase.as_deref())
+    })
+    .await
+    .map_err(|e| AppError::Internal(format!("Task join error while reading backup: {}", e)))?
+}
+
+#[command]
+pub async fn import_backup_cmd(
+    ctx: AppState<'_>,
+    archive_path: String,
+    passphrase: Option<String>,
+) -> Result<BackupManifest> {
+    run_blocking(&ctx, "restore", move |ctx| {
+        services::import_backup(ctx, Path::new(&archive_path), passphrase.as_deref())
+    }).await
+}
+
+#[command]
+pub async fn export_journal_cmd(
+    ctx: AppState<'_>,
+    format: ExportFormat,
+    output_path: String,
+    options: Option<ExportOptions>,
+) -> Result<ExportReport> {
+    run_blocking(&ctx, "export", move |ctx| {
+        services::export_journal(ctx, format, Path::new(&output_path), &options.unwrap_or_default())
+    }).await
+}
+
+#[command]
+pub async fn import_journal_cmd(
+    ctx: AppState<'_>,
+    source: ImportSource,
+    path: String,
+    options: Option<ImportOptions>,
+) -> Result<ImportReport> {
+    run_blocking(&ctx, "import", move |ctx| {
+        services::import_journal(ctx, source, Path::new(&path), &options.unwrap_or_default())
+    }).await
+}
+
+#[command]
+pub async fn export_dataset_cmd(ctx: AppState<'_>, output_path: String) -> Result<Dataset> {
+    run_blocking(&ctx, "dataset export", move |ctx| {
+        let output_path = Path::new(&output_path);
+        services::export_dataset(ctx, output_path, DatasetFileFormat::from_path(output_path))
+    }).await
+}
+
+#[command]
+pub async fn import_dataset_cmd(
+    ctx: AppState<'_>,
+    input_path: String,
+    options: Option<DatasetImportOptions>,
+) -> Result<DatasetImportReport> {
+    run_blocking(&ctx, "dataset import", move |ctx| {
+        services::import_dataset(ctx, Path::new(&input_path), &options.unwrap_or_default())
+    }).await
+}
+
+#[command]
+pub async fn list_snapshots_cmd(ctx: AppState<'_>) -> Result<Vec<SnapshotInfo>> {
+    services::list_snapshots(&ctx)
+}
+
+#[command]
+pub async fn create_snapshot_cmd(ctx: AppState<'_>) -> Result<SnapshotInfo> {
+    run_blocking(&ctx, "snapshot", services::create_snapshot).await
+}
+
+#[command]
+pub async fn restore_snapshot_cmd(ctx: AppState<'_>, file_name: String) -> Result<BackupManifest> {
+    run_blocking(&ctx, "restore", move |ctx| services::restore_snapshot(ctx, &file_name)).await
+}
+
+#[command]
+pub fn classify_emotion(ctx: AppState<'_>, text: String) -> Result<String> {
+    log::debug!("[CMD classify_emotion] Received text: \"{}\"", text);
+    services::classify_emotion(&ctx, &text).inspect_err(|e| log::error!("[CMD classify_emotion] {}", e))
+}
+
+#[command]
+pub async fn perform_dictation_cmd(
+    ctx: AppState<'_>,
+    audio_file_path: String,
+    options: Option<DictationOptions>,
+) -> Result<String> {
+    log::info!("[CMD perform_dictation_cmd] Received request for audio file: {}", audio_file_path);
+    run_blocking(&ctx, "transcription", move |ctx| {
+        services::transcribe(ctx, &audio_file_path, &options.unwrap_or_default())
+    }).await
+}
+
+// same as perform_dictation_cmd but also returns per-chunk timings and durations
+#[command]
+pub async fn perform_dictation_report_cmd(
+    ctx: AppState<'_>,
+    audio_file_path: String,
+    options: Option<DictationOptions>,
+) -> Result<DictationReport> {
+    log::info!("[CMD perform_dictation_report_cmd] Received request for audio file: {}", audio_file_path);
+    run_blocking(&ctx, "transcription", move |ctx| {
+        services::transcribe_with_report(ctx, &audio_file_path, &options.unwrap_or_default())
+    }).await
+}
+
+#[command]
+pub async fn generate_suggestion_cmd(
+    entry_title: Option<String>,
+    entry_content: Option<String>,
+) -> Result<String> {
+    services::generate_suggestion(entry_title.as_deref(), entry_content.as_deref())
+        .await
+}
+
+#[command]
+pub async fn chat_with_moodjourney_cmd(
+    ctx: AppState<'_>,
+    user_message: String,
+    session_id_option: Option<String>,
+) -> Result<ChatCompletionResponse> {
+    log::info!("[CMD chat_with_moodjourney_cmd] User: '{}', Session: {:?}", user_message, session_id_option);
+    services::chat(&ctx, &user_message, session_id_option)
+        .await
+}
+
+#[command]
+pub async fn load_chat_sessions(ctx: AppState<'_>) -> Result<Vec<ChatSession>> {
+    services::list_chat_sessions(&ctx)
+}
+
+#[command]
+pub async fn load_messages_for_session_cmd(ctx: AppState<'_>, session_id: String) -> Result<Vec<ChatMessage>> {
+    services::list_chat_messages(&ctx, &session_id)
+}
+
+#[command]
+pub async fn delete_chat_session_cmd(ctx: AppState<'_>, session_id: String) -> Result<()> {
+    services::delete_chat_session(&ctx, &session_id)
+}
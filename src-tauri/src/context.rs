@@ -0,0 +1,94 @@
+use crate::backup::BackupPaths;
+use crate::db::Database;
+use crate::dictation::DictationModel;
+use crate::drafts::DraftState;
+use crate::emotion::EmotionModel;
+use crate::error::{AppError, Result};
+use crate::password::PasswordState;
+use crate::settings::{self, AppSettings, SettingsState};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+pub const DB_FILE_NAME: &str = "entries.db";
+pub const PASSWORD_FILE_NAME: &str = "password.json";
+pub const SETTINGS_FILE_NAME: &str = "settings.json";
+
+pub struct SafeDictationModelWrapper(pub DictationModel);
+unsafe impl Send for SafeDictationModelWrapper {}
+unsafe impl Sync for SafeDictationModelWrapper {}
+
+pub struct SafeEmotionModelWrapper(pub EmotionModel);
+unsafe impl Send for SafeEmotionModelWrapper {}
+unsafe impl Sync for SafeEmotionModelWrapper {}
+
+// everything a service function needs: where the journal lives, the shared settings and PIN state, and the loaded models
+pub struct AppContext {
+    pub db: Database,
+    // images and attachments are stored relative to this directory
+    pub local_data_dir: PathBuf,
+    pub config_dir: PathBuf,
+    pub settings: Mutex<SettingsState>,
+    pub password: Mutex<PasswordState>,
+    pub drafts: DraftState,
+    emotion_model: Option<Arc<SafeEmotionModelWrapper>>,
+    dictation_model: Option<Arc<SafeDictationModelWrapper>>,
+}
+
+impl AppContext {
+    pub fn new(db: Database, local_data_dir: PathBuf, config_dir: PathBuf) -> Self {
+        let settings = SettingsState::load_from_path(config_dir.join(SETTINGS_FILE_NAME));
+        let password = PasswordState::load_from_path(config_dir.join(PASSWORD_FILE_NAME));
+        Self {
+            db,
+            local_data_dir,
+            config_dir,
+            settings: Mutex::new(settings),
+            password: Mutex::new(password),
+            drafts: DraftState::new(),
+            emotion_model: None,
+            dictation_model: None,
+        }
+    }
+
+    pub fn with_emotion_model(mut self, model: EmotionModel) -> Self {
+        self.emotion_model = Some(Arc::new(SafeEmotionModelWrapper(model)));
+        self
+    }
+
+    pub fn with_dictation_model(mut self, model: DictationModel) -> Self {
+        self.dictation_model = Some(Arc::new(SafeDictationModelWrapper(model)));
+        self
+    }
+
+    pub fn settings_path(&self) -> PathBuf {
+        self.config_dir.join(SETTINGS_FILE_NAME)
+    }
+
+    pub fn current_settings(&self) -> AppSettings {
+        settings::get_settings(&self.settings)
+    }
+
+    // re-reads settings.json after a restore replaced it on disk
+    pub fn reload_settings(&self) {
+        let mut settings_guard = self.settings.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        *settings_guard = SettingsState::load_from_path(self.settings_path());
+    }
+
+    pub fn backup_paths(&self) -> BackupPaths {
+        BackupPaths {
+            db_file_path: self.db.path().to_path_buf(),
+            app_local_data_dir: self.local_data_dir.clone(),
+            settings_file_path: Some(self.settings_path()),
+        }
+    }
+
+    pub fn emotion_model(&self) -> Result<Arc<SafeEmotionModelWrapper>> {
+        self.emotion_model.clone()
+            .ok_or_else(|| AppError::ModelUnavailable("The emotion model is not loaded.".to_string()))
+    }
+
+    pub fn dictation_model(&self) -> Result<Arc<SafeDictationModelWrapper>> {
+        self.dictation_model.clone()
+            .ok_or_else(|| AppError::ModelUnavailable("The dictation model is not loaded.".to_string()))
+    }
+}
@@ -0,0 +1,512 @@
+use crate::db::{self, Database};
+use crate::error::AppError;
+use crate::export;
+use crate::tags;
+use anyhow::{Context, Result, anyhow};
+use chrono::{NaiveDate, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+// see docs/dataset-format.md; bump DATASET_VERSION on any incompatible change
+pub const DATASET_FORMAT: &str = "moodjourney-dataset";
+pub const DATASET_VERSION: u32 = 1;
+pub const ENTRY_CSV_COLUMNS: [&str; 7] = ["date", "title", "content", "password", "image", "tags", "emotion"];
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DatasetEntry {
+    pub date: String,
+    pub title: Option<String>,
+    pub content: Option<String>,
+    pub password: Option<String>,
+    pub image: Option<String>,
+    // tags added by hand; tags from #hashtags are taken from the content again on import
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DatasetChatSession {
+    pub id: String,
+    pub created_at: String,
+    pub last_modified_at: String,
+    pub title: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DatasetChatMessage {
+    pub id: i64,
+    pub session_id: String,
+    pub sender: String,
+    pub content: String,
+    pub timestamp: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Dataset {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: String,
+    pub app_version: String,
+    #[serde(default)]
+    pub entries: Vec<DatasetEntry>,
+    #[serde(default)]
+    pub chat_sessions: Vec<DatasetChatSession>,
+    #[serde(default)]
+    pub chat_messages: Vec<DatasetChatMessage>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum DatasetFileFormat {
+    Json,
+    Csv,
+}
+
+impl DatasetFileFormat {
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("csv") => Self::Csv,
+            _ => Self::Json,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DatasetImportOptions {
+    // replace records whose date/id already exists instead of keeping the local copy
+    pub overwrite: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatasetImportReport {
+    pub entries_imported: usize,
+    pub entries_skipped: usize,
+    pub sessions_imported: usize,
+    pub sessions_skipped: usize,
+    pub messages_imported: usize,
+    pub messages_skipped: usize,
+    pub warnings: Vec<String>,
+}
+
+pub fn export_dataset(db: &Database) -> Result<Dataset> {
+    let conn = db.conn()?;
+
+    let mut stmt = conn.prepare("SELECT date, title, content, password, image FROM entries WHERE deleted_at IS NULL ORDER BY date ASC")?;
+    let mut entries = stmt.query_map([], |row| {
+        Ok(DatasetEntry {
+            date: row.get(0)?,
+            title: row.get(1)?,
+            content: row.get(2)?,
+            password: row.get(3)?,
+            image: row.get(4)?,
+            tags: Vec::new(),
+        })
+    })?.collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut stmt = conn.prepare("SELECT tags.name FROM entry_tags JOIN tags ON tags.id = entry_tags.tag_id
+         WHERE entry_tags.entry_date = ?1 AND entry_tags.source = 'manual' ORDER BY tags.name")?;
+    for entry in &mut entries {
+        entry.tags = stmt.query_map([&entry.date], |row| row.get(0))?.collect::<rusqlite::Result<Vec<_>>>()?;
+    }
+
+    let mut stmt = conn.prepare("SELECT id, created_at, last_modified_at, title FROM assistant_chat_sessions WHERE deleted_at IS NULL ORDER BY created_at ASC, id ASC")?;
+    let chat_sessions = stmt.query_map([], |row| {
+        Ok(DatasetChatSession {
+            id: row.get(0)?,
+            created_at: row.get(1)?,
+            last_modified_at: row.get(2)?,
+            title: row.get(3)?,
+        })
+    })?.collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut stmt = conn.prepare("SELECT id, session_id, sender, content, timestamp FROM assistant_chat_messages
+         WHERE session_id IN (SELECT id FROM assistant_chat_sessions WHERE deleted_at IS NULL) ORDER BY id ASC")?;
+    let chat_messages = stmt.query_map([], |row| {
+        Ok(DatasetChatMessage {
+            id: row.get(0)?,
+            session_id: row.get(1)?,
+            sender: row.get(2)?,
+            content: row.get(3)?,
+            timestamp: row.get(4)?,
+        })
+    })?.collect::<rusqlite::Result<Vec<_>>>()?;
+
+    Ok(Dataset {
+        format: DATASET_FORMAT.to_string(),
+        version: DATASET_VERSION,
+        exported_at: Utc::now().to_rfc3339(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        entries,
+        chat_sessions,
+        chat_messages,
+    })
+}
+
+pub fn parse_dataset_json(bytes: &[u8]) -> Result<Dataset> {
+    let dataset: Dataset = serde_json::from_slice(bytes)
+        .map_err(|e| AppError::InvalidFile(format!("The file is not a MoodJourney dataset: {}", e)))?;
+    if dataset.format != DATASET_FORMAT {
+        return Err(AppError::InvalidFile(format!("Expected format '{}', found '{}'.", DATASET_FORMAT, dataset.format)).into());
+    }
+    if dataset.version > DATASET_VERSION {
+        return Err(AppError::UnsupportedVersion(format!(
+            "The dataset uses version {}, but this version of MoodJourney only reads up to version {}.",
+            dataset.version,
+            DATASET_VERSION
+        )).into());
+    }
+    Ok(dataset)
+}
+
+// ---- CSV (entries only) ----
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+// RFC 4180 with a header row; emotion is derived from the content and ignored on import
+pub fn entries_to_csv(entries: &[DatasetEntry]) -> String {
+    let mut csv = ENTRY_CSV_COLUMNS.join(",");
+    csv.push_str("\r\n");
+    for entry in entries {
+        let (_, emotion, _) = export::parse_entry_content(entry.content.as_deref().unwrap_or(""));
+        let tags = entry.tags.join(",");
+        let fields = [
+            entry.date.as_str(),
+            entry.title.as_deref().unwrap_or(""),
+            entry.content.as_deref().unwrap_or(""),
+            entry.password.as_deref().unwrap_or(""),
+            entry.image.as_deref().unwrap_or(""),
+            tags.as_str(),
+            emotion.as_deref().unwrap_or(""),
+        ];
+        csv.push_str(&fields.iter().map(|f| csv_field(f)).collect::<Vec<_>>().join(","));
+        csv.push_str("\r\n");
+    }
+    csv
+}
+
+fn parse_csv_records(text: &str) -> Result<Vec<Vec<String>>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.trim_start_matches('\u{FEFF}').chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, in_quotes) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            ('"', true) => in_quotes = false,
+            ('"', false) if field.is_empty() => in_quotes = true,
+            (',', false) => record.push(std::mem::take(&mut field)),
+            ('\r', false) if chars.peek() == Some(&'\n') => {}
+            ('\n', false) => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            _ => field.push(c),
+        }
+    }
+    if in_quotes {
+        return Err(AppError::InvalidFile("The CSV file ends inside a quoted field.".to_string()).into());
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    Ok(records)
+}
+
+pub fn entries_from_csv(text: &str) -> Result<Vec<DatasetEntry>> {
+    let mut records = parse_csv_records(text)?.into_iter();
+    let header = records.next().ok_or_else(|| AppError::InvalidFile("The CSV file is empty.".to_string()))?;
+    let column = |name: &str| header.iter().position(|h| h.trim().eq_ignore_ascii_case(name));
+    let date_column = column("date").ok_or_else(|| AppError::InvalidFile("The CSV file has no 'date' column.".to_string()))?;
+    let (title_column, content_column, password_column, image_column) = (column("title"), column("content"), column("password"), column("image"));
+    let tags_column = column("tags");
+
+    let optional = |record: &[String], index: Option<usize>| {
+        index.and_then(|i| record.get(i)).filter(|v| !v.is_empty()).cloned()
+    };
+    Ok(records
+        .filter(|record| record.iter().any(|f| !f.is_empty()))
+        .map(|record| DatasetEntry {
+            date: record.get(date_column).cloned().unwrap_or_default(),
+            title: optional(&record, title_column),
+            content: optional(&record, content_column),
+            password: optional(&record, password_column),
+            image: optional(&record, image_column),
+            tags: optional(&record, tags_column)
+                .map(|tags| tags.split(',').map(str::trim).filter(|t| !t.is_empty()).map(str::to_string).collect())
+                .unwrap_or_default(),
+        })
+        .collect())
+}
+
+pub fn write_dataset(db: &Database, output_path: &Path, format: DatasetFileFormat) -> Result<Dataset> {
+    let dataset = export_dataset(db)?;
+    let bytes = match format {
+        DatasetFileFormat::Json => serde_json::to_vec_pretty(&dataset)?,
+        DatasetFileFormat::Csv => entries_to_csv(&dataset.entries).into_bytes(),
+    };
+    let partial_path = output_path.with_extension("partial");
+    fs::write(&partial_path, &bytes).map_err(|e| anyhow!("Failed to write dataset to {:?}: {}", partial_path, e))?;
+    fs::rename(&partial_path, output_path).map_err(|e| anyhow!("Failed to finalize dataset at {:?}: {}", output_path, e))?;
+    Ok(dataset)
+}
+
+pub fn read_dataset(input_path: &Path, format: DatasetFileFormat) -> Result<Dataset> {
+    let bytes = fs::read(input_path).with_context(|| format!("Failed to read dataset {:?}", input_path))?;
+    match format {
+        DatasetFileFormat::Json => parse_dataset_json(&bytes),
+        DatasetFileFormat::Csv => Ok(Dataset {
+            format: DATASET_FORMAT.to_string(),
+            version: DATASET_VERSION,
+            exported_at: String::new(),
+            app_version: String::new(),
+            entries: entries_from_csv(&String::from_utf8_lossy(&bytes))?,
+            chat_sessions: Vec::new(),
+            chat_messages: Vec::new(),
+        }),
+    }
+}
+
+fn exists(conn: &Connection, sql: &str, key: &dyn rusqlite::ToSql) -> rusqlite::Result<bool> {
+    Ok(conn.query_row(sql, [key], |_| Ok(())).optional()?.is_some())
+}
+
+// inserts every record with its original date/id and timestamps in a single transaction;
+// existing records are kept unless overwrite is set, in which case they are updated in place
+pub fn import_dataset(db: &Database, dataset: &Dataset, options: &DatasetImportOptions) -> Result<DatasetImportReport> {
+    let mut conn = db.conn()?;
+    let tx = conn.transaction()?;
+    let mut report = DatasetImportReport::default();
+
+    for entry in &dataset.entries {
+        if NaiveDate::parse_from_str(&entry.date, "%Y-%m-%d").is_err() {
+            report.warnings.push(format!("Entry with invalid date '{}' was skipped.", entry.date));
+            report.entries_skipped += 1;
+            continue;
+        }
+        let title = entry.title.clone().unwrap_or_default();
+        if exists(&tx, "SELECT 1 FROM entries WHERE date = ?1", &entry.date)? {
+            if !options.overwrite {
+                report.entries_skipped += 1;
+                continue;
+            }
+            // overwriting a trashed entry brings it back
+            tx.execute(
+                "UPDATE entries SET title = ?1, content = ?2, password = ?3, image = ?4, deleted_at = NULL WHERE date = ?5",
+                params![title, entry.content, entry.password, entry.image, entry.date],
+            )?;
+        } else {
+            tx.execute(
+                "INSERT INTO entries (date, title, content, password, image) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![entry.date, title, entry.content, entry.password, entry.image],
+            )?;
+        }
+        db::sync_hashtag_tags(&tx, &entry.date, entry.content.as_deref())?;
+        for tag in &entry.tags {
+            match tags::normalize_tag_name(tag) {
+                Ok(name) => { db::insert_manual_tag(&tx, &entry.date, &name)?; }
+                Err(_) => report.warnings.push(format!("Tag '{}' of entry {} is not a valid tag name and was skipped.", tag, entry.date)),
+            }
+        }
+        report.entries_imported += 1;
+    }
+
+    for session in &dataset.chat_sessions {
+        if exists(&tx, "SELECT 1 FROM assistant_chat_sessions WHERE id = ?1", &session.id)? {
+            if !options.overwrite {
+                report.sessions_skipped += 1;
+                continue;
+            }
+            tx.execute(
+                "UPDATE assistant_chat_sessions SET created_at = ?1, last_modified_at = ?2, title = ?3, deleted_at = NULL WHERE id = ?4",
+                params![session.created_at, session.last_modified_at, session.title, session.id],
+            )?;
+        } else {
+            tx.execute(
+                "INSERT INTO assistant_chat_sessions (id, created_at, last_modified_at, title) VALUES (?1, ?2, ?3, ?4)",
+                params![session.id, session.created_at, session.last_modified_at, session.title],
+            )?;
+        }
+        report.sessions_imported += 1;
+    }
+
+    for message in &dataset.chat_messages {
+        if !exists(&tx, "SELECT 1 FROM assistant_chat_sessions WHERE id = ?1", &message.session_id)? {
+            report.warnings.push(format!("Message {} belongs to unknown session {} and was skipped.", message.id, message.session_id));
+            report.messages_skipped += 1;
+            continue;
+        }
+        if exists(&tx, "SELECT 1 FROM assistant_chat_messages WHERE id = ?1", &message.id)? {
+            if !options.overwrite {
+                report.messages_skipped += 1;
+                continue;
+            }
+            tx.execute(
+                "UPDATE assistant_chat_messages SET session_id = ?1, sender = ?2, content = ?3, timestamp = ?4 WHERE id = ?5",
+                params![message.session_id, message.sender, message.content, message.timestamp, message.id],
+            )?;
+        } else {
+            tx.execute(
+                "INSERT INTO assistant_chat_messages (id, session_id, sender, content, timestamp) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![message.id, message.session_id, message.sender, message.content, message.timestamp],
+            )?;
+        }
+        report.messages_imported += 1;
+    }
+
+    tx.commit()?;
+    log::info!(
+        "[dataset] Imported {} entries, {} chat sessions and {} messages ({} / {} / {} skipped).",
+        report.entries_imported, report.sessions_imported, report.messages_imported,
+        report.entries_skipped, report.sessions_skipped, report.messages_skipped
+    );
+    Ok(report)
+}
+
+pub fn import_dataset_file(db: &Database, input_path: &Path, format: DatasetFileFormat, options: &DatasetImportOptions) -> Result<DatasetImportReport> {
+    let dataset = read_dataset(input_path, format)?;
+    import_dataset(db, &dataset, options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Entry;
+    use std::path::PathBuf;
+
+    fn get_test_dir(test_name: &str) -> PathBuf {
+        let mut path = std::env::temp_dir();
+        path.push("moodjourney_dataset_tests");
+        path.push(test_name);
+        if path.exists() {
+            let _ = fs::remove_dir_all(&path);
+        }
+        fs::create_dir_all(&path).expect("Failed to create temporary test directory for datasets");
+        path
+    }
+
+    fn populated_db(dir: &Path) -> Database {
+        let db = Database::open(&dir.join("source.db")).expect("init failed");
+        db.add_entry(Entry {
+            date: "2025-02-01".to_string(),
+            title: Some("Comma, \"quotes\"".to_string()),
+            content: Some("Line one #garden\nLine two\n\n🧠 Emotion: joy".to_string()),
+            password: Some("pw".to_string()),
+            image: Some("journal_images/a.png".to_string()),
+        }).expect("add failed");
+        db.add_entry(Entry {
+            date: "2025-02-02".to_string(),
+            title: Some("Plain".to_string()),
+            content: None,
+            password: None,
+            image: None,
+        }).expect("add failed");
+        db.add_entry_tag("2025-02-02", "reading").expect("tag failed");
+
+        let session_id = db.create_new_chat_session().expect("session failed");
+        for content in ["first", "second", "third"] {
+            db.save_chat_message(&session_id, "user", content).expect("message failed");
+        }
+        // leave a gap in the message ids so the round trip has to preserve them
+        db.conn().unwrap().execute("DELETE FROM assistant_chat_messages WHERE content = 'second'", []).unwrap();
+        db
+    }
+
+    #[test]
+    fn test_json_round_trip_preserves_ids_and_timestamps() {
+        let dir = get_test_dir("json_round_trip");
+        let source_db = populated_db(&dir);
+        let json_path = dir.join("dataset.json");
+        let exported = write_dataset(&source_db, &json_path, DatasetFileFormat::Json).expect("export failed");
+        assert_eq!(exported.chat_messages.iter().map(|m| m.id).collect::<Vec<_>>(), vec![1, 3]);
+
+        let target_db = Database::open(&dir.join("target.db")).expect("init failed");
+        let report = import_dataset_file(&target_db, &json_path, DatasetFileFormat::Json, &DatasetImportOptions::default()).expect("import failed");
+        assert_eq!((report.entries_imported, report.sessions_imported, report.messages_imported), (2, 1, 2));
+        assert_eq!(exported.entries[1].tags, vec!["reading"]);
+        assert!(target_db.get_entry_tags("2025-02-01").expect("tags failed").is_empty(), "A protected entry should not get the tags of its hashtags.");
+
+        let reimported = export_dataset(&target_db).expect("re-export failed");
+        assert_eq!(reimported.entries, exported.entries);
+        assert_eq!(reimported.chat_sessions, exported.chat_sessions);
+        assert_eq!(reimported.chat_messages, exported.chat_messages);
+
+        let again = import_dataset_file(&target_db, &json_path, DatasetFileFormat::Json, &DatasetImportOptions::default()).expect("second import failed");
+        assert_eq!((again.entries_skipped, again.sessions_skipped, again.messages_skipped), (2, 1, 2));
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_overwrite_updates_in_place_and_orphans_are_reported() {
+        let dir = get_test_dir("overwrite");
+        let db = populated_db(&dir);
+        let mut dataset = export_dataset(&db).expect("export failed");
+        dataset.entries[1].title = Some("Changed".to_string());
+        dataset.chat_messages.push(DatasetChatMessage {
+            id: 99,
+            session_id: "missing-session".to_string(),
+            sender: "user".to_string(),
+            content: "orphan".to_string(),
+            timestamp: "2025-01-01T00:00:00+00:00".to_string(),
+        });
+
+        let report = import_dataset(&db, &dataset, &DatasetImportOptions { overwrite: true }).expect("import failed");
+        assert_eq!(report.entries_imported, 2);
+        assert_eq!(report.messages_skipped, 1);
+        assert!(report.warnings[0].contains("missing-session"));
+        let changed = db.get_entry_by_date("2025-02-02").expect("get failed").expect("entry missing");
+        assert_eq!(changed.title.as_deref(), Some("Changed"));
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_csv_round_trip_and_derived_emotion() {
+        let dir = get_test_dir("csv");
+        let source_db = populated_db(&dir);
+        let csv_path = dir.join("entries.csv");
+        let exported = write_dataset(&source_db, &csv_path, DatasetFileFormat::from_path(&csv_path)).expect("export failed");
+
+        let csv = fs::read_to_string(&csv_path).expect("csv missing");
+        assert!(csv.starts_with("date,title,content,password,image,tags,emotion\r\n"));
+        assert!(csv.contains("\"Comma, \"\"quotes\"\"\""));
+        assert!(csv.lines().nth(1).is_some_and(|l| l.starts_with("2025-02-01,")));
+        assert!(csv.contains(",joy\r\n"));
+
+        let parsed = entries_from_csv(&csv).expect("parse failed");
+        assert_eq!(parsed, exported.entries);
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_rejects_foreign_and_newer_files() {
+        assert_eq!(AppError::code_of(&parse_dataset_json(b"{\"entries\": []}").unwrap_err()), "INVALID_FILE");
+        let newer = format!(
+            "{{\"format\":\"{}\",\"version\":{},\"exportedAt\":\"\",\"appVersion\":\"\"}}",
+            DATASET_FORMAT,
+            DATASET_VERSION + 1
+        );
+        assert_eq!(AppError::code_of(&parse_dataset_json(newer.as_bytes()).unwrap_err()), "UNSUPPORTED_VERSION");
+    }
+}
@@ -1,13 +1,17 @@
-use rusqlite::{params, Connection, Result};
+use crate::error::{AppError, Result};
+use crate::tags;
+use rusqlite::{params, Connection};
 use rusqlite::OptionalExtension;
+use rusqlite::types::Value;
 use rusqlite::{Error as RusqliteError};       
 use rusqlite::ffi;                   
 use serde::{Deserialize, Serialize};
-use chrono::Local;
 use chrono::NaiveDate;
 use chrono::Utc;
-use std::path::Path;
-
+use std::ops::{Deref, DerefMut};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Entry {
     pub date: String,
@@ -17,6 +21,150 @@ pub struct Entry {
     pub image: Option<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Attachment {
+    pub id: i64,
+    pub entry_date: String,
+    pub kind: String,
+    pub relative_path: String,
+    pub mime: Option<String>,
+    pub size: i64,
+    pub checksum: Option<String>,
+    pub position: i64,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NewAttachment {
+    pub kind: String,
+    pub relative_path: String,
+    pub mime: Option<String>,
+    pub size: i64,
+    pub checksum: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SortOrder {
+    #[default]
+    NewestFirst,
+    OldestFirst,
+}
+
+// filters for list_entry_rows; dates are inclusive YYYY-MM-DD and the cursor is the date of the
+// last entry on the previous page
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct EntryQuery {
+    pub cursor: Option<String>,
+    pub limit: Option<usize>,
+    pub from_date: Option<String>,
+    pub to_date: Option<String>,
+    // case-insensitive; empty keeps every entry
+    pub emotions: Vec<String>,
+    // Some(true) keeps entries with a cover image or image attachment, Some(false) those without
+    pub has_image: Option<bool>,
+    // entries must carry every one of these tags; protected entries never match
+    pub tags: Vec<String>,
+    pub order: SortOrder,
+}
+
+// the columns a listing needs; image data and the password itself are never read
+#[derive(Debug, Clone)]
+pub struct EntryListRow {
+    pub date: String,
+    pub title: Option<String>,
+    pub content: Option<String>,
+    pub protected: bool,
+    pub has_image: bool,
+    pub tags: Vec<String>,
+}
+
+// an earlier title and content of an entry; created_at is when it was replaced
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EntryRevision {
+    pub id: i64,
+    pub entry_date: String,
+    pub title: Option<String>,
+    pub content: Option<String>,
+    pub created_at: String,
+}
+
+// an entry in the trash; its attachments and image files stay until it is purged
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashedEntry {
+    pub date: String,
+    pub title: Option<String>,
+    pub image: Option<String>,
+    pub deleted_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashedChatSession {
+    pub id: String,
+    pub title: Option<String>,
+    pub deleted_at: String,
+}
+
+// unsaved text autosaved while an entry is written; slot is an entry date or drafts::NEW_ENTRY_SLOT
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Draft {
+    pub slot: String,
+    pub title: Option<String>,
+    pub content: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Tag {
+    pub id: i64,
+    pub name: String,
+    // live entries carrying the tag, trashed ones are not counted
+    pub entry_count: usize,
+}
+
+// one row of the per-tag emotion breakdown; emotion is None for entries that were never classified
+#[derive(Debug, Clone)]
+pub struct TagEmotionRow {
+    pub tag: String,
+    pub emotion: Option<String>,
+    pub count: usize,
+}
+
+// a self-reported mood check-in, recorded apart from the entries
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Checkin {
+    pub id: i64,
+    // RFC 3339 in UTC, one check-in per instant
+    pub recorded_at: String,
+    // the day in the time zone it was recorded in; the entry of that day is the one it is compared with
+    pub date: String,
+    pub mood: u8,
+    pub emotions: Vec<String>,
+    pub energy: Option<u8>,
+    pub sleep_hours: Option<f64>,
+    pub note: Option<String>,
+}
+
+// validated by checkins::record_checkin before it gets here
+#[derive(Debug, Clone)]
+pub struct NewCheckin {
+    pub recorded_at: String,
+    pub date: String,
+    pub mood: u8,
+    pub emotions: Vec<String>,
+    pub energy: Option<u8>,
+    pub sleep_hours: Option<f64>,
+    pub note: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ChatSession {
     pub id: String,
@@ -34,8 +182,73 @@ pub struct ChatMessage {
     pub timestamp: String,
 }
 
-pub fn init_db_at_path(db_file_path: &Path) -> Result<()> {
+// bump whenever init_schema changes the schema; stored in PRAGMA user_version
+pub const SCHEMA_VERSION: i64 = 8;
+
+// the emotion word written after the marker export::EMOTION_MARKER, lowercased; a virtual column so
+// every writer (imports, dataset restores) keeps it in sync without extra work
+const EMOTION_COLUMN_SQL: &str = "ALTER TABLE entries ADD COLUMN emotion TEXT GENERATED ALWAYS AS (
+    CASE WHEN instr(content, char(10, 10) || '🧠 Emotion: ') > 0 THEN lower(trim(substr(
+        substr(content, instr(content, char(10, 10) || '🧠 Emotion: ') + 13),
+        1,
+        instr(substr(content, instr(content, char(10, 10) || '🧠 Emotion: ') + 13) || char(10), char(10)) - 1
+    ))) END
+) VIRTUAL";
+
+// idle connections kept open for reuse; more are opened on demand and closed when returned to a full pool
+const MAX_IDLE_CONNECTIONS: usize = 4;
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+const STATEMENT_CACHE_CAPACITY: usize = 64;
+
+// one per database file, shared by every command; connections run in WAL mode with foreign keys enforced
+pub struct Database {
+    path: PathBuf,
+    idle_connections: Mutex<Vec<Connection>>,
+}
+
+// hands the connection back to the pool on drop
+pub struct PooledConnection<'a> {
+    database: &'a Database,
+    conn: Option<Connection>,
+}
+
+impl Deref for PooledConnection<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        self.conn.as_ref().expect("pooled connection used after release")
+    }
+}
+
+impl DerefMut for PooledConnection<'_> {
+    fn deref_mut(&mut self) -> &mut Connection {
+        self.conn.as_mut().expect("pooled connection used after release")
+    }
+}
+
+impl Drop for PooledConnection<'_> {
+    fn drop(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            let mut idle = self.database.idle_connections.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            if idle.len() < MAX_IDLE_CONNECTIONS {
+                idle.push(conn);
+            }
+        }
+    }
+}
+
+fn open_connection(db_file_path: &Path) -> Result<Connection> {
     let conn = Connection::open(db_file_path)?;
+    conn.busy_timeout(BUSY_TIMEOUT)?;
+    conn.pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get::<_, String>(0))?;
+    conn.pragma_update(None, "synchronous", "NORMAL")?;
+    conn.pragma_update(None, "foreign_keys", "ON")?;
+    conn.set_prepared_statement_cache_capacity(STATEMENT_CACHE_CAPACITY);
+    Ok(conn)
+}
+
+fn init_schema(conn: &Connection) -> Result<()> {
+    let previous_version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
     conn.execute(
         "CREATE TABLE IF NOT EXISTS entries (
             date TEXT NOT NULL PRIMARY KEY, -- Added PRIMARY KEY for date consistency
@@ -66,247 +279,945 @@ pub fn init_db_at_path(db_file_path: &Path) -> Result<()> {
         )",
         [],
     )?;
-    Ok(())
-}
-
-// DO NOT USE THIS FUNCTION
-#[allow(dead_code)]
-pub fn init_db() -> Result<()> {
-    let default_db_path = Path::new("entries.db"); 
-    init_db_at_path(default_db_path)
-}
-
-pub fn add_entry_to_db(db_file_path: &Path, entry: Entry) -> Result<()> {
-    if NaiveDate::parse_from_str(&entry.date, "%Y-%m-%d").is_err() {
-        return Err(RusqliteError::SqliteFailure(
-            ffi::Error {
-                code: ffi::ErrorCode::ConstraintViolation,
-                extended_code: ffi::ErrorCode::ConstraintViolation as i32,
-            },
-            Some("Date must be a valid YYYY-MM-DD format".to_string()),
-        ));
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS attachments (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            entry_date TEXT NOT NULL,
+            kind TEXT NOT NULL,
+            relative_path TEXT NOT NULL,
+            mime TEXT,
+            size INTEGER NOT NULL DEFAULT 0,
+            checksum TEXT,
+            position INTEGER NOT NULL DEFAULT 0,
+            created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (entry_date) REFERENCES entries(date) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_attachments_entry_date ON attachments(entry_date, position)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS entry_revisions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            entry_date TEXT NOT NULL,
+            title TEXT,
+            content TEXT,
+            created_at TEXT NOT NULL,
+            FOREIGN KEY (entry_date) REFERENCES entries(date) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_entry_revisions_entry_date ON entry_revisions(entry_date, id)",
+        [],
+    )?;
+    if !has_column(conn, "entries", "emotion")? {
+        conn.execute(EMOTION_COLUMN_SQL, [])?;
     }
-
-    let fetched = get_entry_by_date_from_db(db_file_path, entry.date.as_str())?;
-    if fetched.is_some() {
-        return Err(RusqliteError::SqliteFailure(
-            ffi::Error {
-                code: ffi::ErrorCode::ConstraintViolation,
-                extended_code: ffi::ErrorCode::ConstraintViolation as i32, 
-            },
-            Some("Entry with this date already exists".to_string()),
-        ));
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_entries_emotion_date ON entries(emotion, date)",
+        [],
+    )?;
+    // set when an entry or session is moved to the trash; NULL for live rows
+    if !has_column(conn, "entries", "deleted_at")? {
+        conn.execute("ALTER TABLE entries ADD COLUMN deleted_at TEXT", [])?;
+    }
+    if !has_column(conn, "assistant_chat_sessions", "deleted_at")? {
+        conn.execute("ALTER TABLE assistant_chat_sessions ADD COLUMN deleted_at TEXT", [])?;
     }
-
-    let conn = Connection::open(db_file_path)?;
     conn.execute(
-        "INSERT INTO entries (date, title, content, password, image) VALUES (?1, ?2, ?3, ?4, ?5)",
-        params![entry.date, entry.title.as_ref(), entry.content, entry.password, entry.image],
+        "CREATE INDEX IF NOT EXISTS idx_entries_deleted_at ON entries(deleted_at)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_chat_sessions_deleted_at ON assistant_chat_sessions(deleted_at)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS drafts (
+            slot TEXT PRIMARY KEY,
+            title TEXT,
+            content TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+    // names are stored normalized by tags::normalize_tag_name
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS tags (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE,
+            created_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+    // source is 'manual' for tags added by hand and 'hashtag' for tags taken from the entry text
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS entry_tags (
+            entry_date TEXT NOT NULL,
+            tag_id INTEGER NOT NULL,
+            source TEXT NOT NULL DEFAULT 'manual',
+            PRIMARY KEY (entry_date, tag_id),
+            FOREIGN KEY (entry_date) REFERENCES entries(date) ON DELETE CASCADE,
+            FOREIGN KEY (tag_id) REFERENCES tags(id) ON DELETE CASCADE
+        )",
+        [],
     )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_entry_tags_tag_id ON entry_tags(tag_id, entry_date)",
+        [],
+    )?;
+    // emotions holds the chosen labels joined with commas
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS checkins (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            recorded_at TEXT NOT NULL UNIQUE,
+            date TEXT NOT NULL,
+            mood INTEGER NOT NULL CHECK (mood BETWEEN 1 AND 10),
+            emotions TEXT NOT NULL DEFAULT '',
+            energy INTEGER CHECK (energy BETWEEN 1 AND 10),
+            sleep_hours REAL CHECK (sleep_hours BETWEEN 0 AND 24),
+            note TEXT,
+            created_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_checkins_date ON checkins(date, recorded_at)",
+        [],
+    )?;
+    // entries written before tags existed get the tags of their #hashtags once
+    if previous_version < 6 {
+        let mut stmt = conn.prepare("SELECT date, content FROM entries")?;
+        let entries = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?)))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        let tx = conn.unchecked_transaction()?;
+        for (date, content) in entries {
+            sync_hashtag_tags(&tx, &date, content.as_deref())?;
+        }
+        tx.commit()?;
+    }
+    // versions 6 and 7 also took the hashtags of password protected entries; the tags left without
+    // entries afterwards are dropped with them
+    if previous_version == 6 || previous_version == 7 {
+        conn.execute(
+            "DELETE FROM entry_tags WHERE source = 'hashtag'
+             AND entry_date IN (SELECT date FROM entries WHERE password IS NOT NULL AND password != '')",
+            [],
+        )?;
+        conn.execute("DELETE FROM tags WHERE NOT EXISTS (SELECT 1 FROM entry_tags WHERE tag_id = tags.id)", [])?;
+    }
+    conn.pragma_update(None, "user_version", SCHEMA_VERSION)?;
     Ok(())
 }
 
-pub fn get_entries_from_db(db_file_path: &Path) -> Result<Vec<Entry>> {
-    let conn = Connection::open(db_file_path)?;
-    let mut stmt = conn.prepare("SELECT date, title, content, password, image FROM entries ORDER BY date DESC")?;
-    let entry_iter = stmt.query_map([], |row| {
-        Ok(Entry {
-            date: row.get(0)?,
-            title: row.get(1).optional()?,
-            content: row.get(2)?,
-            password: row.get(3)?,
-            image: row.get(4)?,
-        })
-    })?;
+// generated columns only show up in table_xinfo, so that is the one to ask
+fn has_column(conn: &Connection, table: &str, column: &str) -> rusqlite::Result<bool> {
+    conn.query_row(
+        "SELECT COUNT(*) > 0 FROM pragma_table_xinfo(?1) WHERE name = ?2",
+        params![table, column],
+        |row| row.get(0),
+    )
+}
 
-    let mut entries = Vec::new();
-    for entry_result in entry_iter {
-        entries.push(entry_result?);
+// keeps the tags taken from #hashtags in step with the entry text; tags added by hand are left alone.
+// The text of a password protected entry is not read for hashtags, so its tags never give it away.
+// Run after the entry row is written. Public for writers that use Database::conn directly, like the dataset import.
+pub fn sync_hashtag_tags(conn: &Connection, date: &str, content: Option<&str>) -> rusqlite::Result<()> {
+    let previous_tag_ids = conn.prepare_cached("SELECT tag_id FROM entry_tags WHERE entry_date = ?1 AND source = 'hashtag'")?
+        .query_map([date], |row| row.get::<_, i64>(0))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    conn.prepare_cached("DELETE FROM entry_tags WHERE entry_date = ?1 AND source = 'hashtag'")?.execute([date])?;
+    let protected: bool = conn.prepare_cached("SELECT COALESCE(password, '') != '' FROM entries WHERE date = ?1")?
+        .query_row([date], |row| row.get(0))
+        .optional()?
+        .unwrap_or(false);
+    if protected {
+        // a tag left without entries would still show the hashtag in the tag list
+        for tag_id in previous_tag_ids {
+            conn.prepare_cached("DELETE FROM tags WHERE id = ?1 AND NOT EXISTS (SELECT 1 FROM entry_tags WHERE tag_id = ?1)")?
+                .execute([tag_id])?;
+        }
+        return Ok(());
     }
-    Ok(entries)
+    let now = Utc::now().to_rfc3339();
+    for name in tags::extract_hashtags(content.unwrap_or("")) {
+        conn.prepare_cached("INSERT OR IGNORE INTO tags (name, created_at) VALUES (?1, ?2)")?.execute(params![name, now])?;
+        conn.prepare_cached(
+            "INSERT OR IGNORE INTO entry_tags (entry_date, tag_id, source) SELECT ?1, id, 'hashtag' FROM tags WHERE name = ?2",
+        )?.execute(params![date, name])?;
+    }
+    Ok(())
 }
 
-pub fn get_entry_by_date_from_db(db_file_path: &Path, date: &str) -> Result<Option<Entry>> {
-    let conn = Connection::open(db_file_path)?;
-    let mut stmt = conn.prepare("SELECT date, title, content, password, image FROM entries WHERE date = ?1")?;
-    let entry = stmt.query_row([date], |row| {
-        Ok(Entry {
-            date: row.get(0)?,
-            title: row.get(1).optional()?,
-            content: row.get(2)?,
-            password: row.get(3)?,
-            image: row.get(4)?,
-        })
-    }).optional()?;
-    Ok(entry)
+// creates the tag when needed; a tag the entry had from a hashtag becomes a manual one,
+// so it stays when the hashtag is removed. Returns false when it already was a manual tag of the entry.
+pub fn insert_manual_tag(conn: &Connection, date: &str, name: &str) -> rusqlite::Result<bool> {
+    conn.prepare_cached("INSERT OR IGNORE INTO tags (name, created_at) VALUES (?1, ?2)")?
+        .execute(params![name, Utc::now().to_rfc3339()])?;
+    Ok(conn.prepare_cached(
+        "INSERT INTO entry_tags (entry_date, tag_id, source) SELECT ?1, id, 'manual' FROM tags WHERE name = ?2
+         ON CONFLICT(entry_date, tag_id) DO UPDATE SET source = 'manual' WHERE source != 'manual'",
+    )?.execute(params![date, name])? > 0)
 }
 
-pub fn update_entry_by_date_in_db(db_file_path: &Path, date: &str, new_title: Option<&str>, new_content: Option<&str>, new_password: Option<&str>, new_image: Option<&str>) -> Result<()> {
-    let conn = Connection::open(db_file_path)?;
-    conn.execute(
-        "UPDATE entries SET title = ?1, content = ?2, password = ?3, image = ?4 WHERE date = ?5",
-        params![new_title, new_content, new_password, new_image, date],
-    )?;
-    Ok(())
+const CHECKIN_COLUMNS: &str = "id, recorded_at, date, mood, emotions, energy, sleep_hours, note";
+
+fn row_to_checkin(row: &rusqlite::Row) -> rusqlite::Result<Checkin> {
+    let emotions: String = row.get(4)?;
+    Ok(Checkin {
+        id: row.get(0)?,
+        recorded_at: row.get(1)?,
+        date: row.get(2)?,
+        mood: row.get(3)?,
+        emotions: emotions.split(',').filter(|e| !e.is_empty()).map(str::to_string).collect(),
+        energy: row.get(5)?,
+        sleep_hours: row.get(6)?,
+        note: row.get(7)?,
+    })
 }
 
-pub fn delete_entry_by_date_from_db(db_file_path: &Path, date: &str) -> Result<()> {
-    let conn = Connection::open(db_file_path)?;
-    conn.execute("DELETE FROM entries WHERE date = ?1", [date])?;
-    Ok(())
+// the row writes behind add_entry, update_entry_by_date and add_attachment, public for writers that
+// need several of them in one transaction, like the journal import
+pub fn insert_entry_row(conn: &Connection, entry: &Entry) -> rusqlite::Result<()> {
+    conn.prepare_cached(
+        "INSERT INTO entries (date, title, content, password, image) VALUES (?1, ?2, ?3, ?4, ?5)",
+    )?.execute(params![entry.date, entry.title.as_ref(), entry.content, entry.password, entry.image])?;
+    sync_hashtag_tags(conn, &entry.date, entry.content.as_deref())
 }
 
-pub fn create_entry_with_now_in_db(db_file_path: &Path, title: &str, content: Option<&str>, password: Option<&str>, image: Option<&str>) -> Result<()> {
-    let date = Local::now().format("%Y-%m-%d").to_string();
-    let entry = Entry {
-        date,
-        title: Some(title.to_string()),
-        content: content.map(|s| s.to_string()),
-        password: password.map(|s| s.to_string()),
-        image: image.map(|s| s.to_string()),
-    };
-    add_entry_to_db(db_file_path, entry)
+// keeps the replaced title and content as a revision when either of them changes; returns the rows updated
+pub fn update_entry_row(conn: &Connection, date: &str, new_title: Option<&str>, new_content: Option<&str>, new_password: Option<&str>, new_image: Option<&str>) -> rusqlite::Result<usize> {
+    conn.prepare_cached(
+        "INSERT INTO entry_revisions (entry_date, title, content, created_at)
+         SELECT date, title, content, ?4 FROM entries
+         WHERE date = ?1 AND deleted_at IS NULL AND (title IS NOT ?2 OR content IS NOT ?3)",
+    )?.execute(params![date, new_title, new_content, Utc::now().to_rfc3339()])?;
+    let updated = conn.prepare_cached(
+        "UPDATE entries SET title = ?1, content = ?2, password = ?3, image = ?4 WHERE date = ?5 AND deleted_at IS NULL",
+    )?.execute(params![new_title, new_content, new_password, new_image, date])?;
+    if updated > 0 {
+        sync_hashtag_tags(conn, date, new_content)?;
+    }
+    Ok(updated)
 }
 
-#[allow(dead_code)]
-pub fn add_entry(entry: Entry) -> Result<()> {
-    add_entry_to_db(Path::new("entries.db"), entry)
+// appended after the entry's other attachments; returns the new id
+pub fn insert_attachment_row(conn: &Connection, entry_date: &str, attachment: &NewAttachment) -> rusqlite::Result<i64> {
+    conn.prepare_cached(
+        "INSERT INTO attachments (entry_date, kind, relative_path, mime, size, checksum, position, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, (SELECT COALESCE(MAX(position) + 1, 0) FROM attachments WHERE entry_date = ?1), ?7)",
+    )?.execute(params![entry_date, attachment.kind, attachment.relative_path, attachment.mime, attachment.size, attachment.checksum, Utc::now().to_rfc3339()])?;
+    Ok(conn.last_insert_rowid())
 }
 
-#[allow(dead_code)]
-pub fn get_entries() -> Result<Vec<Entry>> {
-    get_entries_from_db(Path::new("entries.db"))
+fn row_to_draft(row: &rusqlite::Row) -> rusqlite::Result<Draft> {
+    Ok(Draft {
+        slot: row.get(0)?,
+        title: row.get(1)?,
+        content: row.get(2)?,
+        created_at: row.get(3)?,
+        updated_at: row.get(4)?,
+    })
 }
 
-#[allow(dead_code)]
-pub fn get_entry_by_date(date: &str) -> Result<Option<Entry>> {
-    get_entry_by_date_from_db(Path::new("entries.db"), date)
+fn row_to_entry(row: &rusqlite::Row) -> rusqlite::Result<Entry> {
+    Ok(Entry {
+        date: row.get(0)?,
+        title: row.get(1).optional()?,
+        content: row.get(2)?,
+        password: row.get(3)?,
+        image: row.get(4)?,
+    })
 }
 
-#[allow(dead_code)]
-pub fn update_entry_by_date(date: &str, new_title: Option<&str>, new_content: Option<&str>, new_password: Option<&str>, new_image: Option<&str>) -> Result<()> {
-    update_entry_by_date_in_db(Path::new("entries.db"), date, new_title, new_content, new_password, new_image)
+fn row_to_trashed_entry(row: &rusqlite::Row) -> rusqlite::Result<TrashedEntry> {
+    Ok(TrashedEntry {
+        date: row.get(0)?,
+        title: row.get(1)?,
+        image: row.get(2)?,
+        deleted_at: row.get(3)?,
+    })
 }
 
-#[allow(dead_code)]
-pub fn delete_entry_by_date(date: &str) -> Result<()> {
-    delete_entry_by_date_from_db(Path::new("entries.db"), date)
+fn row_to_revision(row: &rusqlite::Row) -> rusqlite::Result<EntryRevision> {
+    Ok(EntryRevision {
+        id: row.get(0)?,
+        entry_date: row.get(1)?,
+        title: row.get(2)?,
+        content: row.get(3)?,
+        created_at: row.get(4)?,
+    })
 }
 
-#[allow(dead_code)]
-pub fn create_entry_with_now(title: &str, content: Option<&str>, password: Option<&str>, image: Option<&str>) -> Result<()> {
-    create_entry_with_now_in_db(Path::new("entries.db"), title, content, password, image)
+fn row_to_attachment(row: &rusqlite::Row) -> rusqlite::Result<Attachment> {
+    Ok(Attachment {
+        id: row.get(0)?,
+        entry_date: row.get(1)?,
+        kind: row.get(2)?,
+        relative_path: row.get(3)?,
+        mime: row.get(4)?,
+        size: row.get(5)?,
+        checksum: row.get(6)?,
+        position: row.get(7)?,
+        created_at: row.get(8)?,
+    })
 }
 
-pub fn create_new_chat_session_in_db(db_file_path: &Path) -> Result<String> {
-    let conn = Connection::open(db_file_path)?;
-    let session_id = uuid::Uuid::new_v4().to_string();
-    let now = Utc::now().to_rfc3339();
-    conn.execute(
-        "INSERT INTO assistant_chat_sessions (id, created_at, last_modified_at) VALUES (?1, ?2, ?3)",
-        params![session_id, now, now],
-    )?;
-    Ok(session_id)
-}
+impl Database {
+    // creates or migrates the schema before handing out connections
+    pub fn open(db_file_path: &Path) -> Result<Self> {
+        let conn = open_connection(db_file_path)?;
+        init_schema(&conn)?;
+        Ok(Self {
+            path: db_file_path.to_path_buf(),
+            idle_connections: Mutex::new(vec![conn]),
+        })
+    }
 
-pub fn save_chat_message_in_db(db_file_path: &Path, session_id: &str, sender: &str, content: &str) -> Result<()> {
-    let conn = Connection::open(db_file_path)?;
-    let now = Utc::now().to_rfc3339();
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn conn(&self) -> Result<PooledConnection<'_>> {
+        let idle = self.idle_connections.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).pop();
+        let conn = match idle {
+            Some(conn) => conn,
+            None => open_connection(&self.path)?,
+        };
+        Ok(PooledConnection { database: self, conn: Some(conn) })
+    }
+
+    pub fn schema_version(&self) -> Result<i64> {
+        Ok(self.conn()?.query_row("PRAGMA user_version", [], |row| row.get(0))?)
+    }
+
+    pub fn add_entry(&self, entry: Entry) -> Result<()> {
+        if NaiveDate::parse_from_str(&entry.date, "%Y-%m-%d").is_err() {
+            return Err(AppError::InvalidDate(format!("Date must be a valid YYYY-MM-DD format, got '{}'", entry.date)));
+        }
+
+        // a single INSERT; the primary key rejects duplicate dates without a lookup first
+        let mut conn = self.conn()?;
+        let tx = conn.transaction()?;
+        match insert_entry_row(&tx, &entry) {
+            Err(RusqliteError::SqliteFailure(e, _)) if e.extended_code == ffi::SQLITE_CONSTRAINT_PRIMARYKEY => {
+                drop(tx);
+                drop(conn);
+                if self.get_trashed_entry(&entry.date)?.is_some() {
+                    return Err(AppError::Duplicate(format!("An entry for {} is in the trash. Restore or purge it first.", entry.date)));
+                }
+                Err(AppError::Duplicate(format!("Entry with this date already exists: {}", entry.date)))
+            }
+            other => {
+                other?;
+                tx.commit()?;
+                Ok(())
+            }
+        }
+    }
 
-    if sender == "user" {
-        let current_title: Option<String> = conn.query_row(
-            "SELECT title FROM assistant_chat_sessions WHERE id = ?1",
-            params![session_id],
-            |row| row.get::<_, Option<String>>(0),
-        ).optional()?.flatten();
-
-        if current_title.is_none() || current_title.as_deref() == Some("") {
-             let new_title: String = content.chars().take(50).collect();
-             let final_title = if content.chars().count() > 50 { format!("{}...", new_title) } else { new_title };
-             conn.execute(
-                 "UPDATE assistant_chat_sessions SET title = ?1, last_modified_at = ?2 WHERE id = ?3",
-                 params![final_title, now, session_id],
-             )?;
+    pub fn get_entries(&self) -> Result<Vec<Entry>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare_cached("SELECT date, title, content, password, image FROM entries WHERE deleted_at IS NULL ORDER BY date DESC")?;
+        let entry_iter = stmt.query_map([], row_to_entry)?;
+        Ok(entry_iter.collect::<rusqlite::Result<_>>()?)
+    }
+
+    // one page of entries matching the query, plus one extra row when there are more; the statement
+    // text only varies with which filters are set, so the cache holds a handful of variants
+    pub fn list_entry_rows(&self, query: &EntryQuery, limit: usize) -> Result<Vec<EntryListRow>> {
+        let has_image_sql = "((image IS NOT NULL AND image != '')
+            OR EXISTS (SELECT 1 FROM attachments WHERE attachments.entry_date = entries.date AND attachments.kind = 'image'))";
+        // char(31) cannot be typed into a tag name, so it is safe to split on
+        let tags_sql = "(SELECT group_concat(name, char(31)) FROM (
+            SELECT tags.name FROM entry_tags JOIN tags ON tags.id = entry_tags.tag_id
+            WHERE entry_tags.entry_date = entries.date ORDER BY tags.name))";
+        let mut sql = format!(
+            "SELECT date, title, content, (password IS NOT NULL AND password != ''), {}, {} FROM entries WHERE deleted_at IS NULL",
+            has_image_sql, tags_sql
+        );
+        let mut values: Vec<Value> = Vec::new();
+
+        if let Some(cursor) = &query.cursor {
+            sql.push_str(match query.order {
+                SortOrder::NewestFirst => " AND date < ?",
+                SortOrder::OldestFirst => " AND date > ?",
+            });
+            values.push(Value::Text(cursor.clone()));
+        }
+        if let Some(from_date) = &query.from_date {
+            sql.push_str(" AND date >= ?");
+            values.push(Value::Text(from_date.clone()));
+        }
+        if let Some(to_date) = &query.to_date {
+            sql.push_str(" AND date <= ?");
+            values.push(Value::Text(to_date.clone()));
+        }
+        if !query.emotions.is_empty() {
+            // a protected entry's emotion is part of its content, so it never matches
+            let placeholders = vec!["?"; query.emotions.len()].join(", ");
+            sql.push_str(&format!(" AND emotion IN ({}) AND (password IS NULL OR password = '')", placeholders));
+            values.extend(query.emotions.iter().map(|e| Value::Text(e.trim().to_lowercase())));
+        }
+        if !query.tags.is_empty() {
+            sql.push_str(" AND (password IS NULL OR password = '')");
+            for tag in &query.tags {
+                sql.push_str(" AND EXISTS (SELECT 1 FROM entry_tags JOIN tags ON tags.id = entry_tags.tag_id
+                    WHERE entry_tags.entry_date = entries.date AND tags.name = ?)");
+                values.push(Value::Text(tag.clone()));
+            }
+        }
+        match query.has_image {
+            Some(true) => sql.push_str(&format!(" AND {}", has_image_sql)),
+            Some(false) => sql.push_str(&format!(" AND NOT {}", has_image_sql)),
+            None => {}
+        }
+        sql.push_str(match query.order {
+            SortOrder::NewestFirst => " ORDER BY date DESC LIMIT ?",
+            SortOrder::OldestFirst => " ORDER BY date ASC LIMIT ?",
+        });
+        values.push(Value::Integer(limit as i64 + 1));
+
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare_cached(&sql)?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(values.iter()), |row| {
+            Ok(EntryListRow {
+                date: row.get(0)?,
+                title: row.get(1)?,
+                content: row.get(2)?,
+                protected: row.get(3)?,
+                has_image: row.get(4)?,
+                tags: row.get::<_, Option<String>>(5)?
+                    .map(|names| names.split('\u{1f}').map(str::to_string).collect())
+                    .unwrap_or_default(),
+            })
+        })?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    pub fn get_entry_by_date(&self, date: &str) -> Result<Option<Entry>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare_cached("SELECT date, title, content, password, image FROM entries WHERE date = ?1 AND deleted_at IS NULL")?;
+        Ok(stmt.query_row([date], row_to_entry).optional()?)
+    }
+
+    // keeps the replaced title and content as a revision when either of them changes
+    pub fn update_entry_by_date(&self, date: &str, new_title: Option<&str>, new_content: Option<&str>, new_password: Option<&str>, new_image: Option<&str>) -> Result<()> {
+        let mut conn = self.conn()?;
+        let tx = conn.transaction()?;
+        update_entry_row(&tx, date, new_title, new_content, new_password, new_image)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    // newest first
+    pub fn get_entry_revisions(&self, date: &str) -> Result<Vec<EntryRevision>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare_cached(
+            "SELECT id, entry_date, title, content, created_at FROM entry_revisions WHERE entry_date = ?1 ORDER BY id DESC",
+        )?;
+        let iter = stmt.query_map([date], row_to_revision)?;
+        Ok(iter.collect::<rusqlite::Result<_>>()?)
+    }
+
+    pub fn get_entry_revision(&self, revision_id: i64) -> Result<Option<EntryRevision>> {
+        Ok(self.conn()?.prepare_cached(
+            "SELECT id, entry_date, title, content, created_at FROM entry_revisions WHERE id = ?1",
+        )?.query_row([revision_id], row_to_revision).optional()?)
+    }
+
+    // keeps the newest `keep` revisions of one entry, or of every entry when no date is given
+    pub fn prune_entry_revisions(&self, date: Option<&str>, keep: usize) -> Result<usize> {
+        Ok(self.conn()?.prepare_cached(
+            "DELETE FROM entry_revisions WHERE id IN (
+                SELECT id FROM (
+                    SELECT id, ROW_NUMBER() OVER (PARTITION BY entry_date ORDER BY id DESC) AS newest
+                    FROM entry_revisions WHERE ?1 IS NULL OR entry_date = ?1
+                ) WHERE newest > ?2
+            )",
+        )?.execute(params![date, keep as i64])?)
+    }
+
+    // false when there is no live entry for the date
+    pub fn trash_entry(&self, date: &str) -> Result<bool> {
+        Ok(self.conn()?.prepare_cached("UPDATE entries SET deleted_at = ?1 WHERE date = ?2 AND deleted_at IS NULL")?
+            .execute(params![Utc::now().to_rfc3339(), date])? > 0)
+    }
+
+    pub fn restore_trashed_entry(&self, date: &str) -> Result<bool> {
+        Ok(self.conn()?.prepare_cached("UPDATE entries SET deleted_at = NULL WHERE date = ?1 AND deleted_at IS NOT NULL")?
+            .execute([date])? > 0)
+    }
+
+    pub fn get_trashed_entry(&self, date: &str) -> Result<Option<TrashedEntry>> {
+        Ok(self.conn()?.prepare_cached(
+            "SELECT date, title, image, deleted_at FROM entries WHERE date = ?1 AND deleted_at IS NOT NULL",
+        )?.query_row([date], row_to_trashed_entry).optional()?)
+    }
+
+    // most recently deleted first
+    pub fn get_trashed_entries(&self) -> Result<Vec<TrashedEntry>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare_cached(
+            "SELECT date, title, image, deleted_at FROM entries WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC, date DESC",
+        )?;
+        let iter = stmt.query_map([], row_to_trashed_entry)?;
+        Ok(iter.collect::<rusqlite::Result<_>>()?)
+    }
+
+    // removes the row, its attachments and revisions for good, whether or not it is in the trash
+    pub fn delete_entry_by_date(&self, date: &str) -> Result<()> {
+        let mut conn = self.conn()?;
+        let tx = conn.transaction()?;
+        tx.prepare_cached("DELETE FROM attachments WHERE entry_date = ?1")?.execute([date])?;
+        tx.prepare_cached("DELETE FROM entry_revisions WHERE entry_date = ?1")?.execute([date])?;
+        tx.prepare_cached("DELETE FROM entry_tags WHERE entry_date = ?1")?.execute([date])?;
+        tx.prepare_cached("DELETE FROM entries WHERE date = ?1")?.execute([date])?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn add_attachment(&self, entry_date: &str, attachment: &NewAttachment) -> Result<Attachment> {
+        if self.get_entry_by_date(entry_date)?.is_none() {
+            return Err(AppError::NotFound(format!("Entry with this date does not exist: {}", entry_date)));
+        }
+
+        let conn = self.conn()?;
+        let id = insert_attachment_row(&conn, entry_date, attachment)?;
+        let mut stmt = conn.prepare_cached(
+            "SELECT id, entry_date, kind, relative_path, mime, size, checksum, position, created_at FROM attachments WHERE id = ?1",
+        )?;
+        Ok(stmt.query_row(params![id], row_to_attachment)?)
+    }
+
+    // (date, image) of live entries whose image has no attachment row yet
+    pub fn get_unattached_entry_images(&self) -> Result<Vec<(String, String)>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare_cached(
+            "SELECT date, image FROM entries
+             WHERE deleted_at IS NULL AND image IS NOT NULL AND TRIM(image) != ''
+               AND NOT EXISTS (SELECT 1 FROM attachments
+                               WHERE attachments.entry_date = entries.date AND attachments.relative_path = entries.image)
+             ORDER BY date DESC",
+        )?;
+        let iter = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        Ok(iter.collect::<rusqlite::Result<_>>()?)
+    }
+
+    pub fn get_attachments_for_entry(&self, entry_date: &str) -> Result<Vec<Attachment>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare_cached(
+            "SELECT id, entry_date, kind, relative_path, mime, size, checksum, position, created_at
+             FROM attachments WHERE entry_date = ?1 ORDER BY position ASC, id ASC",
+        )?;
+        let iter = stmt.query_map(params![entry_date], row_to_attachment)?;
+        Ok(iter.collect::<rusqlite::Result<_>>()?)
+    }
+
+    pub fn get_attachment_by_id(&self, attachment_id: i64) -> Result<Option<Attachment>> {
+        Ok(self.conn()?.prepare_cached(
+            "SELECT id, entry_date, kind, relative_path, mime, size, checksum, position, created_at FROM attachments WHERE id = ?1",
+        )?.query_row(params![attachment_id], row_to_attachment).optional()?)
+    }
+
+    // also clears the legacy image column when it points at the removed file
+    pub fn delete_attachment(&self, attachment_id: i64) -> Result<Option<Attachment>> {
+        let attachment = match self.get_attachment_by_id(attachment_id)? {
+            Some(attachment) => attachment,
+            None => return Ok(None),
+        };
+
+        let mut conn = self.conn()?;
+        let tx = conn.transaction()?;
+        tx.prepare_cached("DELETE FROM attachments WHERE id = ?1")?.execute(params![attachment_id])?;
+        tx.prepare_cached("UPDATE entries SET image = NULL WHERE date = ?1 AND image = ?2")?
+            .execute(params![attachment.entry_date, attachment.relative_path])?;
+        tx.commit()?;
+        Ok(Some(attachment))
+    }
+
+    pub fn get_all_attachments(&self) -> Result<Vec<Attachment>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare_cached(
+            "SELECT id, entry_date, kind, relative_path, mime, size, checksum, position, created_at
+             FROM attachments ORDER BY entry_date ASC, position ASC, id ASC",
+        )?;
+        let iter = stmt.query_map([], row_to_attachment)?;
+        Ok(iter.collect::<rusqlite::Result<_>>()?)
+    }
+
+    pub fn clear_entry_image(&self, date: &str) -> Result<()> {
+        self.conn()?.prepare_cached("UPDATE entries SET image = NULL WHERE date = ?1")?.execute([date])?;
+        Ok(())
+    }
+
+    pub fn delete_attachments_by_path(&self, entry_date: &str, relative_path: &str) -> Result<usize> {
+        Ok(self.conn()?.prepare_cached("DELETE FROM attachments WHERE entry_date = ?1 AND relative_path = ?2")?
+            .execute(params![entry_date, relative_path])?)
+    }
+
+    pub fn reorder_attachments(&self, entry_date: &str, ordered_ids: &[i64]) -> Result<()> {
+        let mut conn = self.conn()?;
+        let tx = conn.transaction()?;
+
+        let existing_count: i64 = tx.prepare_cached("SELECT COUNT(*) FROM attachments WHERE entry_date = ?1")?
+            .query_row(params![entry_date], |row| row.get(0))?;
+        let mut unique_ids = ordered_ids.to_vec();
+        unique_ids.sort_unstable();
+        unique_ids.dedup();
+        if unique_ids.len() != ordered_ids.len() || existing_count != ordered_ids.len() as i64 {
+            return Err(AppError::InvalidInput("Attachment order must list every attachment of the entry exactly once".to_string()));
+        }
+
+        {
+            let mut update = tx.prepare_cached("UPDATE attachments SET position = ?1 WHERE id = ?2 AND entry_date = ?3")?;
+            for (position, attachment_id) in ordered_ids.iter().enumerate() {
+                if update.execute(params![position as i64, attachment_id, entry_date])? == 0 {
+                    return Err(AppError::InvalidInput(format!("Attachment {} does not belong to entry {}", attachment_id, entry_date)));
+                }
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn create_new_chat_session(&self) -> Result<String> {
+        let session_id = uuid::Uuid::new_v4().to_string();
+        let now = Utc::now().to_rfc3339();
+        self.conn()?.prepare_cached(
+            "INSERT INTO assistant_chat_sessions (id, created_at, last_modified_at) VALUES (?1, ?2, ?3)",
+        )?.execute(params![session_id, now, now])?;
+        Ok(session_id)
+    }
+
+    // the first user message names an untitled session
+    pub fn save_chat_message(&self, session_id: &str, sender: &str, content: &str) -> Result<()> {
+        let conn = self.conn()?;
+        let now = Utc::now().to_rfc3339();
+
+        let current_title: Option<String> = if sender == "user" {
+            conn.prepare_cached("SELECT title FROM assistant_chat_sessions WHERE id = ?1")?
+                .query_row(params![session_id], |row| row.get::<_, Option<String>>(0))
+                .optional()?
+                .flatten()
+        } else {
+            None
+        };
+
+        if sender == "user" && current_title.as_deref().unwrap_or("").is_empty() {
+            let new_title: String = content.chars().take(50).collect();
+            let final_title = if content.chars().count() > 50 { format!("{}...", new_title) } else { new_title };
+            conn.prepare_cached("UPDATE assistant_chat_sessions SET title = ?1, last_modified_at = ?2 WHERE id = ?3")?
+                .execute(params![final_title, now, session_id])?;
         }
         else {
-             conn.execute(
-                 "UPDATE assistant_chat_sessions SET last_modified_at = ?1 WHERE id = ?2",
-                 params![now, session_id],
-             )?;
+            conn.prepare_cached("UPDATE assistant_chat_sessions SET last_modified_at = ?1 WHERE id = ?2")?
+                .execute(params![now, session_id])?;
         }
+
+        conn.prepare_cached(
+            "INSERT INTO assistant_chat_messages (session_id, sender, content, timestamp) VALUES (?1, ?2, ?3, ?4)",
+        )?.execute(params![session_id, sender, content, now])?;
+        Ok(())
     }
-    else {
-         conn.execute(
-             "UPDATE assistant_chat_sessions SET last_modified_at = ?1 WHERE id = ?2",
-             params![now, session_id],
-         )?;
+
+    pub fn get_all_chat_sessions(&self) -> Result<Vec<ChatSession>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare_cached("SELECT id, created_at, last_modified_at, title FROM assistant_chat_sessions WHERE deleted_at IS NULL ORDER BY last_modified_at DESC")?;
+        let iter = stmt.query_map([], |row| {
+            Ok(ChatSession {
+                id: row.get(0)?,
+                created_at: row.get(1)?,
+                last_modified_at: row.get(2)?,
+                title: row.get::<_, Option<String>>(3)?,
+            })
+        })?;
+        Ok(iter.collect::<rusqlite::Result<_>>()?)
     }
 
-    conn.execute(
-        "INSERT INTO assistant_chat_messages (session_id, sender, content, timestamp) VALUES (?1, ?2, ?3, ?4)",
-        params![session_id, sender, content, now],
-    )?;
-    Ok(())
-}
+    pub fn get_messages_for_session(&self, session_id: &str) -> Result<Vec<ChatMessage>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare_cached("SELECT id, session_id, sender, content, timestamp FROM assistant_chat_messages WHERE session_id = ?1 ORDER BY timestamp ASC")?;
+        let iter = stmt.query_map(params![session_id], |row| {
+            Ok(ChatMessage {
+                id: row.get(0)?,
+                session_id: row.get(1)?,
+                sender: row.get(2)?,
+                content: row.get(3)?,
+                timestamp: row.get(4)?,
+            })
+        })?;
+        Ok(iter.collect::<rusqlite::Result<_>>()?)
+    }
 
-pub fn get_all_chat_sessions_from_db(db_file_path: &Path) -> Result<Vec<ChatSession>> {
-    let conn = Connection::open(db_file_path)?;
-    let mut stmt = conn.prepare("SELECT id, created_at, last_modified_at, title FROM assistant_chat_sessions ORDER BY last_modified_at DESC")?;
-    let iter = stmt.query_map([], |row| {
-        Ok(ChatSession {
-            id: row.get(0)?,
-            created_at: row.get(1)?,
-            last_modified_at: row.get(2)?,
-            title: row.get::<_, Option<String>>(3)?,
-        })
-    })?;
-    iter.collect()
-}
+    pub fn trash_chat_session(&self, session_id: &str) -> Result<bool> {
+        Ok(self.conn()?.prepare_cached("UPDATE assistant_chat_sessions SET deleted_at = ?1 WHERE id = ?2 AND deleted_at IS NULL")?
+            .execute(params![Utc::now().to_rfc3339(), session_id])? > 0)
+    }
 
-pub fn get_messages_for_session_from_db(db_file_path: &Path, session_id: &str) -> Result<Vec<ChatMessage>> {
-    let conn = Connection::open(db_file_path)?;
-    let mut stmt = conn.prepare("SELECT id, session_id, sender, content, timestamp FROM assistant_chat_messages WHERE session_id = ?1 ORDER BY timestamp ASC")?;
-    let iter = stmt.query_map(params![session_id], |row| {
-        Ok(ChatMessage {
-            id: row.get(0)?,
-            session_id: row.get(1)?,
-            sender: row.get(2)?,
-            content: row.get(3)?,
-            timestamp: row.get(4)?,
-        })
-    })?;
-    iter.collect()
-}
+    pub fn restore_trashed_chat_session(&self, session_id: &str) -> Result<bool> {
+        Ok(self.conn()?.prepare_cached("UPDATE assistant_chat_sessions SET deleted_at = NULL WHERE id = ?1 AND deleted_at IS NOT NULL")?
+            .execute([session_id])? > 0)
+    }
 
-pub fn delete_chat_session_from_db(db_file_path: &Path, session_id: &str) -> Result<()> {
-    let conn = Connection::open(db_file_path)?;
-    conn.execute("DELETE FROM assistant_chat_sessions WHERE id = ?1", params![session_id])?;
-    Ok(())
-}
+    pub fn get_trashed_chat_sessions(&self) -> Result<Vec<TrashedChatSession>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare_cached(
+            "SELECT id, title, deleted_at FROM assistant_chat_sessions WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC",
+        )?;
+        let iter = stmt.query_map([], |row| {
+            Ok(TrashedChatSession {
+                id: row.get(0)?,
+                title: row.get(1)?,
+                deleted_at: row.get(2)?,
+            })
+        })?;
+        Ok(iter.collect::<rusqlite::Result<_>>()?)
+    }
 
-#[allow(dead_code)]
-pub fn create_new_chat_session() -> Result<String> {
-    create_new_chat_session_in_db(Path::new("entries.db"))
-}
+    // removes the session and, through the foreign key, its messages
+    pub fn delete_chat_session(&self, session_id: &str) -> Result<()> {
+        self.conn()?.prepare_cached("DELETE FROM assistant_chat_sessions WHERE id = ?1")?.execute(params![session_id])?;
+        Ok(())
+    }
 
-#[allow(dead_code)]
-pub fn save_chat_message(session_id: &str, sender: &str, content: &str) -> Result<()> {
-    save_chat_message_in_db(Path::new("entries.db"), session_id, sender, content)
-}
+    // protected entries are not counted, and a tag only they carry is left out
+    pub fn get_tags(&self) -> Result<Vec<Tag>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare_cached(
+            "SELECT tags.id, tags.name, COUNT(entries.date) FROM tags
+             LEFT JOIN entry_tags ON entry_tags.tag_id = tags.id
+             LEFT JOIN entries ON entries.date = entry_tags.entry_date AND entries.deleted_at IS NULL
+                AND (entries.password IS NULL OR entries.password = '')
+             GROUP BY tags.id
+             HAVING COUNT(entries.date) > 0 OR NOT EXISTS (
+                SELECT 1 FROM entry_tags JOIN entries AS protected ON protected.date = entry_tags.entry_date
+                WHERE entry_tags.tag_id = tags.id AND protected.password IS NOT NULL AND protected.password != '')
+             ORDER BY tags.name",
+        )?;
+        let iter = stmt.query_map([], |row| {
+            Ok(Tag {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                entry_count: row.get::<_, i64>(2)? as usize,
+            })
+        })?;
+        Ok(iter.collect::<rusqlite::Result<_>>()?)
+    }
 
-#[allow(dead_code)]
-pub fn get_all_chat_sessions() -> Result<Vec<ChatSession>> {
-    get_all_chat_sessions_from_db(Path::new("entries.db"))
-}
+    pub fn get_tag_id(&self, name: &str) -> Result<Option<i64>> {
+        Ok(self.conn()?.prepare_cached("SELECT id FROM tags WHERE name = ?1")?
+            .query_row([name], |row| row.get(0)).optional()?)
+    }
 
-#[allow(dead_code)]
-pub fn get_messages_for_session(session_id: &str) -> Result<Vec<ChatMessage>> {
-    get_messages_for_session_from_db(Path::new("entries.db"), session_id)
-}
+    pub fn get_entry_tags(&self, date: &str) -> Result<Vec<String>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare_cached(
+            "SELECT tags.name FROM entry_tags JOIN tags ON tags.id = entry_tags.tag_id WHERE entry_tags.entry_date = ?1 ORDER BY tags.name",
+        )?;
+        let iter = stmt.query_map([date], |row| row.get(0))?;
+        Ok(iter.collect::<rusqlite::Result<_>>()?)
+    }
+
+    pub fn add_entry_tag(&self, date: &str, name: &str) -> Result<bool> {
+        if self.get_entry_by_date(date)?.is_none() {
+            return Err(AppError::NotFound(format!("Entry with this date does not exist: {}", date)));
+        }
+        let mut conn = self.conn()?;
+        let tx = conn.transaction()?;
+        let added = insert_manual_tag(&tx, date, name)?;
+        tx.commit()?;
+        Ok(added)
+    }
+
+    pub fn remove_entry_tag(&self, date: &str, name: &str) -> Result<bool> {
+        Ok(self.conn()?.prepare_cached(
+            "DELETE FROM entry_tags WHERE entry_date = ?1 AND tag_id = (SELECT id FROM tags WHERE name = ?2)",
+        )?.execute(params![date, name])? > 0)
+    }
+
+    // the renamed tag no longer follows hashtags; an entry still containing the old #hashtag gets it back on its next save
+    pub fn rename_tag(&self, name: &str, new_name: &str) -> Result<bool> {
+        let mut conn = self.conn()?;
+        let tx = conn.transaction()?;
+        let renamed = match tx.prepare_cached("UPDATE tags SET name = ?1 WHERE name = ?2")?.execute(params![new_name, name]) {
+            Err(RusqliteError::SqliteFailure(e, _)) if e.extended_code == ffi::SQLITE_CONSTRAINT_UNIQUE => {
+                return Err(AppError::Duplicate(format!("A tag named '{}' already exists. Merge the tags instead.", new_name)));
+            }
+            other => other? > 0,
+        };
+        tx.prepare_cached("UPDATE entry_tags SET source = 'manual' WHERE tag_id = (SELECT id FROM tags WHERE name = ?1)")?
+            .execute([new_name])?;
+        tx.commit()?;
+        Ok(renamed)
+    }
+
+    // moves every entry of the source tags onto the target, creating it if needed, and deletes the sources;
+    // returns the number of entries that gained the target tag
+    pub fn merge_tags(&self, sources: &[String], target: &str) -> Result<usize> {
+        let mut conn = self.conn()?;
+        let tx = conn.transaction()?;
+        tx.prepare_cached("INSERT OR IGNORE INTO tags (name, created_at) VALUES (?1, ?2)")?
+            .execute(params![target, Utc::now().to_rfc3339()])?;
+        let mut retagged = 0;
+        for source in sources.iter().filter(|source| source.as_str() != target) {
+            retagged += tx.prepare_cached(
+                "INSERT OR IGNORE INTO entry_tags (entry_date, tag_id, source)
+                 SELECT entry_date, (SELECT id FROM tags WHERE name = ?2), 'manual' FROM entry_tags
+                 WHERE tag_id = (SELECT id FROM tags WHERE name = ?1)",
+            )?.execute(params![source, target])?;
+            tx.prepare_cached("DELETE FROM tags WHERE name = ?1")?.execute([source])?;
+        }
+        tx.commit()?;
+        Ok(retagged)
+    }
+
+    // removes the tag from every entry
+    pub fn delete_tag(&self, name: &str) -> Result<bool> {
+        Ok(self.conn()?.prepare_cached("DELETE FROM tags WHERE name = ?1")?.execute([name])? > 0)
+    }
 
-#[allow(dead_code)]
-pub fn delete_chat_session(session_id: &str) -> Result<()> {
-    delete_chat_session_from_db(Path::new("entries.db"), session_id)
+    // protected and trashed entries are left out, as in the emotion filter of the listing
+    pub fn get_tag_emotion_rows(&self) -> Result<Vec<TagEmotionRow>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare_cached(
+            "SELECT tags.name, entries.emotion, COUNT(*) FROM tags
+             JOIN entry_tags ON entry_tags.tag_id = tags.id
+             JOIN entries ON entries.date = entry_tags.entry_date
+             WHERE entries.deleted_at IS NULL AND (entries.password IS NULL OR entries.password = '')
+             GROUP BY tags.name, entries.emotion ORDER BY tags.name",
+        )?;
+        let iter = stmt.query_map([], |row| {
+            Ok(TagEmotionRow {
+                tag: row.get(0)?,
+                emotion: row.get(1)?,
+                count: row.get::<_, i64>(2)? as usize,
+            })
+        })?;
+        Ok(iter.collect::<rusqlite::Result<_>>()?)
+    }
+
+    pub fn add_checkin(&self, checkin: &NewCheckin) -> Result<Checkin> {
+        let conn = self.conn()?;
+        let inserted = conn.prepare_cached(
+            "INSERT INTO checkins (recorded_at, date, mood, emotions, energy, sleep_hours, note, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        )?.execute(params![
+            checkin.recorded_at,
+            checkin.date,
+            checkin.mood,
+            checkin.emotions.join(","),
+            checkin.energy,
+            checkin.sleep_hours,
+            checkin.note,
+            Utc::now().to_rfc3339()
+        ]);
+        match inserted {
+            Err(RusqliteError::SqliteFailure(e, _)) if e.extended_code == ffi::SQLITE_CONSTRAINT_UNIQUE => {
+                return Err(AppError::Duplicate(format!("A check-in was already recorded at {}.", checkin.recorded_at)));
+            }
+            other => other?,
+        };
+        let id = conn.last_insert_rowid();
+        let mut stmt = conn.prepare_cached(&format!("SELECT {} FROM checkins WHERE id = ?1", CHECKIN_COLUMNS))?;
+        Ok(stmt.query_row([id], row_to_checkin)?)
+    }
+
+    pub fn get_checkin(&self, id: i64) -> Result<Option<Checkin>> {
+        Ok(self.conn()?.prepare_cached(&format!("SELECT {} FROM checkins WHERE id = ?1", CHECKIN_COLUMNS))?
+            .query_row([id], row_to_checkin).optional()?)
+    }
+
+    // newest first; dates are inclusive YYYY-MM-DD days and a limit of None returns all of them
+    pub fn get_checkins(&self, from_date: Option<&str>, to_date: Option<&str>, limit: Option<usize>) -> Result<Vec<Checkin>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare_cached(&format!(
+            "SELECT {} FROM checkins WHERE (?1 IS NULL OR date >= ?1) AND (?2 IS NULL OR date <= ?2)
+             ORDER BY recorded_at DESC LIMIT ?3",
+            CHECKIN_COLUMNS
+        ))?;
+        let limit = limit.map_or(-1, |limit| limit as i64);
+        let iter = stmt.query_map(params![from_date, to_date, limit], row_to_checkin)?;
+        Ok(iter.collect::<rusqlite::Result<_>>()?)
+    }
+
+    pub fn delete_checkin(&self, id: i64) -> Result<bool> {
+        Ok(self.conn()?.prepare_cached("DELETE FROM checkins WHERE id = ?1")?.execute([id])? > 0)
+    }
+
+    pub fn save_draft(&self, slot: &str, title: Option<&str>, content: &str) -> Result<()> {
+        let now = Utc::now().to_rfc3339();
+        self.conn()?.prepare_cached(
+            "INSERT INTO drafts (slot, title, content, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?4)
+             ON CONFLICT(slot) DO UPDATE SET title = excluded.title, content = excluded.content, updated_at = excluded.updated_at",
+        )?.execute(params![slot, title, content, now])?;
+        Ok(())
+    }
+
+    pub fn get_draft(&self, slot: &str) -> Result<Option<Draft>> {
+        Ok(self.conn()?.prepare_cached(
+            "SELECT slot, title, content, created_at, updated_at FROM drafts WHERE slot = ?1",
+        )?.query_row([slot], row_to_draft).optional()?)
+    }
+
+    pub fn get_drafts(&self) -> Result<Vec<Draft>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare_cached(
+            "SELECT slot, title, content, created_at, updated_at FROM drafts ORDER BY updated_at DESC",
+        )?;
+        let iter = stmt.query_map([], row_to_draft)?;
+        Ok(iter.collect::<rusqlite::Result<_>>()?)
+    }
+
+    pub fn delete_draft(&self, slot: &str) -> Result<bool> {
+        Ok(self.conn()?.prepare_cached("DELETE FROM drafts WHERE slot = ?1")?.execute([slot])? > 0)
+    }
+
+    // rows of PRAGMA integrity_check; a healthy file reports the single row "ok"
+    pub fn integrity_check(&self) -> Result<Vec<String>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare("PRAGMA integrity_check")?;
+        let rows = stmt.query_map([], |row| row.get(0))?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    // messages left behind by sessions deleted before foreign keys were enforced
+    pub fn count_orphaned_chat_messages(&self) -> Result<usize> {
+        Ok(self.conn()?.prepare_cached(
+            "SELECT COUNT(*) FROM assistant_chat_messages WHERE session_id NOT IN (SELECT id FROM assistant_chat_sessions)",
+        )?.query_row([], |row| row.get::<_, i64>(0)).map(|count| count as usize)?)
+    }
+
+    pub fn delete_orphaned_chat_messages(&self) -> Result<usize> {
+        Ok(self.conn()?.prepare_cached(
+            "DELETE FROM assistant_chat_messages WHERE session_id NOT IN (SELECT id FROM assistant_chat_sessions)",
+        )?.execute([])?)
+    }
+
+    pub fn count_orphaned_attachments(&self) -> Result<usize> {
+        Ok(self.conn()?.prepare_cached(
+            "SELECT COUNT(*) FROM attachments WHERE entry_date NOT IN (SELECT date FROM entries)",
+        )?.query_row([], |row| row.get::<_, i64>(0)).map(|count| count as usize)?)
+    }
+
+    // only drops the rows; the files they pointed at are left for the image cleanup
+    pub fn delete_orphaned_attachments(&self) -> Result<usize> {
+        Ok(self.conn()?.prepare_cached(
+            "DELETE FROM attachments WHERE entry_date NOT IN (SELECT date FROM entries)",
+        )?.execute([])?)
+    }
+
+    // entry dates that are not a real YYYY-MM-DD day, e.g. written by an older import
+    pub fn get_invalid_entry_dates(&self) -> Result<Vec<String>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare_cached("SELECT date FROM entries ORDER BY date ASC")?;
+        let dates = stmt.query_map([], |row| row.get::<_, String>(0))?.collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(dates.into_iter().filter(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").is_err()).collect())
+    }
 }
 
 #[cfg(test)]
@@ -327,6 +1238,10 @@ mod tests {
         if path.exists() {
             let _ = fs::remove_file(&path);
         }
+        // a leftover write-ahead log from an earlier run must not be replayed into the new file
+        for suffix in ["-wal", "-shm"] {
+            let _ = fs::remove_file(path.with_file_name(format!("test_entries_{}.db{}", test_name, suffix)));
+        }
         path
     }
 
@@ -334,7 +1249,7 @@ mod tests {
     fn test_add_and_get_entries() {
         let db_path = get_test_db_file_path("add_and_get_entries");
 
-        init_db_at_path(&db_path).expect("Failed to init DB at test path");
+        let db = Database::open(&db_path).expect("Failed to init DB at test path");
 
         let new_entry = Entry {
             date: "2025-04-20".to_string(),
@@ -344,9 +1259,9 @@ mod tests {
             image: Some("images/test_image.jpg".to_string()),
         };
 
-        add_entry_to_db(&db_path, new_entry).expect("Failed to add entry");
+        db.add_entry(new_entry).expect("Failed to add entry");
 
-        let entries = get_entries_from_db(&db_path).expect("Failed to get entries");
+        let entries = db.get_entries().expect("Failed to get entries");
         
         assert!(entries.len() > 0, "Expected at least one entry after adding.");
 
@@ -357,7 +1272,7 @@ mod tests {
     fn test_get_update_delete_entry_by_date() {
         let db_path = get_test_db_file_path("get_update_delete_entry");
 
-        init_db_at_path(&db_path).expect("init failed for test path");
+        let db = Database::open(&db_path).expect("init failed for test path");
 
         let entry = Entry {
             date: "2025-04-21".to_string(),
@@ -367,43 +1282,43 @@ mod tests {
             image: None,
         };
 
-        add_entry_to_db(&db_path, entry).expect("add failed");
+        db.add_entry(entry).expect("add failed");
 
-        let fetched = get_entry_by_date_from_db(&db_path, "2025-04-21").expect("get failed").expect("no entry found after add");
+        let fetched = db.get_entry_by_date("2025-04-21").expect("get failed").expect("no entry found after add");
         assert_eq!(fetched.title, Some("Initial Title".to_string()));
         assert!(fetched.image.is_none());
 
-        update_entry_by_date_in_db(&db_path, "2025-04-21", Some("Updated"), Some("Updated Content"), Some("newpass"), None)
+        db.update_entry_by_date("2025-04-21", Some("Updated"), Some("Updated Content"), Some("newpass"), None)
             .expect("update failed");
 
-        let updated = get_entry_by_date_from_db(&db_path, "2025-04-21").expect("get failed").expect("no entry found after update");
+        let updated = db.get_entry_by_date("2025-04-21").expect("get failed").expect("no entry found after update");
         assert_eq!(updated.title, Some("Updated".to_string()));
         assert_eq!(updated.content.as_deref(), Some("Updated Content"));
 
-        delete_entry_by_date_from_db(&db_path, "2025-04-21").expect("delete failed");
+        db.delete_entry_by_date("2025-04-21").expect("delete failed");
 
-        let deleted = get_entry_by_date_from_db(&db_path, "2025-04-21").expect("get failed for deleted entry");
+        let deleted = db.get_entry_by_date("2025-04-21").expect("get failed for deleted entry");
         assert!(deleted.is_none(), "Entry should be None after deletion.");
 
         let _ = fs::remove_file(db_path);
     }
 
-    fn setup_db_for_chat_tests_internal_consistent(db_path: &Path) {
+    fn setup_db_for_chat_tests_internal_consistent(db_path: &Path) -> Database {
         if db_path.exists() {
             let _ = fs::remove_file(db_path);
         }
-        init_db_at_path(db_path).expect("Failed to init DB for chat test");
+        Database::open(db_path).expect("Failed to init DB for chat test")
     }
 
     #[test]
     fn test_create_and_get_chat_session_from_db() -> std::result::Result<(), String> {
         let db_path = get_test_db_file_path("create_and_get_chat_session");
-        setup_db_for_chat_tests_internal_consistent(&db_path);
+        let db = setup_db_for_chat_tests_internal_consistent(&db_path);
 
-        let session_id = create_new_chat_session_in_db(&db_path).map_err(|e| e.to_string())?;
+        let session_id = db.create_new_chat_session().map_err(|e| e.to_string())?;
         assert!(!session_id.is_empty(), "Session ID empty.");
 
-        let sessions = get_all_chat_sessions_from_db(&db_path).map_err(|e| e.to_string())?;
+        let sessions = db.get_all_chat_sessions().map_err(|e| e.to_string())?;
         assert_eq!(sessions.len(), 1, "Expected one session.");
         assert_eq!(sessions[0].id, session_id, "Session ID mismatch.");
         assert!(sessions[0].title.is_none(), "New session title not None.");
@@ -415,14 +1330,14 @@ mod tests {
     #[test]
     fn test_save_and_get_chat_messages_from_db() -> std::result::Result<(), String> {
         let db_path = get_test_db_file_path("save_and_get_chat_messages");
-        setup_db_for_chat_tests_internal_consistent(&db_path);
+        let db = setup_db_for_chat_tests_internal_consistent(&db_path);
 
-        let session_id = create_new_chat_session_in_db(&db_path).map_err(|e| e.to_string())?;
+        let session_id = db.create_new_chat_session().map_err(|e| e.to_string())?;
 
-        save_chat_message_in_db(&db_path, &session_id, "user", "Hello Assistant!").map_err(|e| e.to_string())?;
-        save_chat_message_in_db(&db_path, &session_id, "assistant", "Hello User!").map_err(|e| e.to_string())?;
+        db.save_chat_message(&session_id, "user", "Hello Assistant!").map_err(|e| e.to_string())?;
+        db.save_chat_message(&session_id, "assistant", "Hello User!").map_err(|e| e.to_string())?;
 
-        let messages = get_messages_for_session_from_db(&db_path, &session_id).map_err(|e| e.to_string())?;
+        let messages = db.get_messages_for_session(&session_id).map_err(|e| e.to_string())?;
         assert_eq!(messages.len(), 2, "Expected two messages.");
 
         assert_eq!(messages[0].sender, "user");
@@ -439,13 +1354,13 @@ mod tests {
     #[test]
     fn test_chat_session_title_update_on_first_user_message_in_db() -> std::result::Result<(), String> {
         let db_path = get_test_db_file_path("chat_session_title_update");
-        setup_db_for_chat_tests_internal_consistent(&db_path);
-        let session_id = create_new_chat_session_in_db(&db_path).map_err(|e| e.to_string())?;
+        let db = setup_db_for_chat_tests_internal_consistent(&db_path);
+        let session_id = db.create_new_chat_session().map_err(|e| e.to_string())?;
 
         let first_message_content = "This is the very first message from the user.";
-        save_chat_message_in_db(&db_path, &session_id, "user", first_message_content).map_err(|e| e.to_string())?;
+        db.save_chat_message(&session_id, "user", first_message_content).map_err(|e| e.to_string())?;
 
-        let sessions = get_all_chat_sessions_from_db(&db_path).map_err(|e| e.to_string())?;
+        let sessions = db.get_all_chat_sessions().map_err(|e| e.to_string())?;
         let session = sessions.iter().find(|s| s.id == session_id).expect("Session not found");
         
         let expected_title_prefix: String = first_message_content.chars().take(50).collect();
@@ -457,13 +1372,13 @@ mod tests {
 
         assert_eq!(session.title.as_ref().unwrap(), &final_expected_title, "Session title not set correctly.");
 
-        save_chat_message_in_db(&db_path, &session_id, "assistant", "Okay, interesting.").map_err(|e| e.to_string())?;
-        let sessions_after_assistant = get_all_chat_sessions_from_db(&db_path).map_err(|e| e.to_string())?;
+        db.save_chat_message(&session_id, "assistant", "Okay, interesting.").map_err(|e| e.to_string())?;
+        let sessions_after_assistant = db.get_all_chat_sessions().map_err(|e| e.to_string())?;
         let session_after_assistant = sessions_after_assistant.iter().find(|s| s.id == session_id).expect("Session not found");
         assert_eq!(session_after_assistant.title.as_ref().unwrap(), &final_expected_title, "Session title changed after assistant message.");
 
-        save_chat_message_in_db(&db_path, &session_id, "user", "Another user message.").map_err(|e| e.to_string())?;
-        let sessions_after_second_user = get_all_chat_sessions_from_db(&db_path).map_err(|e| e.to_string())?;
+        db.save_chat_message(&session_id, "user", "Another user message.").map_err(|e| e.to_string())?;
+        let sessions_after_second_user = db.get_all_chat_sessions().map_err(|e| e.to_string())?;
         let session_after_second_user = sessions_after_second_user.iter().find(|s| s.id == session_id).expect("Session not found");
         assert_eq!(session_after_second_user.title.as_ref().unwrap(), &final_expected_title, "Session title changed after second user message.");
 
@@ -474,12 +1389,12 @@ mod tests {
     #[test]
     fn test_chat_session_title_truncation_in_db() -> std::result::Result<(), String> {
         let db_path = get_test_db_file_path("chat_session_title_truncation");
-        setup_db_for_chat_tests_internal_consistent(&db_path);
-        let session_id = create_new_chat_session_in_db(&db_path).map_err(|e| e.to_string())?;
+        let db = setup_db_for_chat_tests_internal_consistent(&db_path);
+        let session_id = db.create_new_chat_session().map_err(|e| e.to_string())?;
         let long_message = "This is a very long first message that definitely exceeds the fifty character limit for the title of a chat session, it just keeps going on and on and on and on.";
-        save_chat_message_in_db(&db_path, &session_id, "user", long_message).map_err(|e| e.to_string())?;
+        db.save_chat_message(&session_id, "user", long_message).map_err(|e| e.to_string())?;
 
-        let sessions = get_all_chat_sessions_from_db(&db_path).map_err(|e| e.to_string())?;
+        let sessions = db.get_all_chat_sessions().map_err(|e| e.to_string())?;
         let session = sessions.iter().find(|s| s.id == session_id).expect("Session not found");
         
         let expected_truncated_prefix: String = long_message.chars().take(50).collect();
@@ -494,28 +1409,154 @@ mod tests {
     #[test]
     fn test_delete_chat_session_and_cascade_in_db() -> std::result::Result<(), String> {
         let db_path = get_test_db_file_path("delete_chat_session_cascade");
-        setup_db_for_chat_tests_internal_consistent(&db_path);
-        let session_id1 = create_new_chat_session_in_db(&db_path).map_err(|e| e.to_string())?;
-        let session_id2 = create_new_chat_session_in_db(&db_path).map_err(|e| e.to_string())?;
+        let db = setup_db_for_chat_tests_internal_consistent(&db_path);
+        let session_id1 = db.create_new_chat_session().map_err(|e| e.to_string())?;
+        let session_id2 = db.create_new_chat_session().map_err(|e| e.to_string())?;
 
-        save_chat_message_in_db(&db_path, &session_id1, "user", "Hi from session 1").map_err(|e| e.to_string())?;
-        save_chat_message_in_db(&db_path, &session_id1, "assistant", "Reply in session 1").map_err(|e| e.to_string())?;
-        save_chat_message_in_db(&db_path, &session_id2, "user", "Hi from session 2").map_err(|e| e.to_string())?;
+        db.save_chat_message(&session_id1, "user", "Hi from session 1").map_err(|e| e.to_string())?;
+        db.save_chat_message(&session_id1, "assistant", "Reply in session 1").map_err(|e| e.to_string())?;
+        db.save_chat_message(&session_id2, "user", "Hi from session 2").map_err(|e| e.to_string())?;
 
-        delete_chat_session_from_db(&db_path, &session_id1).map_err(|e| e.to_string())?;
+        db.delete_chat_session(&session_id1).map_err(|e| e.to_string())?;
 
-        let sessions = get_all_chat_sessions_from_db(&db_path).map_err(|e| e.to_string())?;
+        let sessions = db.get_all_chat_sessions().map_err(|e| e.to_string())?;
         assert_eq!(sessions.len(), 1, "Expected one session left.");
         assert_eq!(sessions[0].id, session_id2, "Wrong session deleted or ID incorrect.");
 
-        let messages_for_deleted_session = get_messages_for_session_from_db(&db_path, &session_id1).map_err(|e| e.to_string())?;
+        let messages_for_deleted_session = db.get_messages_for_session(&session_id1).map_err(|e| e.to_string())?;
         assert!(messages_for_deleted_session.is_empty(), "Messages for deleted session not gone.");
         
-        let messages_for_remaining_session = get_messages_for_session_from_db(&db_path, &session_id2).map_err(|e| e.to_string())?;
+        let messages_for_remaining_session = db.get_messages_for_session(&session_id2).map_err(|e| e.to_string())?;
         assert_eq!(messages_for_remaining_session.len(), 1, "Messages for remaining session incorrect.");
         assert_eq!(messages_for_remaining_session[0].content, "Hi from session 2");
 
         let _ = fs::remove_file(&db_path);
         Ok(())
     }
+
+    fn sample_attachment(relative_path: &str) -> NewAttachment {
+        NewAttachment {
+            kind: "image".to_string(),
+            relative_path: relative_path.to_string(),
+            mime: Some("image/png".to_string()),
+            size: 42,
+            checksum: Some("abc".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_add_reorder_and_delete_attachments() {
+        let db_path = get_test_db_file_path("add_reorder_delete_attachments");
+        let db = Database::open(&db_path).expect("init failed");
+        db.add_entry(Entry {
+            date: "2025-04-22".to_string(),
+            title: Some("Attachments".to_string()),
+            content: None,
+            password: None,
+            image: Some("journal_images/a.png".to_string()),
+        }).expect("add failed");
+
+        let first = db.add_attachment("2025-04-22", &sample_attachment("journal_images/a.png")).expect("add attachment failed");
+        let second = db.add_attachment("2025-04-22", &sample_attachment("journal_images/b.png")).expect("add attachment failed");
+        assert_eq!(first.position, 0);
+        assert_eq!(second.position, 1);
+
+        db.reorder_attachments("2025-04-22", &[second.id, first.id]).expect("reorder failed");
+        let reordered = db.get_attachments_for_entry("2025-04-22").expect("list failed");
+        assert_eq!(reordered.iter().map(|a| a.id).collect::<Vec<_>>(), vec![second.id, first.id]);
+
+        assert!(db.reorder_attachments("2025-04-22", &[first.id]).is_err(), "Partial order should be rejected.");
+        assert!(db.reorder_attachments("2025-04-22", &[first.id, first.id]).is_err(), "Duplicate ids should be rejected.");
+
+        let removed = db.delete_attachment(first.id).expect("delete failed");
+        assert_eq!(removed.map(|a| a.relative_path), Some("journal_images/a.png".to_string()));
+        let entry = db.get_entry_by_date("2025-04-22").expect("get failed").expect("entry missing");
+        assert!(entry.image.is_none(), "Legacy image column should be cleared with its attachment.");
+
+        let _ = fs::remove_file(db_path);
+    }
+
+    #[test]
+    fn test_attachments_require_entry_and_cascade_on_delete() {
+        let db_path = get_test_db_file_path("attachments_cascade");
+        let db = Database::open(&db_path).expect("init failed");
+
+        assert!(db.add_attachment("2025-04-23", &sample_attachment("journal_images/x.png")).is_err(),
+            "Attachment without an entry should be rejected.");
+
+        db.add_entry(Entry {
+            date: "2025-04-23".to_string(),
+            title: Some("Cascade".to_string()),
+            content: None,
+            password: None,
+            image: None,
+        }).expect("add failed");
+        db.add_attachment("2025-04-23", &sample_attachment("journal_images/x.png")).expect("add attachment failed");
+
+        db.delete_entry_by_date("2025-04-23").expect("delete failed");
+        let remaining = db.get_attachments_for_entry("2025-04-23").expect("list failed");
+        assert!(remaining.is_empty(), "Attachments should be removed with their entry.");
+
+        let _ = fs::remove_file(db_path);
+    }
+
+    #[test]
+    fn test_orphaned_chat_messages_are_counted_and_removed() {
+        let db_path = get_test_db_file_path("orphaned_chat_messages");
+        let db = Database::open(&db_path).expect("init failed");
+        let kept = db.create_new_chat_session().expect("create failed");
+        db.save_chat_message(&kept, "user", "Still here").expect("save failed");
+
+        // what a connection without foreign keys used to leave behind
+        {
+            let conn = Connection::open(&db_path).expect("open failed");
+            conn.pragma_update(None, "foreign_keys", "OFF").expect("pragma failed");
+            conn.execute("INSERT INTO assistant_chat_sessions (id) VALUES ('gone')", []).expect("insert failed");
+            conn.execute("INSERT INTO assistant_chat_messages (session_id, sender, content) VALUES ('gone', 'user', 'Lost')", []).expect("insert failed");
+            conn.execute("DELETE FROM assistant_chat_sessions WHERE id = 'gone'", []).expect("delete failed");
+        }
+
+        assert_eq!(db.count_orphaned_chat_messages().expect("count failed"), 1);
+        assert_eq!(db.delete_orphaned_chat_messages().expect("repair failed"), 1);
+        assert_eq!(db.count_orphaned_chat_messages().expect("count failed"), 0);
+        assert_eq!(db.get_messages_for_session(&kept).expect("list failed").len(), 1, "Messages of live sessions must be kept.");
+        assert_eq!(db.integrity_check().expect("check failed"), vec!["ok".to_string()]);
+
+        drop(db);
+        let _ = fs::remove_file(db_path);
+    }
+
+    #[test]
+    fn test_pooled_connections_share_settings_and_are_reused() {
+        let db_path = get_test_db_file_path("pooled_connections");
+        let db = Database::open(&db_path).expect("init failed");
+        {
+            let first = db.conn().expect("conn failed");
+            let second = db.conn().expect("A second connection should open while the first is in use.");
+            for conn in [&first, &second] {
+                let journal_mode: String = conn.query_row("PRAGMA journal_mode", [], |row| row.get(0)).expect("pragma failed");
+                assert_eq!(journal_mode, "wal");
+                let foreign_keys: i64 = conn.query_row("PRAGMA foreign_keys", [], |row| row.get(0)).expect("pragma failed");
+                assert_eq!(foreign_keys, 1, "Foreign keys should be enforced on every connection.");
+            }
+        }
+        assert_eq!(db.idle_connections.lock().unwrap().len(), 2, "Released connections should return to the pool.");
+
+        let entry = |title: &str| Entry {
+            date: "2025-04-24".to_string(),
+            title: Some(title.to_string()),
+            content: None,
+            password: None,
+            image: None,
+        };
+        db.add_entry(entry("First")).expect("add failed");
+        let duplicate = db.add_entry(entry("Second")).expect_err("A second entry for the same date should be rejected.");
+        assert_eq!(duplicate, AppError::Duplicate("Entry with this date already exists: 2025-04-24".to_string()));
+        let invalid = db.add_entry(Entry { date: "2025-02-30".to_string(), ..entry("Invalid") }).expect_err("An impossible date should be rejected.");
+        assert_eq!(invalid.code(), "INVALID_DATE");
+        assert_eq!(db.get_entry_by_date("2025-04-24").expect("get failed").and_then(|e| e.title).as_deref(), Some("First"));
+
+        drop(db);
+        let _ = fs::remove_file(db_path);
+    }
 }
@@ -1,33 +1,25 @@
 use whisper_rs::{convert_integer_to_float_audio, FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
 use std::path::{Path, PathBuf};
-use hound;
-use tauri::{AppHandle, Manager, path::BaseDirectory};
 use anyhow::{Result, anyhow};
+use crate::error::AppError;
+use serde::{Deserialize, Serialize};
 
-fn get_model_path(app_handle: &AppHandle, model_name: &str) -> Result<PathBuf> {
-    match app_handle
-        .path()
-        .resolve(&format!("models/dictation/{}", model_name), BaseDirectory::Resource)
-    {
-        Ok(resolved_path) if resolved_path.exists() => {
-            log::info!("[DictationModel] Found model '{}' in bundled resources: {:?}", model_name, resolved_path);
-            return Ok(resolved_path);
-        }
-        Ok(resolved_path) => {
-            log::warn!("[DictationModel] Model '{}' path resolved to {:?} but file does not exist. Will check dev path.", model_name, resolved_path);
-        }
-        Err(e) => {
-            log::warn!("[DictationModel] Failed to resolve resource path for '{}': {}. Will check dev path.", model_name, e);
-        }
+pub const DEFAULT_DICTATION_MODEL: &str = "ggml-tiny.en-q5_1.bin";
+
+// looks in <models_dir>/dictation first, then in src-tauri/models/dictation under the working directory for dev runs
+pub fn find_model_file(models_dir: &Path, model_name: &str) -> Result<PathBuf> {
+    let bundled_path = models_dir.join("dictation").join(model_name);
+    if bundled_path.exists() {
+        log::info!("[DictationModel] Found model '{}' in models directory: {:?}", model_name, bundled_path);
+        return Ok(bundled_path);
     }
+    log::warn!("[DictationModel] Model '{}' not found at {:?}. Will check dev path.", model_name, bundled_path);
 
     let mut dev_path_base = std::env::current_dir()
         .map_err(|e| anyhow!("Failed to get current directory: {}", e))?;
 
-    if dev_path_base.ends_with("src-tauri") {
-        if !dev_path_base.pop() {
-            return Err(anyhow!("Failed to navigate up from src-tauri directory."));
-        }
+    if dev_path_base.ends_with("src-tauri") && !dev_path_base.pop() {
+        return Err(anyhow!("Failed to navigate up from src-tauri directory."));
     }
 
     let model_folder_path = dev_path_base.join("src-tauri").join("models").join("dictation");
@@ -37,12 +29,82 @@ fn get_model_path(app_handle: &AppHandle, model_name: &str) -> Result<PathBuf> {
         log::info!("[DictationModel] Found model '{}' in development path: {:?}", model_name, full_model_path);
         Ok(full_model_path)
     } else {
-        Err(anyhow!(
-            "Model '{}' not found. Checked bundled resources and development path: {}. (Current dev check base: {})",
+        Err(AppError::ModelUnavailable(format!(
+            "Model '{}' not found. Checked {} and development path: {}. (Current dev check base: {})",
             model_name,
+            bundled_path.display(),
             full_model_path.display(),
             dev_path_base.display()
-        ))
+        )).into())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DictationOptions {
+    // beam search is used when set, otherwise greedy sampling with best_of candidates
+    pub beam_size: Option<i32>,
+    pub best_of: i32,
+    pub temperature: f32,
+    // step added to the temperature when a decode fails, 0.0 disables fallback
+    pub temperature_inc: f32,
+    // vocabulary and names to bias the decoder towards
+    pub initial_prompt: Option<String>,
+    // defaults to the number of available cores
+    pub n_threads: Option<i32>,
+    pub translate: bool,
+    // None lets Whisper detect the spoken language
+    pub language: Option<String>,
+    pub vad: VadOptions,
+}
+
+impl Default for DictationOptions {
+    fn default() -> Self {
+        Self {
+            beam_size: None,
+            best_of: 1,
+            temperature: 0.0,
+            temperature_inc: 0.2,
+            initial_prompt: None,
+            n_threads: None,
+            translate: false,
+            language: Some("en".to_string()),
+            vad: VadOptions::default(),
+        }
+    }
+}
+
+impl DictationOptions {
+    pub fn resolved_n_threads(&self) -> i32 {
+        match self.n_threads {
+            Some(n) if n > 0 => n,
+            _ => std::thread::available_parallelism()
+                .map(|n| n.get() as i32)
+                .unwrap_or(4),
+        }
+    }
+
+    pub fn sampling_strategy(&self) -> SamplingStrategy {
+        match self.beam_size {
+            Some(beam_size) if beam_size > 1 => SamplingStrategy::BeamSearch { beam_size, patience: -1.0 },
+            _ => SamplingStrategy::Greedy { best_of: self.best_of.max(1) },
+        }
+    }
+
+    fn build_params(&self) -> FullParams<'_, '_> {
+        let mut params = FullParams::new(self.sampling_strategy());
+        params.set_n_threads(self.resolved_n_threads());
+        params.set_translate(self.translate);
+        params.set_temperature(self.temperature.max(0.0));
+        params.set_temperature_inc(self.temperature_inc.max(0.0));
+
+        params.set_language(self.language.as_deref().map(str::trim).filter(|l| !l.is_empty()));
+
+        if let Some(prompt) = self.initial_prompt.as_deref().map(str::trim).filter(|p| !p.is_empty()) {
+            let sanitized_prompt = prompt.replace('\0', "");
+            params.set_initial_prompt(&sanitized_prompt);
+        }
+        params
     }
 }
 
@@ -51,12 +113,13 @@ pub struct DictationModel {
 }
 
 impl DictationModel {
-    pub fn new(app_handle: &AppHandle, model_name: &str) -> Result<Self> {
-        log::info!("[DictationModel] Initializing DictationModel with model: {}", model_name);
+    pub fn new(model_path: &Path) -> Result<Self> {
+        log::info!("[DictationModel] Initializing DictationModel with model: {}", model_path.display());
 
-        // 1. Resolve Model Path
-        let model_path = get_model_path(app_handle, model_name)
-            .map_err(|e| anyhow!("Failed to get model path for '{}': {}", model_name, e))?;
+        // 1. Check Model Path
+        if !model_path.is_file() {
+            return Err(AppError::ModelUnavailable(format!("Dictation model not found at {}", model_path.display())).into());
+        }
 
         let model_path_str = model_path.to_str().ok_or_else(|| {
             anyhow!(
@@ -70,15 +133,19 @@ impl DictationModel {
         let ctx = WhisperContext::new_with_params(model_path_str, WhisperContextParameters::default())
             .map_err(|e| anyhow!("Failed to load Whisper model from '{}': {:?}", model_path_str, e))?;
         
-        log::info!("[DictationModel] Whisper model '{}' loaded successfully.", model_name);
-        Ok(Self { ctx }) // model_name removed from struct initialization
+        log::info!("[DictationModel] Whisper model '{}' loaded successfully.", model_path_str);
+        Ok(Self { ctx })
     }
 
-    pub fn transcribe(&self, audio_file_path_str: &str) -> Result<String> {
+    pub fn transcribe(&self, audio_file_path_str: &str, options: &DictationOptions) -> Result<String> {
+        self.transcribe_with_report(audio_file_path_str, options).map(|report| report.text)
+    }
+
+    pub fn transcribe_with_report(&self, audio_file_path_str: &str, options: &DictationOptions) -> Result<DictationReport> {
         log::debug!("[DictationModel] Attempting to transcribe audio file: {}", audio_file_path_str);
         let audio_file_path = Path::new(audio_file_path_str);
         if !audio_file_path.exists() {
-            return Err(anyhow!("Audio file not found at path: {}", audio_file_path_str));
+            return Err(AppError::NotFound(format!("Audio file not found at path: {}", audio_file_path_str)).into());
         }
 
         // 3. Create a Transcription State
@@ -88,90 +155,391 @@ impl DictationModel {
             .map_err(|e| anyhow!("Failed to create Whisper transcription state: {:?}", e))?;
 
         // 4. Load WAV File
-        let mut reader = hound::WavReader::open(audio_file_path).map_err(|e| {
-            anyhow!(
-                "Failed to open WAV audio file '{}': {}",
-                audio_file_path_str,
-                e
-            )
-        })?;
+        let audio_data_f32 = load_wav_samples(audio_file_path)?;
+
+        // 5. Split Audio Into Voiced Regions
+        let regions = transcription_regions(&audio_data_f32, &options.vad);
+
+        let total_duration_secs = samples_to_secs(audio_data_f32.len());
+        let voiced_duration_secs = regions.iter().map(|r| r.duration_secs()).sum::<f32>();
+        log::info!(
+            "[DictationModel] {} voiced region(s) found: {:.2}s of speech in {:.2}s of audio.",
+            regions.len(),
+            voiced_duration_secs,
+            total_duration_secs
+        );
+
+        // 6. Run Transcription On Each Region
+        log::debug!("[DictationModel] Using dictation options: {:?}", options);
+        let mut chunks: Vec<TranscribedChunk> = Vec::with_capacity(regions.len());
+        for region in &regions {
+            let chunk_audio = pad_to_min_whisper_length(&audio_data_f32[region.start_sample..region.end_sample]);
 
-        let wav_spec = reader.spec();
-        if wav_spec.sample_rate != 16000 {
-            return Err(anyhow!(
-                "Unsupported audio sample rate: {}. Whisper requires 16kHz.",
-                wav_spec.sample_rate
-            ));
+            state
+                .full(options.build_params(), &chunk_audio[..])
+                .map_err(|e| anyhow!("Transcription failed during full processing: {:?}", e))?;
+
+            // 7. Extract Transcribed Text
+            let num_segments = state
+                .full_n_segments()
+                .map_err(|e| anyhow!("Failed to get number of transcribed segments: {:?}", e))?;
+
+            let mut chunk_text = String::new();
+            for i in 0..num_segments {
+                let segment_text = state
+                    .full_get_segment_text(i)
+                    .map_err(|e| anyhow!("Failed to get text for segment {}: {:?}", i, e))?;
+                chunk_text.push_str(&segment_text);
+            }
+
+            let chunk_text = chunk_text.trim().to_string();
+            if !chunk_text.is_empty() {
+                chunks.push(TranscribedChunk {
+                    start_secs: samples_to_secs(region.start_sample),
+                    end_secs: samples_to_secs(region.end_sample),
+                    text: chunk_text,
+                });
+            }
         }
-        if wav_spec.channels != 1 {
-            return Err(anyhow!(
-                "Unsupported audio channel count: {}. Whisper requires mono (1 channel).",
-                wav_spec.channels
-            ));
+
+        // 8. Return The Transcribed Text
+        let full_text = chunks.iter().map(|c| c.text.as_str()).collect::<Vec<_>>().join(" ");
+        log::info!("[DictationModel] Transcription successful for '{}'. Length: {}", audio_file_path_str, full_text.len());
+        Ok(DictationReport {
+            text: full_text,
+            total_duration_secs,
+            voiced_duration_secs,
+            chunks,
+        })
+    }
+}
+
+fn load_wav_samples(audio_file_path: &Path) -> Result<Vec<f32>> {
+    let mut reader = hound::WavReader::open(audio_file_path).map_err(|e| {
+        anyhow!(
+            "Failed to open WAV audio file '{}': {}",
+            audio_file_path.display(),
+            e
+        )
+    })?;
+
+    let wav_spec = reader.spec();
+    if wav_spec.sample_rate != WHISPER_SAMPLE_RATE as u32 {
+        return Err(AppError::InvalidInput(format!(
+            "Unsupported audio sample rate: {}. Whisper requires 16kHz.",
+            wav_spec.sample_rate
+        )).into());
+    }
+    if wav_spec.channels != 1 {
+        return Err(AppError::InvalidInput(format!(
+            "Unsupported audio channel count: {}. Whisper requires mono (1 channel).",
+            wav_spec.channels
+        )).into());
+    }
+    if wav_spec.bits_per_sample != 16 || wav_spec.sample_format != hound::SampleFormat::Int {
+        return Err(AppError::InvalidInput(
+            "Unsupported audio sample format or bits per sample. Whisper requires 16-bit Integer PCM.".to_string()
+        ).into());
+    }
+
+    let samples_i16: Vec<i16> = reader
+        .samples::<i16>()
+        .collect::<std::result::Result<_, _>>()
+        .map_err(|e| anyhow!("Failed to read i16 samples from WAV file: {}", e))?;
+
+    let mut audio_data_f32: Vec<f32> = vec![0.0; samples_i16.len()];
+    convert_integer_to_float_audio(&samples_i16, &mut audio_data_f32)
+        .map_err(|e| anyhow!("Failed to convert audio samples from i16 to f32: {:?}", e))?;
+    Ok(audio_data_f32)
+}
+
+pub const WHISPER_SAMPLE_RATE: usize = 16000;
+
+// whisper.cpp silently skips inputs shorter than one second
+const MIN_WHISPER_CHUNK_MS: usize = 1100;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct VadOptions {
+    pub enabled: bool,
+    pub frame_ms: u32,
+    // RMS level a frame must reach to count as speech
+    pub energy_threshold: f32,
+    // pauses shorter than this stay inside the surrounding region
+    pub min_silence_ms: u32,
+    // voiced regions shorter than this are treated as noise
+    pub min_speech_ms: u32,
+    // silence kept on each side of a region so words are not clipped
+    pub padding_ms: u32,
+}
+
+impl Default for VadOptions {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            frame_ms: 30,
+            energy_threshold: 0.01,
+            min_silence_ms: 600,
+            min_speech_ms: 200,
+            padding_ms: 200,
         }
-        if wav_spec.bits_per_sample != 16 || wav_spec.sample_format != hound::SampleFormat::Int {
-            return Err(anyhow!(
-                "Unsupported audio sample format or bits per sample. Whisper requires 16-bit Integer PCM."
-            ));
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VoicedRegion {
+    pub start_sample: usize,
+    pub end_sample: usize,
+}
+
+impl VoicedRegion {
+    pub fn len(&self) -> usize {
+        self.end_sample - self.start_sample
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn duration_secs(&self) -> f32 {
+        samples_to_secs(self.len())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscribedChunk {
+    pub start_secs: f32,
+    pub end_secs: f32,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DictationReport {
+    pub text: String,
+    pub total_duration_secs: f32,
+    pub voiced_duration_secs: f32,
+    pub chunks: Vec<TranscribedChunk>,
+}
+
+fn samples_to_secs(samples: usize) -> f32 {
+    samples as f32 / WHISPER_SAMPLE_RATE as f32
+}
+
+fn ms_to_samples(ms: u32) -> usize {
+    ms as usize * WHISPER_SAMPLE_RATE / 1000
+}
+
+fn frame_rms(frame: &[f32]) -> f32 {
+    if frame.is_empty() {
+        return 0.0;
+    }
+    (frame.iter().map(|s| s * s).sum::<f32>() / frame.len() as f32).sqrt()
+}
+
+// the regions handed to Whisper; a quiet recording the energy detector finds no speech in is
+// transcribed whole rather than coming back empty
+pub fn transcription_regions(samples: &[f32], options: &VadOptions) -> Vec<VoicedRegion> {
+    let whole = VoicedRegion { start_sample: 0, end_sample: samples.len() };
+    if !options.enabled {
+        return vec![whole];
+    }
+    let regions = detect_voiced_regions(samples, options);
+    if regions.is_empty() && !samples.is_empty() {
+        log::info!("[DictationModel] No voiced region found; transcribing the whole recording.");
+        return vec![whole];
+    }
+    regions
+}
+
+pub fn detect_voiced_regions(samples: &[f32], options: &VadOptions) -> Vec<VoicedRegion> {
+    let frame_len = ms_to_samples(options.frame_ms).max(1);
+    let min_silence = ms_to_samples(options.min_silence_ms);
+    let min_speech = ms_to_samples(options.min_speech_ms);
+    let padding = ms_to_samples(options.padding_ms);
+
+    // 1. Collect runs of frames above the energy threshold
+    let mut runs: Vec<VoicedRegion> = Vec::new();
+    for (index, frame) in samples.chunks(frame_len).enumerate() {
+        if frame_rms(frame) < options.energy_threshold {
+            continue;
+        }
+        let start_sample = index * frame_len;
+        let end_sample = start_sample + frame.len();
+        match runs.last_mut() {
+            Some(last) if start_sample - last.end_sample < min_silence => last.end_sample = end_sample,
+            _ => runs.push(VoicedRegion { start_sample, end_sample }),
         }
+    }
 
-        let samples_i16: Vec<i16> = reader
-            .samples::<i16>()
-            .collect::<std::result::Result<_, _>>()
-            .map_err(|e| anyhow!("Failed to read i16 samples from WAV file: {}", e))?;
-
-        let mut audio_data_f32: Vec<f32> = vec![0.0; samples_i16.len()];
-        convert_integer_to_float_audio(&samples_i16, &mut audio_data_f32)
-            .map_err(|e| anyhow!("Failed to convert audio samples from i16 to f32: {:?}", e))?;
-
-        // 5. Set Transcription Parameters
-        let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
-        params.set_n_threads(4);
-        params.set_language(Some("en"));
-
-        // 6. Run Transcription
-        state
-            .full(params, &audio_data_f32[..])
-            .map_err(|e| anyhow!("Transcription failed during full processing: {:?}", e))?;
-
-        // 7. Extract Transcribed Text
-        let num_segments = state
-            .full_n_segments()
-            .map_err(|e| anyhow!("Failed to get number of transcribed segments: {:?}", e))?;
-
-        let mut full_text = String::new();
-        for i in 0..num_segments {
-            let segment_text = state
-                .full_get_segment_text(i)
-                .map_err(|e| anyhow!("Failed to get text for segment {}: {:?}", i, e))?;
-            full_text.push_str(&segment_text);
+    // 2. Drop short bursts, pad the rest and merge regions the padding made overlap
+    let mut regions: Vec<VoicedRegion> = Vec::new();
+    for run in runs.into_iter().filter(|r| r.len() >= min_speech) {
+        let padded = VoicedRegion {
+            start_sample: run.start_sample.saturating_sub(padding),
+            end_sample: (run.end_sample + padding).min(samples.len()),
+        };
+        match regions.last_mut() {
+            Some(last) if padded.start_sample <= last.end_sample => last.end_sample = padded.end_sample,
+            _ => regions.push(padded),
         }
-        
-        // 8. Return The Transcribed Text
-        log::info!("[DictationModel] Transcription successful for '{}'. Length: {}", audio_file_path_str, full_text.len());
-        Ok(full_text.trim().to_string())
-    }
-}
-
-#[tauri::command]
-pub async fn perform_dictation_cmd(
-    audio_file_path: String,
-    dictation_model_state: tauri::State<'_, crate::AppDictationModel>,
-) -> Result<String, String> {
-    log::info!("[CMD perform_dictation_cmd] Received request for audio file: {}", audio_file_path);
-
-    let model_arc = dictation_model_state.inner().0.clone();
-
-    tokio::task::spawn_blocking(move || {
-        model_arc.0.transcribe(&audio_file_path)
-    })
-    .await
-    .map_err(|e| { 
-        log::error!("[CMD perform_dictation_cmd] Task join error: {}", e);
-        format!("Task join error during transcription: {}", e)
-    })?
-    .map_err(|e| { 
-        log::error!("[CMD perform_dictation_cmd] Transcription error: {}", e);
-        e.to_string()
-    })
-}
\ No newline at end of file
+    }
+    regions
+}
+
+fn pad_to_min_whisper_length(chunk: &[f32]) -> Vec<f32> {
+    let min_len = MIN_WHISPER_CHUNK_MS * WHISPER_SAMPLE_RATE / 1000;
+    let mut padded = chunk.to_vec();
+    if padded.len() < min_len {
+        padded.resize(min_len, 0.0);
+    }
+    padded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_options_use_greedy_english() {
+        let options = DictationOptions::default();
+        assert!(matches!(options.sampling_strategy(), SamplingStrategy::Greedy { best_of: 1 }));
+        assert_eq!(options.language.as_deref(), Some("en"));
+        assert!(!options.translate);
+        assert!(options.resolved_n_threads() >= 1, "Thread count should default to available cores.");
+    }
+
+    #[test]
+    fn test_beam_size_selects_beam_search() {
+        let options = DictationOptions { beam_size: Some(5), ..Default::default() };
+        assert!(matches!(options.sampling_strategy(), SamplingStrategy::BeamSearch { beam_size: 5, .. }));
+
+        let single_beam = DictationOptions { beam_size: Some(1), best_of: 3, ..Default::default() };
+        assert!(matches!(single_beam.sampling_strategy(), SamplingStrategy::Greedy { best_of: 3 }));
+    }
+
+    #[test]
+    fn test_explicit_thread_count_is_respected() {
+        let options = DictationOptions { n_threads: Some(2), ..Default::default() };
+        assert_eq!(options.resolved_n_threads(), 2);
+
+        let invalid = DictationOptions { n_threads: Some(0), ..Default::default() };
+        assert!(invalid.resolved_n_threads() >= 1);
+    }
+
+    #[test]
+    fn test_options_deserialize_from_partial_camel_case_json() {
+        let options: DictationOptions = serde_json::from_str(r#"{"beamSize": 4, "initialPrompt": "MoodJourney", "translate": true}"#)
+            .expect("Failed to deserialize dictation options");
+        assert_eq!(options.beam_size, Some(4));
+        assert_eq!(options.initial_prompt.as_deref(), Some("MoodJourney"));
+        assert!(options.translate);
+        assert_eq!(options.best_of, 1);
+    }
+
+    fn synthetic_tone(duration_ms: u32, amplitude: f32) -> Vec<f32> {
+        let len = ms_to_samples(duration_ms);
+        (0..len)
+            .map(|i| amplitude * (2.0 * std::f32::consts::PI * 220.0 * i as f32 / WHISPER_SAMPLE_RATE as f32).sin())
+            .collect()
+    }
+
+    fn synthetic_silence(duration_ms: u32) -> Vec<f32> {
+        vec![0.0; ms_to_samples(duration_ms)]
+    }
+
+    fn concat(parts: &[Vec<f32>]) -> Vec<f32> {
+        parts.iter().flatten().copied().collect()
+    }
+
+    #[test]
+    fn test_vad_silence_only_yields_no_regions() {
+        let audio = synthetic_silence(3000);
+        let regions = detect_voiced_regions(&audio, &VadOptions::default());
+        assert!(regions.is_empty(), "Expected no voiced regions in pure silence, got {:?}", regions);
+    }
+
+    #[test]
+    fn test_vad_splits_speech_on_long_silence() {
+        let audio = concat(&[
+            synthetic_silence(1000),
+            synthetic_tone(1200, 0.5),
+            synthetic_silence(2000),
+            synthetic_tone(800, 0.5),
+            synthetic_silence(1000),
+        ]);
+        let options = VadOptions::default();
+        let regions = detect_voiced_regions(&audio, &options);
+        assert_eq!(regions.len(), 2, "Expected two voiced regions, got {:?}", regions);
+
+        let padding_secs = options.padding_ms as f32 / 1000.0;
+        let first_secs = regions[0].duration_secs();
+        let second_secs = regions[1].duration_secs();
+        assert!((first_secs - (1.2 + 2.0 * padding_secs)).abs() < 0.05, "First region duration was {}", first_secs);
+        assert!((second_secs - (0.8 + 2.0 * padding_secs)).abs() < 0.05, "Second region duration was {}", second_secs);
+        assert!(regions[0].start_sample < ms_to_samples(1000));
+        assert!(regions[1].start_sample > ms_to_samples(2200));
+    }
+
+    #[test]
+    fn test_vad_keeps_short_pauses_inside_one_region() {
+        let audio = concat(&[
+            synthetic_tone(700, 0.5),
+            synthetic_silence(300),
+            synthetic_tone(700, 0.5),
+        ]);
+        let regions = detect_voiced_regions(&audio, &VadOptions::default());
+        assert_eq!(regions.len(), 1, "Short pause should not split the region: {:?}", regions);
+        assert_eq!(regions[0], VoicedRegion { start_sample: 0, end_sample: audio.len() });
+    }
+
+    #[test]
+    fn test_vad_ignores_short_noise_bursts_and_quiet_audio() {
+        let audio = concat(&[
+            synthetic_silence(1000),
+            synthetic_tone(60, 0.8),
+            synthetic_silence(1000),
+            synthetic_tone(1000, 0.001),
+            synthetic_silence(1000),
+        ]);
+        let regions = detect_voiced_regions(&audio, &VadOptions::default());
+        assert!(regions.is_empty(), "Bursts and quiet audio should be skipped, got {:?}", regions);
+    }
+
+    #[test]
+    fn test_quiet_recordings_fall_back_to_the_whole_audio() {
+        let audio = concat(&[synthetic_silence(500), synthetic_tone(1500, 0.005), synthetic_silence(500)]);
+        let options = VadOptions::default();
+        assert!(detect_voiced_regions(&audio, &options).is_empty(), "The tone should stay below the energy threshold.");
+        assert_eq!(transcription_regions(&audio, &options), vec![VoicedRegion { start_sample: 0, end_sample: audio.len() }]);
+
+        let speech = concat(&[synthetic_silence(1000), synthetic_tone(1200, 0.5), synthetic_silence(1000)]);
+        assert_eq!(transcription_regions(&speech, &options), detect_voiced_regions(&speech, &options));
+        assert!(transcription_regions(&[], &options).is_empty());
+    }
+
+    #[test]
+    fn test_missing_model_is_reported_without_loading() {
+        let mut models_dir = std::env::temp_dir();
+        models_dir.push("moodjourney_dictation_tests");
+        models_dir.push("missing_model");
+        let model_name = "ggml-does-not-exist.bin";
+
+        let result = DictationModel::new(&models_dir.join("dictation").join(model_name));
+        assert!(result.is_err(), "Loading a missing model should fail.");
+        let error = find_model_file(&models_dir, model_name).expect_err("A missing model should not be found.");
+        assert!(error.to_string().contains(model_name), "Error should name the model: {}", error);
+    }
+
+    #[test]
+    fn test_short_chunks_are_padded_for_whisper() {
+        let chunk = synthetic_tone(400, 0.5);
+        let padded = pad_to_min_whisper_length(&chunk);
+        assert_eq!(padded.len(), MIN_WHISPER_CHUNK_MS * WHISPER_SAMPLE_RATE / 1000);
+        assert_eq!(&padded[..chunk.len()], &chunk[..]);
+
+        let long_chunk = synthetic_tone(2000, 0.5);
+        assert_eq!(pad_to_min_whisper_length(&long_chunk).len(), long_chunk.len());
+    }
+}
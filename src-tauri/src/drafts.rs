@@ -0,0 +1,196 @@
+use crate::db::{Database, Draft, Entry};
+use crate::error::AppError;
+use crate::export::{EMOTION_MARKER, SUGGESTION_MARKER};
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+// the slot of the entry being written on the new entry page; other slots are the date of the entry being edited
+pub const NEW_ENTRY_SLOT: &str = "new";
+// a save waits this long and is dropped if a newer one for the same slot arrives meanwhile
+pub const SAVE_DEBOUNCE: Duration = Duration::from_millis(1500);
+// while the user keeps typing, a slot is still written at least this often
+pub const MAX_SAVE_DELAY: Duration = Duration::from_secs(10);
+const DEFAULT_ENTRY_TITLE: &str = "Journal Entry";
+
+pub fn validate_slot(slot: &str) -> Result<()> {
+    if slot == NEW_ENTRY_SLOT || NaiveDate::parse_from_str(slot, "%Y-%m-%d").is_ok() {
+        return Ok(());
+    }
+    Err(AppError::InvalidInput(format!("A draft slot must be '{}' or a date in YYYY-MM-DD format, got '{}'.", NEW_ENTRY_SLOT, slot)).into())
+}
+
+#[derive(Default)]
+struct SlotState {
+    generation: u64,
+    last_written: Option<Instant>,
+}
+
+// tracks the autosaves in flight so only the newest of a burst reaches the database
+pub struct DraftState {
+    // drafts last written before this were left over from an earlier run
+    pub started_at: DateTime<Utc>,
+    slots: Mutex<HashMap<String, SlotState>>,
+}
+
+impl Default for DraftState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DraftState {
+    pub fn new() -> Self {
+        Self { started_at: Utc::now(), slots: Mutex::new(HashMap::new()) }
+    }
+
+    fn with_slot<T>(&self, slot: &str, f: impl FnOnce(&mut SlotState) -> T) -> T {
+        let mut slots = self.slots.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        f(slots.entry(slot.to_string()).or_default())
+    }
+
+    // returns the generation of this save and whether it is overdue and should be written without waiting
+    pub fn begin_save(&self, slot: &str) -> (u64, bool) {
+        self.with_slot(slot, |state| {
+            state.generation += 1;
+            let overdue = state.last_written.is_none_or(|written| written.elapsed() >= MAX_SAVE_DELAY);
+            (state.generation, overdue)
+        })
+    }
+
+    pub fn is_current(&self, slot: &str, generation: u64) -> bool {
+        self.with_slot(slot, |state| state.generation == generation)
+    }
+
+    pub fn mark_written(&self, slot: &str) {
+        self.with_slot(slot, |state| state.last_written = Some(Instant::now()));
+    }
+
+    // drops the saves still waiting for the slot, once it is discarded or saved as an entry
+    pub fn invalidate(&self, slot: &str) {
+        self.with_slot(slot, |state| {
+            state.generation += 1;
+            state.last_written = None;
+        });
+    }
+}
+
+pub fn is_stale(draft: &Draft, started_at: DateTime<Utc>) -> bool {
+    DateTime::parse_from_rfc3339(&draft.updated_at).map_or(true, |updated_at| updated_at < started_at)
+}
+
+// drafts only hold what the user wrote, so the emotion and suggestions already saved with the entry are kept
+fn with_existing_analysis(existing: &str, body: &str) -> String {
+    let analysis_start = [existing.find(EMOTION_MARKER), existing.find(SUGGESTION_MARKER)].into_iter().flatten().min();
+    match analysis_start {
+        Some(start) => format!("{}{}", body.trim_end(), &existing[start..]),
+        None => body.to_string(),
+    }
+}
+
+// turns a draft into its entry and removes it; the new entry slot becomes the entry for `today`.
+// An existing entry is updated, so the text it had is kept as a revision. Returns the entry date.
+pub fn promote_draft(db: &Database, slot: &str, today: &str) -> Result<String> {
+    validate_slot(slot)?;
+    let draft = db.get_draft(slot)?
+        .ok_or_else(|| AppError::NotFound(format!("There is no draft for {}.", slot)))?;
+    let date = if slot == NEW_ENTRY_SLOT { today } else { slot };
+
+    match db.get_entry_by_date(date)? {
+        Some(entry) => {
+            let content = with_existing_analysis(entry.content.as_deref().unwrap_or(""), &draft.content);
+            let title = draft.title.or(entry.title);
+            db.update_entry_by_date(date, title.as_deref(), Some(&content), entry.password.as_deref(), entry.image.as_deref())?;
+        }
+        None => db.add_entry(Entry {
+            date: date.to_string(),
+            title: Some(draft.title.unwrap_or_else(|| DEFAULT_ENTRY_TITLE.to_string())),
+            content: Some(draft.content),
+            password: None,
+            image: None,
+        })?,
+    }
+    db.delete_draft(slot)?;
+    Ok(date.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn setup(test_name: &str) -> (PathBuf, Database) {
+        let mut dir = std::env::temp_dir();
+        dir.push("moodjourney_draft_tests");
+        dir.push(test_name);
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).expect("Failed to create test directory");
+        let db = Database::open(&dir.join("journal.db")).expect("Failed to open database");
+        (dir, db)
+    }
+
+    #[test]
+    fn test_only_the_newest_save_is_current() {
+        let state = DraftState::new();
+        let (first, overdue) = state.begin_save(NEW_ENTRY_SLOT);
+        assert!(overdue, "The first save of a slot should be written right away.");
+        state.mark_written(NEW_ENTRY_SLOT);
+
+        let (second, overdue) = state.begin_save(NEW_ENTRY_SLOT);
+        assert!(!overdue, "A save right after a write should wait.");
+        let (third, _) = state.begin_save(NEW_ENTRY_SLOT);
+        assert!(!state.is_current(NEW_ENTRY_SLOT, first) && !state.is_current(NEW_ENTRY_SLOT, second));
+        assert!(state.is_current(NEW_ENTRY_SLOT, third));
+        assert!(state.begin_save("2025-06-01").1, "Slots should be debounced separately.");
+
+        state.invalidate(NEW_ENTRY_SLOT);
+        assert!(!state.is_current(NEW_ENTRY_SLOT, third), "A discard should drop the waiting save.");
+    }
+
+    #[test]
+    fn test_save_load_and_stale_drafts() {
+        let (dir, db) = setup("save");
+        assert_eq!(AppError::code_of(&validate_slot("tomorrow").unwrap_err()), "INVALID_INPUT");
+
+        db.save_draft(NEW_ENTRY_SLOT, None, "First words").expect("save failed");
+        let started_at = Utc::now();
+        db.save_draft(NEW_ENTRY_SLOT, Some("Title"), "First words, then more").expect("save failed");
+        let draft = db.get_draft(NEW_ENTRY_SLOT).expect("load failed").expect("draft missing");
+        assert_eq!(draft.content, "First words, then more");
+        assert_eq!(draft.title.as_deref(), Some("Title"));
+        assert!(!is_stale(&draft, started_at), "A draft saved in this run should not be stale.");
+        assert!(is_stale(&draft, Utc::now() + chrono::Duration::seconds(1)));
+
+        assert!(db.delete_draft(NEW_ENTRY_SLOT).expect("delete failed"));
+        assert!(db.get_drafts().expect("list failed").is_empty());
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_promote_creates_or_updates_the_entry() {
+        let (dir, db) = setup("promote");
+        db.save_draft(NEW_ENTRY_SLOT, None, "A quiet morning.").expect("save failed");
+        assert_eq!(promote_draft(&db, NEW_ENTRY_SLOT, "2025-06-01").expect("promote failed"), "2025-06-01");
+        let entry = db.get_entry_by_date("2025-06-01").expect("get failed").expect("entry missing");
+        assert_eq!(entry.title.as_deref(), Some(DEFAULT_ENTRY_TITLE));
+        assert_eq!(entry.content.as_deref(), Some("A quiet morning."));
+        assert!(db.get_draft(NEW_ENTRY_SLOT).expect("load failed").is_none(), "A promoted draft should be removed.");
+
+        db.update_entry_by_date("2025-06-01", Some("Morning"), Some("A quiet morning.\n\n🧠 Emotion: joy\n\n💡 Suggestion: - Walk"), Some("hash"), None)
+            .expect("update failed");
+        db.save_draft("2025-06-01", None, "A quiet, rainy morning.\n").expect("save failed");
+        promote_draft(&db, "2025-06-01", "2025-06-02").expect("promote failed");
+        let entry = db.get_entry_by_date("2025-06-01").expect("get failed").expect("entry missing");
+        assert_eq!(entry.title.as_deref(), Some("Morning"));
+        assert_eq!(entry.content.as_deref(), Some("A quiet, rainy morning.\n\n🧠 Emotion: joy\n\n💡 Suggestion: - Walk"));
+        assert_eq!(entry.password.as_deref(), Some("hash"), "The password should be kept.");
+        assert_eq!(db.get_entry_revisions("2025-06-01").expect("revisions failed")[0].content.as_deref(), Some("A quiet morning.\n\n🧠 Emotion: joy\n\n💡 Suggestion: - Walk"));
+
+        let err = promote_draft(&db, "2025-06-01", "2025-06-02").unwrap_err();
+        assert_eq!(AppError::code_of(&err), "NOT_FOUND");
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -4,6 +4,7 @@ use rust_bert::pipelines::common::ModelType;
 use tch::Device;
 use std::path::PathBuf;
 use anyhow::{Result, anyhow};
+use crate::error::AppError;
 
 pub struct EmotionModel {
     model: SequenceClassificationModel,
@@ -19,16 +20,16 @@ impl EmotionModel {
         let model_weights_path = model_base_path.join("model.safetensors"); 
 
         if !config_path.exists() {
-            return Err(anyhow!("Emotion model config.json not found at {:?}", config_path));
+            return Err(AppError::ModelUnavailable(format!("Emotion model config.json not found at {:?}", config_path)).into());
         }
         if !vocab_path.exists() {
-            return Err(anyhow!("Emotion model vocab.json not found at {:?}", vocab_path));
+            return Err(AppError::ModelUnavailable(format!("Emotion model vocab.json not found at {:?}", vocab_path)).into());
         }
         if !merges_path.exists() {
-            return Err(anyhow!("Emotion model merges.txt not found at {:?}", merges_path));
+            return Err(AppError::ModelUnavailable(format!("Emotion model merges.txt not found at {:?}", merges_path)).into());
         }
         if !model_weights_path.exists() {
-            return Err(anyhow!("Emotion model model.safetensors not found at {:?}", model_weights_path));
+            return Err(AppError::ModelUnavailable(format!("Emotion model model.safetensors not found at {:?}", model_weights_path)).into());
         }
 
         let config_resource = LocalResource { local_path: config_path };
@@ -60,7 +61,7 @@ impl EmotionModel {
     pub fn classify(&self, text: &str) -> Result<String> {
         if text.trim().is_empty() {
             log::warn!("[EmotionModel] Attempted to classify with an empty text.");
-            return Err(anyhow!("Input text for emotion classification cannot be empty."));
+            return Err(AppError::InvalidInput("Input text for emotion classification cannot be empty.".to_string()).into());
         }
         log::debug!("[EmotionModel] Classifying emotion for text: \"{}\"", text.trim());
 
@@ -77,24 +78,6 @@ impl EmotionModel {
     }
 }
 
-#[tauri::command]
-pub fn classify_emotion(
-    text: String,
-    emotion_model_state: tauri::State<'_, crate::AppEmotionModel>, 
-) -> Result<String, String> {
-    log::debug!("[CMD classify_emotion] Received text: \"{}\"", text);
-    let model_wrapper_arc = &emotion_model_state.inner().0; 
-    let emotion_model_in_wrapper = &model_wrapper_arc.0;   
-    
-    match emotion_model_in_wrapper.classify(&text) {
-        Ok(emotion) => Ok(emotion),
-        Err(e) => {
-            log::error!("[CMD classify_emotion] Error classifying emotion: {}", e);
-            Err(format!("Failed to classify emotion: {}", e))
-        }
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
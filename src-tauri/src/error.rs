@@ -0,0 +1,231 @@
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use std::fmt;
+
+pub type Result<T> = std::result::Result<T, AppError>;
+
+// the error every command and the CLI report; commands serialize it as { code, message }
+// and the frontend matches on the code, so existing codes must not change
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AppError {
+    NotFound(String),
+    Duplicate(String),
+    InvalidDate(String),
+    InvalidInput(String),
+    Locked(String),
+    WrongPassword(String),
+    PassphraseRequired(String),
+    NotConfigured(String),
+    ModelUnavailable(String),
+    ApiKeyMissing(String),
+    Network(String),
+    RateLimited(String),
+    TooLarge(String),
+    UnsupportedImage(String),
+    InvalidFile(String),
+    UnsupportedVersion(String),
+    Corrupted(String),
+    Io(String),
+    Database(String),
+    Internal(String),
+}
+
+impl AppError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::NotFound(_) => "NOT_FOUND",
+            AppError::Duplicate(_) => "DUPLICATE",
+            AppError::InvalidDate(_) => "INVALID_DATE",
+            AppError::InvalidInput(_) => "INVALID_INPUT",
+            AppError::Locked(_) => "LOCKED",
+            AppError::WrongPassword(_) => "WRONG_PASSWORD",
+            AppError::PassphraseRequired(_) => "PASSPHRASE_REQUIRED",
+            AppError::NotConfigured(_) => "NOT_CONFIGURED",
+            AppError::ModelUnavailable(_) => "MODEL_UNAVAILABLE",
+            AppError::ApiKeyMissing(_) => "API_KEY_MISSING",
+            AppError::Network(_) => "NETWORK",
+            AppError::RateLimited(_) => "RATE_LIMITED",
+            AppError::TooLarge(_) => "TOO_LARGE",
+            AppError::UnsupportedImage(_) => "UNSUPPORTED_IMAGE",
+            AppError::InvalidFile(_) => "INVALID_FILE",
+            AppError::UnsupportedVersion(_) => "UNSUPPORTED_VERSION",
+            AppError::Corrupted(_) => "CORRUPTED",
+            AppError::Io(_) => "IO",
+            AppError::Database(_) => "DATABASE",
+            AppError::Internal(_) => "INTERNAL",
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            AppError::NotFound(message)
+            | AppError::Duplicate(message)
+            | AppError::InvalidDate(message)
+            | AppError::InvalidInput(message)
+            | AppError::Locked(message)
+            | AppError::WrongPassword(message)
+            | AppError::PassphraseRequired(message)
+            | AppError::NotConfigured(message)
+            | AppError::ModelUnavailable(message)
+            | AppError::ApiKeyMissing(message)
+            | AppError::Network(message)
+            | AppError::RateLimited(message)
+            | AppError::TooLarge(message)
+            | AppError::UnsupportedImage(message)
+            | AppError::InvalidFile(message)
+            | AppError::UnsupportedVersion(message)
+            | AppError::Corrupted(message)
+            | AppError::Io(message)
+            | AppError::Database(message)
+            | AppError::Internal(message) => message,
+        }
+    }
+
+    // same kind, new text; used to prefix context without losing the code
+    pub fn with_message(&self, message: impl Into<String>) -> AppError {
+        let message = message.into();
+        match self {
+            AppError::NotFound(_) => AppError::NotFound(message),
+            AppError::Duplicate(_) => AppError::Duplicate(message),
+            AppError::InvalidDate(_) => AppError::InvalidDate(message),
+            AppError::InvalidInput(_) => AppError::InvalidInput(message),
+            AppError::Locked(_) => AppError::Locked(message),
+            AppError::WrongPassword(_) => AppError::WrongPassword(message),
+            AppError::PassphraseRequired(_) => AppError::PassphraseRequired(message),
+            AppError::NotConfigured(_) => AppError::NotConfigured(message),
+            AppError::ModelUnavailable(_) => AppError::ModelUnavailable(message),
+            AppError::ApiKeyMissing(_) => AppError::ApiKeyMissing(message),
+            AppError::Network(_) => AppError::Network(message),
+            AppError::RateLimited(_) => AppError::RateLimited(message),
+            AppError::TooLarge(_) => AppError::TooLarge(message),
+            AppError::UnsupportedImage(_) => AppError::UnsupportedImage(message),
+            AppError::InvalidFile(_) => AppError::InvalidFile(message),
+            AppError::UnsupportedVersion(_) => AppError::UnsupportedVersion(message),
+            AppError::Corrupted(_) => AppError::Corrupted(message),
+            AppError::Io(_) => AppError::Io(message),
+            AppError::Database(_) => AppError::Database(message),
+            AppError::Internal(_) => AppError::Internal(message),
+        }
+    }
+
+    // the code of an error raised inside an anyhow chain, INTERNAL when it is untyped
+    pub fn code_of(error: &anyhow::Error) -> &'static str {
+        error.chain()
+            .find_map(|cause| cause.downcast_ref::<AppError>())
+            .map(AppError::code)
+            .unwrap_or("INTERNAL")
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("AppError", 2)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", self.message())?;
+        state.end()
+    }
+}
+
+impl From<rusqlite::Error> for AppError {
+    fn from(error: rusqlite::Error) -> Self {
+        match &error {
+            rusqlite::Error::QueryReturnedNoRows => AppError::NotFound(error.to_string()),
+            rusqlite::Error::SqliteFailure(e, _)
+                if e.extended_code == rusqlite::ffi::SQLITE_CONSTRAINT_PRIMARYKEY
+                    || e.extended_code == rusqlite::ffi::SQLITE_CONSTRAINT_UNIQUE =>
+            {
+                AppError::Duplicate(error.to_string())
+            }
+            _ => AppError::Database(error.to_string()),
+        }
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(error: std::io::Error) -> Self {
+        match error.kind() {
+            std::io::ErrorKind::NotFound => AppError::NotFound(error.to_string()),
+            _ => AppError::Io(error.to_string()),
+        }
+    }
+}
+
+impl From<reqwest::Error> for AppError {
+    fn from(error: reqwest::Error) -> Self {
+        if error.status().is_some_and(|status| status.as_u16() == 429) {
+            AppError::RateLimited(error.to_string())
+        } else {
+            AppError::Network(error.to_string())
+        }
+    }
+}
+
+// keeps the kind of the first typed cause and the full context chain as the message
+impl From<anyhow::Error> for AppError {
+    fn from(error: anyhow::Error) -> Self {
+        let message = format!("{:#}", error);
+        for cause in error.chain() {
+            if let Some(app_error) = cause.downcast_ref::<AppError>() {
+                return app_error.with_message(message);
+            }
+            if let Some(e) = cause.downcast_ref::<rusqlite::Error>() {
+                return AppError::from(clone_sqlite_error(e)).with_message(message);
+            }
+            if let Some(e) = cause.downcast_ref::<std::io::Error>() {
+                return AppError::from(std::io::Error::from(e.kind())).with_message(message);
+            }
+            if cause.downcast_ref::<reqwest::Error>().is_some() {
+                return AppError::Network(message);
+            }
+        }
+        AppError::Internal(message)
+    }
+}
+
+// rusqlite errors are not Clone; only the variant matters for classification
+fn clone_sqlite_error(error: &rusqlite::Error) -> rusqlite::Error {
+    match error {
+        rusqlite::Error::QueryReturnedNoRows => rusqlite::Error::QueryReturnedNoRows,
+        rusqlite::Error::SqliteFailure(e, _) => rusqlite::Error::SqliteFailure(*e, None),
+        _ => rusqlite::Error::InvalidQuery,
+    }
+}
+
+impl From<tokio::task::JoinError> for AppError {
+    fn from(error: tokio::task::JoinError) -> Self {
+        AppError::Internal(error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn test_serializes_code_and_message() {
+        let error = AppError::NotFound("No entry for 2025-01-01.".to_string());
+        let json = serde_json::to_value(&error).expect("serialize failed");
+        assert_eq!(json, serde_json::json!({ "code": "NOT_FOUND", "message": "No entry for 2025-01-01." }));
+    }
+
+    #[test]
+    fn test_anyhow_chain_keeps_the_typed_kind() {
+        let wrapped = anyhow::Error::from(AppError::InvalidDate("bad date".to_string())).context("Failed to export");
+        assert_eq!(AppError::code_of(&wrapped), "INVALID_DATE");
+        assert_eq!(AppError::from(wrapped), AppError::InvalidDate("Failed to export: bad date".to_string()));
+
+        let untyped = anyhow::anyhow!("something else");
+        assert_eq!(AppError::from(untyped).code(), "INTERNAL");
+
+        let io = Err::<(), _>(std::io::Error::from(std::io::ErrorKind::PermissionDenied)).context("Failed to write").unwrap_err();
+        assert_eq!(AppError::from(io).code(), "IO");
+    }
+}
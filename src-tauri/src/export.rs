@@ -0,0 +1,864 @@
+use crate::attachments;
+use crate::calendar;
+use crate::db::{Database, Entry};
+use crate::error::AppError;
+use crate::images;
+use anyhow::{Result, anyhow};
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64_STANDARD};
+use chrono::{NaiveDate, Utc};
+use pdf_writer::{Content, Filter, Finish, Name, Pdf, Rect, Ref, Str};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+pub const EMOTION_MARKER: &str = "\n\n🧠 Emotion:";
+pub const SUGGESTION_MARKER: &str = "\n\n💡 Suggestion:";
+const MARKDOWN_IMAGES_DIR_NAME: &str = "images";
+const BOOK_TITLE: &str = "MoodJourney Journal";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ExportFormat {
+    Markdown,
+    Html,
+    Pdf,
+    // iCalendar file with one all-day event per entry
+    Ics,
+}
+
+impl ExportFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "markdown" | "md" => Some(Self::Markdown),
+            "html" => Some(Self::Html),
+            "pdf" => Some(Self::Pdf),
+            "ics" | "ical" | "calendar" => Some(Self::Ics),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ExportOptions {
+    pub from_date: Option<String>,
+    pub to_date: Option<String>,
+    // case-insensitive; empty keeps every entry
+    pub emotions: Vec<String>,
+    // password-protected entries are left out unless this is set
+    pub include_protected: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedEntry {
+    pub date: String,
+    pub title: String,
+    pub body: String,
+    pub emotion: Option<String>,
+    pub suggestions: Vec<String>,
+    // paths relative to the app local data dir, cover image first
+    pub images: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportReport {
+    pub output_path: String,
+    pub entry_count: usize,
+    pub image_count: usize,
+    pub skipped_protected: usize,
+    pub missing_images: Vec<String>,
+}
+
+// splits stored content into the text the user wrote, the classified emotion and the suggestions,
+// mirroring getMainContent / extractEmotionFromContent / parseSuggestions on the frontend
+pub fn parse_entry_content(full_content: &str) -> (String, Option<String>, Vec<String>) {
+    let emotion_index = full_content.find(EMOTION_MARKER);
+    let suggestion_index = full_content.find(SUGGESTION_MARKER);
+    let end_of_body = [emotion_index, suggestion_index].into_iter().flatten().min().unwrap_or(full_content.len());
+    let body = full_content[..end_of_body].trim().to_string();
+
+    let emotion = emotion_index.and_then(|index| {
+        let word: String = full_content[index + EMOTION_MARKER.len()..]
+            .trim_start()
+            .chars()
+            .take_while(|c| c.is_alphanumeric() || *c == '_')
+            .collect();
+        Some(word).filter(|w| !w.is_empty())
+    });
+
+    let suggestions = suggestion_index
+        .map(|index| split_suggestions(&full_content[index + SUGGESTION_MARKER.len()..]))
+        .unwrap_or_default();
+
+    (body, emotion, suggestions)
+}
+
+// the first max_chars characters of the text on one line, cut at a word boundary when possible
+pub fn excerpt(text: &str, max_chars: usize) -> String {
+    let flattened = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if flattened.chars().count() <= max_chars {
+        return flattened;
+    }
+    let cut: String = flattened.chars().take(max_chars).collect();
+    let trimmed = match cut.rfind(' ') {
+        Some(index) if index > max_chars / 2 => &cut[..index],
+        _ => cut.as_str(),
+    };
+    format!("{}…", trimmed.trim_end_matches([',', ';', ':', '.', ' ']))
+}
+
+fn split_suggestions(suggestion_text: &str) -> Vec<String> {
+    let trimmed = suggestion_text.trim();
+    if trimmed.is_empty() || trimmed.eq_ignore_ascii_case("suggestion not available.") {
+        return Vec::new();
+    }
+    let separator = if trimmed.contains("\n\n") { "\n\n" } else { "\n" };
+    trimmed.split(separator)
+        .map(|s| s.trim())
+        .map(|s| s.strip_prefix("- ").unwrap_or(s).trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+fn parse_filter_date(value: Option<&str>, label: &str) -> Result<Option<NaiveDate>> {
+    match value.map(str::trim).filter(|v| !v.is_empty()) {
+        Some(v) => NaiveDate::parse_from_str(v, "%Y-%m-%d")
+            .map(Some)
+            .map_err(|_| AppError::InvalidDate(format!("The {} date must be in YYYY-MM-DD format, got '{}'.", label, v)).into()),
+        None => Ok(None),
+    }
+}
+
+fn is_protected(entry: &Entry) -> bool {
+    entry.password.as_deref().is_some_and(|p| !p.is_empty())
+}
+
+fn entry_images(db: &Database, entry: &Entry) -> Result<Vec<String>> {
+    let mut image_paths: Vec<String> = db.get_attachments_for_entry(&entry.date)?
+        .into_iter()
+        .filter(|a| a.kind == "image")
+        .map(|a| a.relative_path)
+        .collect();
+    if let Some(cover) = entry.image.as_deref().filter(|p| !p.trim().is_empty()) {
+        image_paths.retain(|p| p != cover);
+        image_paths.insert(0, cover.to_string());
+    }
+    Ok(image_paths)
+}
+
+// oldest first; the second value counts password-protected entries that were left out
+pub fn select_entries(db: &Database, options: &ExportOptions) -> Result<(Vec<ExportedEntry>, usize)> {
+    let from_date = parse_filter_date(options.from_date.as_deref(), "start")?;
+    let to_date = parse_filter_date(options.to_date.as_deref(), "end")?;
+    if let (Some(from), Some(to)) = (from_date, to_date) {
+        if from > to {
+            return Err(AppError::InvalidDate(format!("The start date {} is after the end date {}.", from, to)).into());
+        }
+    }
+
+    let mut entries = db.get_entries()?;
+    entries.reverse();
+
+    let mut selected = Vec::new();
+    let mut skipped_protected = 0;
+    for entry in entries {
+        let date = match NaiveDate::parse_from_str(&entry.date, "%Y-%m-%d") {
+            Ok(date) => date,
+            Err(_) => continue,
+        };
+        if from_date.is_some_and(|from| date < from) || to_date.is_some_and(|to| date > to) {
+            continue;
+        }
+
+        let (body, emotion, suggestions) = parse_entry_content(entry.content.as_deref().unwrap_or(""));
+        if !options.emotions.is_empty() {
+            let matches = emotion.as_deref()
+                .is_some_and(|e| options.emotions.iter().any(|wanted| wanted.trim().eq_ignore_ascii_case(e)));
+            if !matches {
+                continue;
+            }
+        }
+        if is_protected(&entry) && !options.include_protected {
+            skipped_protected += 1;
+            continue;
+        }
+
+        selected.push(ExportedEntry {
+            images: entry_images(db, &entry)?,
+            title: entry.title.clone().filter(|t| !t.trim().is_empty()).unwrap_or_else(|| entry.date.clone()),
+            date: entry.date,
+            body,
+            emotion,
+            suggestions,
+        });
+    }
+    Ok((selected, skipped_protected))
+}
+
+fn write_atomically(output_path: &Path, bytes: &[u8]) -> Result<()> {
+    if let Some(parent_dir) = output_path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent_dir)
+            .map_err(|e| anyhow!("Failed to create export directory {:?}: {}", parent_dir, e))?;
+    }
+    let partial_path = output_path.with_extension("partial");
+    fs::write(&partial_path, bytes).map_err(|e| anyhow!("Failed to write export to {:?}: {}", partial_path, e))?;
+    fs::rename(&partial_path, output_path).map_err(|e| anyhow!("Failed to finalize export at {:?}: {}", output_path, e))
+}
+
+// ---- Markdown ----
+
+// JSON strings are valid double-quoted YAML scalars
+fn yaml_string(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| "\"\"".to_string())
+}
+
+pub fn render_markdown(entry: &ExportedEntry, image_links: &[String]) -> String {
+    let mut markdown = String::from("---\n");
+    markdown.push_str(&format!("date: {}\n", entry.date));
+    markdown.push_str(&format!("title: {}\n", yaml_string(&entry.title)));
+    match &entry.emotion {
+        Some(emotion) => markdown.push_str(&format!("emotion: {}\n", yaml_string(emotion))),
+        None => markdown.push_str("emotion: null\n"),
+    }
+    if entry.suggestions.is_empty() {
+        markdown.push_str("suggestions: []\n");
+    } else {
+        markdown.push_str("suggestions:\n");
+        for suggestion in &entry.suggestions {
+            markdown.push_str(&format!("  - {}\n", yaml_string(suggestion)));
+        }
+    }
+    markdown.push_str("---\n\n");
+    markdown.push_str(&format!("# {}\n\n", entry.title));
+    if !entry.body.is_empty() {
+        markdown.push_str(&entry.body);
+        markdown.push_str("\n\n");
+    }
+    for link in image_links {
+        markdown.push_str(&format!("![]({})\n\n", link));
+    }
+    markdown
+}
+
+// one <date>.md per entry, with images copied into an images/ folder next to them
+pub fn export_markdown(entries: &[ExportedEntry], base_dir: &Path, output_dir: &Path, report: &mut ExportReport) -> Result<()> {
+    let images_dir = output_dir.join(MARKDOWN_IMAGES_DIR_NAME);
+    fs::create_dir_all(&images_dir)
+        .map_err(|e| anyhow!("Failed to create export directory {:?}: {}", images_dir, e))?;
+
+    for entry in entries {
+        let mut image_links = Vec::new();
+        for relative_path in &entry.images {
+            let source = attachments::stored_file_path(base_dir, relative_path).ok();
+            let (source, file_name) = match (source, Path::new(relative_path).file_name()) {
+                (Some(source), Some(file_name)) if source.is_file() => (source, file_name.to_string_lossy().into_owned()),
+                _ => {
+                    report.missing_images.push(relative_path.clone());
+                    continue;
+                }
+            };
+            fs::copy(&source, images_dir.join(&file_name))
+                .map_err(|e| anyhow!("Failed to copy image {:?}: {}", source, e))?;
+            image_links.push(format!("{}/{}", MARKDOWN_IMAGES_DIR_NAME, file_name));
+            report.image_count += 1;
+        }
+        write_atomically(&output_dir.join(format!("{}.md", entry.date)), render_markdown(entry, &image_links).as_bytes())?;
+    }
+    Ok(())
+}
+
+// ---- HTML ----
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn html_paragraphs(body: &str) -> String {
+    body.split("\n\n")
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(|p| format!("<p>{}</p>\n", escape_html(p).replace('\n', "<br>\n")))
+        .collect()
+}
+
+const HTML_STYLE: &str = "body{font-family:Georgia,'Times New Roman',serif;max-width:46rem;margin:2rem auto;padding:0 1rem;color:#222;line-height:1.6}\
+header{text-align:center;margin-bottom:3rem}\
+article{border-top:1px solid #ddd;padding:2rem 0;page-break-inside:avoid}\
+.meta{color:#777;font-size:.9rem;margin:0}\
+.emotion{display:inline-block;padding:0 .5rem;border-radius:.75rem;background:#eee;text-transform:capitalize}\
+figure{margin:1rem 0}img{max-width:100%;height:auto;border-radius:4px}\
+.suggestions{background:#f7f4ea;padding:.75rem 1rem .75rem 2rem;border-radius:4px}";
+
+// a single file with inline styles and images embedded as data URIs
+pub fn render_html_book(entries: &[ExportedEntry], base_dir: &Path, report: &mut ExportReport) -> String {
+    let mut html = String::from("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str(&format!("<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n", BOOK_TITLE, HTML_STYLE));
+    html.push_str(&format!("<header>\n<h1>{}</h1>\n<p class=\"meta\">{}</p>\n</header>\n", BOOK_TITLE, escape_html(&book_subtitle(entries))));
+
+    for entry in entries {
+        html.push_str(&format!("<article id=\"entry-{}\">\n<h2>{}</h2>\n<p class=\"meta\">{}", entry.date, escape_html(&entry.title), entry.date));
+        if let Some(emotion) = &entry.emotion {
+            html.push_str(&format!(" &middot; <span class=\"emotion\">{}</span>", escape_html(emotion)));
+        }
+        html.push_str("</p>\n");
+        html.push_str(&html_paragraphs(&entry.body));
+
+        for relative_path in &entry.images {
+            match attachments::stored_file_path(base_dir, relative_path).and_then(|path| Ok(fs::read(path)?)) {
+                Ok(bytes) => {
+                    let mime = attachments::guess_mime(relative_path).unwrap_or("application/octet-stream");
+                    html.push_str(&format!(
+                        "<figure><img alt=\"\" src=\"data:{};base64,{}\"></figure>\n",
+                        mime,
+                        BASE64_STANDARD.encode(&bytes)
+                    ));
+                    report.image_count += 1;
+                }
+                Err(_) => report.missing_images.push(relative_path.clone()),
+            }
+        }
+
+        if !entry.suggestions.is_empty() {
+            html.push_str("<ul class=\"suggestions\">\n");
+            for suggestion in &entry.suggestions {
+                html.push_str(&format!("<li>{}</li>\n", escape_html(suggestion)));
+            }
+            html.push_str("</ul>\n");
+        }
+        html.push_str("</article>\n");
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+fn book_subtitle(entries: &[ExportedEntry]) -> String {
+    match (entries.first(), entries.last()) {
+        (Some(first), Some(last)) if first.date != last.date => format!("{} – {} · {} entries", first.date, last.date, entries.len()),
+        (Some(first), _) => format!("{} · 1 entry", first.date),
+        _ => "No entries".to_string(),
+    }
+}
+
+// ---- PDF ----
+
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const PAGE_MARGIN: f32 = 56.0;
+const CONTENT_WIDTH: f32 = PAGE_WIDTH - 2.0 * PAGE_MARGIN;
+const MAX_PDF_IMAGE_HEIGHT: f32 = 360.0;
+const PDF_IMAGE_MAX_DIMENSION: u32 = 1600;
+
+#[derive(Clone, Copy, PartialEq)]
+enum PdfFont {
+    Regular,
+    Bold,
+    Italic,
+}
+
+impl PdfFont {
+    const ALL: [PdfFont; 3] = [PdfFont::Regular, PdfFont::Bold, PdfFont::Italic];
+
+    fn resource_name(self) -> Name<'static> {
+        match self {
+            PdfFont::Regular => Name(b"F1"),
+            PdfFont::Bold => Name(b"F2"),
+            PdfFont::Italic => Name(b"F3"),
+        }
+    }
+
+    fn base_font(self) -> Name<'static> {
+        match self {
+            PdfFont::Regular => Name(b"Helvetica"),
+            PdfFont::Bold => Name(b"Helvetica-Bold"),
+            PdfFont::Italic => Name(b"Helvetica-Oblique"),
+        }
+    }
+}
+
+// Helvetica advance widths for printable ASCII, in thousandths of the font size
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278,
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556,
+    1015, 667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, 722, 778,
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 278, 278, 278, 469, 556,
+    333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, 556, 556,
+    556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+
+fn text_width(text: &str, font: PdfFont, size: f32) -> f32 {
+    let units: u32 = text.chars()
+        .map(|c| match c as u32 {
+            code @ 0x20..=0x7E => HELVETICA_WIDTHS[(code - 0x20) as usize] as u32,
+            _ => 556,
+        })
+        .sum();
+    // the bold cut runs roughly five percent wider than the regular widths above
+    let factor = if font == PdfFont::Bold { 1.05 } else { 1.0 };
+    units as f32 * size / 1000.0 * factor
+}
+
+// the standard fonts only cover WinAnsi; anything else (emoji, CJK) becomes '?'
+fn encode_win_ansi(text: &str) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(text.len());
+    for c in text.chars() {
+        let byte = match c {
+            ' '..='~' => c as u8,
+            '\u{A0}'..='\u{FF}' => c as u32 as u8,
+            '\t' => b' ',
+            '€' => 0x80,
+            '…' => 0x85,
+            '‘' => 0x91,
+            '’' => 0x92,
+            '“' => 0x93,
+            '”' => 0x94,
+            '•' => 0x95,
+            '–' => 0x96,
+            '—' => 0x97,
+            '\u{200D}' | '\u{FE00}'..='\u{FE0F}' => continue,
+            _ => b'?',
+        };
+        bytes.push(byte);
+    }
+    bytes
+}
+
+fn wrap_text(text: &str, font: PdfFont, size: f32, max_width: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    for source_line in text.lines() {
+        let mut current = String::new();
+        for word in source_line.split_whitespace() {
+            let candidate = if current.is_empty() { word.to_string() } else { format!("{} {}", current, word) };
+            if text_width(&candidate, font, size) <= max_width {
+                current = candidate;
+                continue;
+            }
+            if !current.is_empty() {
+                lines.push(std::mem::take(&mut current));
+            }
+            // words wider than the column are broken wherever they overflow
+            for c in word.chars() {
+                current.push(c);
+                if text_width(&current, font, size) > max_width && current.chars().count() > 1 {
+                    let overflow = current.pop().unwrap_or(' ');
+                    lines.push(std::mem::take(&mut current));
+                    current.push(overflow);
+                }
+            }
+        }
+        lines.push(current);
+    }
+    lines
+}
+
+struct PdfImage {
+    data: Vec<u8>,
+    width: u32,
+    height: u32,
+}
+
+struct PdfPage {
+    content: Content,
+    image_indices: Vec<usize>,
+}
+
+struct PdfBuilder {
+    pages: Vec<PdfPage>,
+    images: Vec<PdfImage>,
+    cursor_y: f32,
+}
+
+impl PdfBuilder {
+    fn new() -> Self {
+        Self { pages: Vec::new(), images: Vec::new(), cursor_y: 0.0 }
+    }
+
+    fn new_page(&mut self) {
+        self.pages.push(PdfPage { content: Content::new(), image_indices: Vec::new() });
+        self.cursor_y = PAGE_HEIGHT - PAGE_MARGIN;
+    }
+
+    fn ensure_space(&mut self, height: f32) {
+        if self.pages.is_empty() || self.cursor_y - height < PAGE_MARGIN {
+            self.new_page();
+        }
+    }
+
+    fn current_page(&mut self) -> &mut PdfPage {
+        if self.pages.is_empty() {
+            self.new_page();
+        }
+        self.pages.last_mut().expect("a page was just added")
+    }
+
+    fn text(&mut self, text: &str, font: PdfFont, size: f32, centered: bool) {
+        let leading = size * 1.4;
+        for line in wrap_text(text, font, size, CONTENT_WIDTH) {
+            self.ensure_space(leading);
+            self.cursor_y -= leading;
+            let x = if centered { (PAGE_WIDTH - text_width(&line, font, size)) / 2.0 } else { PAGE_MARGIN };
+            let y = self.cursor_y;
+            let content = &mut self.current_page().content;
+            content.begin_text();
+            content.set_font(font.resource_name(), size);
+            content.next_line(x, y);
+            content.show(Str(&encode_win_ansi(&line)));
+            content.end_text();
+        }
+    }
+
+    fn space(&mut self, height: f32) {
+        self.cursor_y -= height;
+    }
+
+    fn image(&mut self, image: PdfImage) {
+        let scale = (CONTENT_WIDTH / image.width as f32)
+            .min(MAX_PDF_IMAGE_HEIGHT / image.height as f32)
+            .min(1.0);
+        let (width, height) = (image.width as f32 * scale, image.height as f32 * scale);
+        self.ensure_space(height + 8.0);
+        self.cursor_y -= height + 8.0;
+
+        let image_index = self.images.len();
+        self.images.push(image);
+        let y = self.cursor_y;
+        let page = self.current_page();
+        page.image_indices.push(image_index);
+        page.content.save_state();
+        page.content.transform([width, 0.0, 0.0, height, PAGE_MARGIN, y]);
+        page.content.x_object(Name(format!("Im{}", image_index).as_bytes()));
+        page.content.restore_state();
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.pages.is_empty() {
+            self.new_page();
+        }
+
+        let mut pdf = Pdf::new();
+        let mut next_id = 1;
+        let mut alloc = || {
+            let id = Ref::new(next_id);
+            next_id += 1;
+            id
+        };
+        let catalog_id = alloc();
+        let page_tree_id = alloc();
+        let font_ids: Vec<Ref> = PdfFont::ALL.iter().map(|_| alloc()).collect();
+        let page_ids: Vec<(Ref, Ref)> = self.pages.iter().map(|_| (alloc(), alloc())).collect();
+        let image_ids: Vec<Ref> = self.images.iter().map(|_| alloc()).collect();
+
+        pdf.catalog(catalog_id).pages(page_tree_id);
+        pdf.pages(page_tree_id)
+            .kids(page_ids.iter().map(|(page_id, _)| *page_id))
+            .count(page_ids.len() as i32);
+
+        for (font, font_id) in PdfFont::ALL.iter().zip(&font_ids) {
+            pdf.type1_font(*font_id)
+                .base_font(font.base_font())
+                .encoding_predefined(Name(b"WinAnsiEncoding"));
+        }
+
+        for (page, (page_id, content_id)) in self.pages.into_iter().zip(&page_ids) {
+            let mut page_writer = pdf.page(*page_id);
+            page_writer.media_box(Rect::new(0.0, 0.0, PAGE_WIDTH, PAGE_HEIGHT));
+            page_writer.parent(page_tree_id);
+            page_writer.contents(*content_id);
+            let mut resources = page_writer.resources();
+            let mut fonts = resources.fonts();
+            for (font, font_id) in PdfFont::ALL.iter().zip(&font_ids) {
+                fonts.pair(font.resource_name(), *font_id);
+            }
+            fonts.finish();
+            if !page.image_indices.is_empty() {
+                let mut x_objects = resources.x_objects();
+                for image_index in &page.image_indices {
+                    x_objects.pair(Name(format!("Im{}", image_index).as_bytes()), image_ids[*image_index]);
+                }
+                x_objects.finish();
+            }
+            resources.finish();
+            page_writer.finish();
+            pdf.stream(*content_id, &page.content.finish());
+        }
+
+        for (image, image_id) in self.images.iter().zip(&image_ids) {
+            let mut image_writer = pdf.image_xobject(*image_id, &image.data);
+            image_writer.filter(Filter::DctDecode);
+            image_writer.width(image.width as i32);
+            image_writer.height(image.height as i32);
+            image_writer.color_space().device_rgb();
+            image_writer.bits_per_component(8);
+            image_writer.finish();
+        }
+
+        pdf.finish()
+    }
+}
+
+// a title page followed by one page (or more) per entry
+pub fn render_pdf(entries: &[ExportedEntry], base_dir: &Path, report: &mut ExportReport) -> Vec<u8> {
+    let mut builder = PdfBuilder::new();
+    builder.new_page();
+    builder.space(PAGE_HEIGHT / 3.0);
+    builder.text(BOOK_TITLE, PdfFont::Bold, 28.0, true);
+    builder.space(12.0);
+    builder.text(&book_subtitle(entries), PdfFont::Italic, 12.0, true);
+
+    for entry in entries {
+        builder.new_page();
+        builder.text(&entry.date, PdfFont::Italic, 10.0, false);
+        builder.text(&entry.title, PdfFont::Bold, 18.0, false);
+        if let Some(emotion) = &entry.emotion {
+            builder.text(&format!("Emotion: {}", emotion), PdfFont::Italic, 11.0, false);
+        }
+        builder.space(8.0);
+
+        for paragraph in entry.body.split("\n\n").map(str::trim).filter(|p| !p.is_empty()) {
+            builder.text(paragraph, PdfFont::Regular, 11.0, false);
+            builder.space(6.0);
+        }
+
+        for relative_path in &entry.images {
+            let embedded = attachments::stored_file_path(base_dir, relative_path)
+                .and_then(|path| Ok(fs::read(path)?))
+                .and_then(|bytes| images::jpeg_for_embedding(&bytes, PDF_IMAGE_MAX_DIMENSION));
+            match embedded {
+                Ok((data, width, height)) => {
+                    builder.image(PdfImage { data, width, height });
+                    report.image_count += 1;
+                }
+                Err(e) => {
+                    log::warn!("[export] Skipping image {} in PDF export: {}", relative_path, e);
+                    report.missing_images.push(relative_path.clone());
+                }
+            }
+        }
+
+        if !entry.suggestions.is_empty() {
+            builder.space(8.0);
+            builder.text("Suggestions", PdfFont::Bold, 12.0, false);
+            for suggestion in &entry.suggestions {
+                builder.text(&format!("• {}", suggestion), PdfFont::Regular, 11.0, false);
+            }
+        }
+    }
+
+    builder.finish()
+}
+
+// for Markdown the output path is a directory, for HTML and PDF it is the file to write
+pub fn export_journal(db: &Database, base_dir: &Path, format: ExportFormat, output_path: &Path, options: &ExportOptions) -> Result<ExportReport> {
+    let (entries, skipped_protected) = select_entries(db, options)?;
+    let mut report = ExportReport {
+        output_path: output_path.to_string_lossy().into_owned(),
+        entry_count: entries.len(),
+        skipped_protected,
+        ..Default::default()
+    };
+
+    match format {
+        ExportFormat::Markdown => export_markdown(&entries, base_dir, output_path, &mut report)?,
+        ExportFormat::Html => {
+            let html = render_html_book(&entries, base_dir, &mut report);
+            write_atomically(output_path, html.as_bytes())?;
+        }
+        ExportFormat::Pdf => {
+            let pdf = render_pdf(&entries, base_dir, &mut report);
+            write_atomically(output_path, &pdf)?;
+        }
+        ExportFormat::Ics => {
+            let ics = calendar::render_ics(&entries, Utc::now());
+            write_atomically(output_path, ics.as_bytes())?;
+        }
+    }
+
+    log::info!(
+        "[export] Exported {} entries and {} image(s) as {:?} to {:?} ({} protected skipped, {} image(s) missing).",
+        report.entry_count,
+        report.image_count,
+        format,
+        output_path,
+        report.skipped_protected,
+        report.missing_images.len()
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Database;
+    use std::path::PathBuf;
+
+    fn get_test_dir(test_name: &str) -> PathBuf {
+        let mut path = std::env::temp_dir();
+        path.push("moodjourney_export_tests");
+        path.push(test_name);
+        if path.exists() {
+            let _ = fs::remove_dir_all(&path);
+        }
+        fs::create_dir_all(&path).expect("Failed to create temporary test directory for export");
+        path
+    }
+
+    fn tiny_png() -> Vec<u8> {
+        let img = image::DynamicImage::ImageRgb8(image::RgbImage::from_pixel(4, 3, image::Rgb([200, 100, 50])));
+        let mut bytes = Vec::new();
+        img.write_to(&mut std::io::Cursor::new(&mut bytes), image::ImageFormat::Png).expect("encode failed");
+        bytes
+    }
+
+    fn setup(test_name: &str) -> (PathBuf, Database) {
+        let base_dir = get_test_dir(test_name);
+        let db = Database::open(&base_dir.join("entries.db")).expect("init failed");
+
+        let image_path = attachments::store_file(&base_dir, &tiny_png(), "photo.png", "png").expect("store failed");
+        db.add_entry(Entry {
+            date: "2025-04-01".to_string(),
+            title: Some("Spring \"walk\"".to_string()),
+            content: Some("Saw the first <blossoms>.\n\nFelt calm.\n\n🧠 Emotion: joy\n\n💡 Suggestion: - Walk again tomorrow\n- Take a photo".to_string()),
+            password: None,
+            image: Some(image_path),
+        }).expect("add failed");
+        db.add_entry(Entry {
+            date: "2025-04-02".to_string(),
+            title: Some("Rainy".to_string()),
+            content: Some("Stayed in.\n\n🧠 Emotion: sadness".to_string()),
+            password: None,
+            image: None,
+        }).expect("add failed");
+        db.add_entry(Entry {
+            date: "2025-04-03".to_string(),
+            title: Some("Private".to_string()),
+            content: Some("Secret.\n\n🧠 Emotion: joy".to_string()),
+            password: Some("1234".to_string()),
+            image: None,
+        }).expect("add failed");
+        (base_dir, db)
+    }
+
+    #[test]
+    fn test_parse_entry_content() {
+        let (body, emotion, suggestions) = parse_entry_content("Dear diary.\n\n🧠 Emotion: joy\n\n💡 Suggestion: - One\n\n- Two");
+        assert_eq!(body, "Dear diary.");
+        assert_eq!(emotion.as_deref(), Some("joy"));
+        assert_eq!(suggestions, vec!["One".to_string(), "Two".to_string()]);
+
+        let (body, emotion, suggestions) = parse_entry_content("Just text");
+        assert_eq!(body, "Just text");
+        assert!(emotion.is_none());
+        assert!(suggestions.is_empty());
+    }
+
+    #[test]
+    fn test_select_entries_filters_dates_emotions_and_protected() {
+        let (base_dir, db) = setup("select");
+
+        let (all, skipped) = select_entries(&db, &ExportOptions::default()).expect("select failed");
+        assert_eq!(all.iter().map(|e| e.date.as_str()).collect::<Vec<_>>(), vec!["2025-04-01", "2025-04-02"]);
+        assert_eq!(skipped, 1, "The password-protected entry should be skipped by default.");
+
+        let options = ExportOptions { emotions: vec!["JOY".to_string()], include_protected: true, ..Default::default() };
+        let (joyful, _) = select_entries(&db, &options).expect("select failed");
+        assert_eq!(joyful.iter().map(|e| e.date.as_str()).collect::<Vec<_>>(), vec!["2025-04-01", "2025-04-03"]);
+
+        let options = ExportOptions { from_date: Some("2025-04-02".to_string()), to_date: Some("2025-04-02".to_string()), ..Default::default() };
+        let (ranged, _) = select_entries(&db, &options).expect("select failed");
+        assert_eq!(ranged.len(), 1);
+        assert_eq!(ranged[0].emotion.as_deref(), Some("sadness"));
+
+        let options = ExportOptions { from_date: Some("April".to_string()), ..Default::default() };
+        assert_eq!(AppError::code_of(&select_entries(&db, &options).unwrap_err()), "INVALID_DATE");
+
+        let _ = fs::remove_dir_all(base_dir);
+    }
+
+    #[test]
+    fn test_markdown_export_writes_front_matter_and_copies_images() {
+        let (base_dir, db) = setup("markdown");
+        let output_dir = base_dir.join("out");
+
+        let report = export_journal(&db, &base_dir, ExportFormat::Markdown, &output_dir, &ExportOptions::default()).expect("export failed");
+        assert_eq!(report.entry_count, 2);
+        assert_eq!(report.image_count, 1);
+
+        let markdown = fs::read_to_string(output_dir.join("2025-04-01.md")).expect("markdown missing");
+        assert!(markdown.starts_with("---\ndate: 2025-04-01\ntitle: \"Spring \\\"walk\\\"\"\nemotion: \"joy\"\nsuggestions:\n  - \"Walk again tomorrow\"\n  - \"Take a photo\"\n---\n"));
+        assert!(!markdown.contains("🧠"), "Embedded markers should not leak into the body.");
+        let image_link = markdown.lines().find(|l| l.starts_with("![](images/")).expect("image link missing");
+        let linked_file = image_link.trim_start_matches("![](").trim_end_matches(')');
+        assert!(output_dir.join(linked_file).exists(), "Linked image should be copied next to the Markdown files.");
+
+        let _ = fs::remove_dir_all(base_dir);
+    }
+
+    #[test]
+    fn test_html_and_pdf_exports_are_self_contained() {
+        let (base_dir, db) = setup("html_pdf");
+
+        let html_path = base_dir.join("journal.html");
+        let report = export_journal(&db, &base_dir, ExportFormat::Html, &html_path, &ExportOptions::default()).expect("html export failed");
+        assert_eq!(report.image_count, 1);
+        let html = fs::read_to_string(&html_path).expect("html missing");
+        assert!(html.contains("src=\"data:image/png;base64,"));
+        assert!(html.contains("Saw the first &lt;blossoms&gt;."));
+        assert!(!html.contains("journal_images/"), "The HTML book must not reference files on disk.");
+
+        let pdf_path = base_dir.join("journal.pdf");
+        let report = export_journal(&db, &base_dir, ExportFormat::Pdf, &pdf_path, &ExportOptions::default()).expect("pdf export failed");
+        assert_eq!(report.image_count, 1);
+        let pdf = fs::read(&pdf_path).expect("pdf missing");
+        assert!(pdf.starts_with(b"%PDF-"));
+        assert!(pdf.windows(b"/Count 3".len()).any(|w| w == b"/Count 3"), "Expected a title page plus one page per entry.");
+        assert!(pdf.windows(b"/DCTDecode".len()).any(|w| w == b"/DCTDecode"));
+        assert!(!base_dir.join("journal.partial").exists());
+
+        let _ = fs::remove_dir_all(base_dir);
+    }
+
+    #[test]
+    fn test_ics_export_respects_filters() {
+        let (base_dir, db) = setup("ics");
+        let ics_path = base_dir.join("journal.ics");
+
+        let options = ExportOptions { to_date: Some("2025-04-02".to_string()), ..Default::default() };
+        let report = export_journal(&db, &base_dir, ExportFormat::Ics, &ics_path, &options).expect("ics export failed");
+        assert_eq!(report.entry_count, 2);
+        let ics = fs::read_to_string(&ics_path).expect("ics missing");
+        assert!(ics.contains("SUMMARY:Spring \"walk\" (joy)"));
+        assert!(!ics.contains("Private"), "Password-protected entries are excluded by default.");
+
+        let options = ExportOptions { include_protected: true, ..Default::default() };
+        export_journal(&db, &base_dir, ExportFormat::Ics, &ics_path, &options).expect("ics export failed");
+        assert_eq!(fs::read_to_string(&ics_path).expect("ics missing").matches("BEGIN:VEVENT").count(), 3);
+
+        let _ = fs::remove_dir_all(base_dir);
+    }
+
+    #[test]
+    fn test_excerpt_cuts_at_word_boundary() {
+        assert_eq!(excerpt("Short\ntext", 50), "Short text");
+        assert_eq!(excerpt("The quick brown fox jumps over the lazy dog", 20), "The quick brown fox…");
+    }
+
+    #[test]
+    fn test_wrap_text_and_win_ansi_encoding() {
+        let lines = wrap_text("one two three four five six seven eight nine ten", PdfFont::Regular, 11.0, 60.0);
+        assert!(lines.len() > 1);
+        assert!(lines.iter().all(|l| text_width(l, PdfFont::Regular, 11.0) <= 60.0));
+        assert_eq!(encode_win_ansi("café — ok 🧠"), b"caf\xe9 \x97 ok ?".to_vec());
+    }
+}
@@ -0,0 +1,220 @@
+use crate::attachments;
+use crate::error::AppError;
+use crate::settings::AppSettings;
+use anyhow::{Result, anyhow};
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageDecoder, ImageFormat, ImageReader};
+use std::io::Cursor;
+use std::path::Path;
+
+const JPEG_QUALITY: u8 = 90;
+const THUMBNAIL_JPEG_QUALITY: u8 = 80;
+
+pub struct ProcessedImage {
+    pub bytes: Vec<u8>,
+    pub extension: &'static str,
+    pub width: u32,
+    pub height: u32,
+    pub thumbnail: Vec<u8>,
+}
+
+fn format_size(bytes: u64) -> String {
+    format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+}
+
+pub fn check_upload_size(len: usize, max_upload_bytes: u64) -> Result<()> {
+    if max_upload_bytes > 0 && len as u64 > max_upload_bytes {
+        return Err(AppError::TooLarge(format!(
+            "The file is {}, which exceeds the {} upload limit.",
+            format_size(len as u64),
+            format_size(max_upload_bytes)
+        )).into());
+    }
+    Ok(())
+}
+
+// trusts the file contents rather than the extension of the original name
+pub fn sniff_image_format(bytes: &[u8]) -> Option<ImageFormat> {
+    match image::guess_format(bytes) {
+        Ok(format @ (ImageFormat::Png | ImageFormat::Jpeg | ImageFormat::Gif | ImageFormat::WebP | ImageFormat::Bmp)) => Some(format),
+        _ => None,
+    }
+}
+
+fn decode_oriented(bytes: &[u8], format: ImageFormat) -> Result<DynamicImage> {
+    let mut decoder = ImageReader::with_format(Cursor::new(bytes), format)
+        .into_decoder()
+        .map_err(|e| anyhow!("Failed to read image data: {}", e))?;
+    let orientation = decoder.orientation().ok();
+    let mut img = DynamicImage::from_decoder(decoder)
+        .map_err(|e| anyhow!("Failed to decode image: {}", e))?;
+    if let Some(orientation) = orientation {
+        img.apply_orientation(orientation);
+    }
+    Ok(img)
+}
+
+fn encode_jpeg(img: &DynamicImage, quality: u8) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    let encoder = JpegEncoder::new_with_quality(&mut bytes, quality);
+    img.to_rgb8()
+        .write_with_encoder(encoder)
+        .map_err(|e| anyhow!("Failed to encode JPEG image: {}", e))?;
+    Ok(bytes)
+}
+
+fn encode_png(img: &DynamicImage) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    img.write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+        .map_err(|e| anyhow!("Failed to encode PNG image: {}", e))?;
+    Ok(bytes)
+}
+
+pub fn make_thumbnail(img: &DynamicImage, thumbnail_size: u32) -> Result<Vec<u8>> {
+    let size = thumbnail_size.max(1);
+    encode_jpeg(&img.thumbnail(size, size), THUMBNAIL_JPEG_QUALITY)
+}
+
+pub fn thumbnail_from_bytes(bytes: &[u8], thumbnail_size: u32) -> Result<Vec<u8>> {
+    let format = sniff_image_format(bytes)
+        .ok_or_else(|| AppError::UnsupportedImage("The file is not a supported image (PNG, JPEG, GIF, WebP or BMP).".to_string()))?;
+    make_thumbnail(&decode_oriented(bytes, format)?, thumbnail_size)
+}
+
+// baseline RGB JPEG that documents such as PDFs can embed as-is; returns bytes, width, height
+pub fn jpeg_for_embedding(bytes: &[u8], max_dimension: u32) -> Result<(Vec<u8>, u32, u32)> {
+    let format = sniff_image_format(bytes)
+        .ok_or_else(|| AppError::UnsupportedImage("The file is not a supported image (PNG, JPEG, GIF, WebP or BMP).".to_string()))?;
+    let mut img = decode_oriented(bytes, format)?;
+    if max_dimension > 0 && (img.width() > max_dimension || img.height() > max_dimension) {
+        img = img.resize(max_dimension, max_dimension, FilterType::Triangle);
+    }
+    Ok((encode_jpeg(&img, JPEG_QUALITY)?, img.width(), img.height()))
+}
+
+// decoding and re-encoding drops EXIF/GPS and every other metadata block; JPEGs stay JPEG,
+// everything else (including animated GIFs, which keep their first frame) becomes PNG
+pub fn process_image(bytes: &[u8], settings: &AppSettings) -> Result<ProcessedImage> {
+    check_upload_size(bytes.len(), settings.max_upload_bytes)?;
+
+    let format = sniff_image_format(bytes)
+        .ok_or_else(|| AppError::UnsupportedImage("The file is not a supported image (PNG, JPEG, GIF, WebP or BMP).".to_string()))?;
+    let mut img = decode_oriented(bytes, format)?;
+
+    let max_dimension = settings.max_image_dimension;
+    if max_dimension > 0 && (img.width() > max_dimension || img.height() > max_dimension) {
+        log::info!(
+            "[images] Downscaling {}x{} image to fit within {}px.",
+            img.width(),
+            img.height(),
+            max_dimension
+        );
+        img = img.resize(max_dimension, max_dimension, FilterType::Lanczos3);
+    }
+
+    let (bytes, extension) = if format == ImageFormat::Jpeg {
+        (encode_jpeg(&img, JPEG_QUALITY)?, "jpg")
+    } else {
+        (encode_png(&img)?, "png")
+    };
+
+    Ok(ProcessedImage {
+        bytes,
+        extension,
+        width: img.width(),
+        height: img.height(),
+        thumbnail: make_thumbnail(&img, settings.thumbnail_size)?,
+    })
+}
+
+// validates, strips metadata from, downscales and thumbnails an image before saving it
+pub fn store_processed_image(base_dir: &Path, image_bytes: &[u8], settings: &AppSettings) -> Result<String> {
+    store_image(base_dir, &process_image(image_bytes, settings)?)
+}
+
+// saves an image that already went through process_image, with its thumbnail
+pub fn store_image(base_dir: &Path, processed: &ProcessedImage) -> Result<String> {
+    let relative_path = attachments::store_file(base_dir, &processed.bytes, &format!("upload.{}", processed.extension), processed.extension)?;
+    if let Err(e) = attachments::store_thumbnail(base_dir, &relative_path, &processed.thumbnail) {
+        let _ = attachments::remove_stored_file(base_dir, &relative_path);
+        return Err(e);
+    }
+    Ok(relative_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage};
+
+    fn sample_image(width: u32, height: u32) -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_fn(width, height, |x, y| Rgb([(x % 256) as u8, (y % 256) as u8, 128])))
+    }
+
+    // inserts an APP1 Exif segment carrying a fake GPS marker right after the JPEG SOI marker
+    fn jpeg_with_exif(img: &DynamicImage) -> Vec<u8> {
+        let jpeg = encode_jpeg(img, 90).expect("Failed to encode test JPEG");
+        let payload = b"Exif\0\0MM\0*\0\0\0\x08GPSLatitude=34.0";
+        let segment_len = (payload.len() + 2) as u16;
+
+        let mut with_exif = jpeg[..2].to_vec();
+        with_exif.extend_from_slice(&[0xFF, 0xE1]);
+        with_exif.extend_from_slice(&segment_len.to_be_bytes());
+        with_exif.extend_from_slice(payload);
+        with_exif.extend_from_slice(&jpeg[2..]);
+        with_exif
+    }
+
+    fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+        haystack.windows(needle.len()).any(|w| w == needle)
+    }
+
+    #[test]
+    fn test_sniffing_ignores_file_name_and_rejects_non_images() {
+        let png = encode_png(&sample_image(4, 4)).unwrap();
+        assert_eq!(sniff_image_format(&png), Some(ImageFormat::Png));
+        assert_eq!(sniff_image_format(b"definitely not an image"), None);
+
+        let result = process_image(b"<html>not an image</html>", &AppSettings::default());
+        assert!(result.is_err());
+        assert_eq!(AppError::code_of(&result.err().unwrap()), "UNSUPPORTED_IMAGE");
+    }
+
+    #[test]
+    fn test_upload_size_limit() {
+        let png = encode_png(&sample_image(64, 64)).unwrap();
+        let settings = AppSettings { max_upload_bytes: 10, ..AppSettings::default() };
+        let result = process_image(&png, &settings);
+        assert!(result.is_err());
+        assert_eq!(AppError::code_of(&result.err().unwrap()), "TOO_LARGE");
+
+        assert!(check_upload_size(10, 10).is_ok());
+        assert!(check_upload_size(11, 0).is_ok(), "A zero limit disables the check.");
+    }
+
+    #[test]
+    fn test_exif_is_stripped_from_jpeg() {
+        let jpeg = jpeg_with_exif(&sample_image(32, 16));
+        assert!(contains(&jpeg, b"GPSLatitude"), "Test JPEG should carry the fake EXIF block.");
+
+        let processed = process_image(&jpeg, &AppSettings::default()).expect("Failed to process JPEG");
+        assert_eq!(processed.extension, "jpg");
+        assert_eq!((processed.width, processed.height), (32, 16));
+        assert!(!contains(&processed.bytes, b"Exif"), "EXIF segment should be removed.");
+        assert!(!contains(&processed.bytes, b"GPSLatitude"), "GPS data should be removed.");
+    }
+
+    #[test]
+    fn test_large_images_are_downscaled_and_thumbnailed() {
+        let png = encode_png(&sample_image(400, 200)).unwrap();
+        let settings = AppSettings { max_image_dimension: 100, thumbnail_size: 50, ..AppSettings::default() };
+        let processed = process_image(&png, &settings).expect("Failed to process PNG");
+
+        assert_eq!(processed.extension, "png");
+        assert_eq!((processed.width, processed.height), (100, 50));
+
+        let thumbnail = image::load_from_memory(&processed.thumbnail).expect("Thumbnail should decode");
+        assert_eq!((thumbnail.width(), thumbnail.height()), (50, 25));
+    }
+}
@@ -0,0 +1,945 @@
+use crate::attachments;
+use crate::db::{self, Database, Entry};
+use crate::error::AppError;
+use crate::export::{self, EMOTION_MARKER, SUGGESTION_MARKER};
+use crate::images;
+use crate::settings::AppSettings;
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Local, NaiveDate, TimeZone, Utc};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+// how far ahead the rename policy looks for a free date
+const MAX_RENAME_DAYS: i64 = 3650;
+const MERGE_SEPARATOR: &str = "\n\n---\n\n";
+// same default the frontend uses when saving an untitled entry
+const DEFAULT_ENTRY_TITLE: &str = "Journal Entry";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportSource {
+    DayOne,
+    Journey,
+    Markdown,
+}
+
+impl ImportSource {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "dayone" | "day-one" | "day_one" => Some(Self::DayOne),
+            "journey" => Some(Self::Journey),
+            "markdown" | "md" | "text" => Some(Self::Markdown),
+            _ => None,
+        }
+    }
+}
+
+// entries are keyed by date, so a conflict is any imported entry whose date is already taken
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum ConflictPolicy {
+    // leave the existing entry alone
+    #[default]
+    Skip,
+    // append the imported text and images to the existing entry
+    Merge,
+    // move the imported entry to the next free date
+    Rename,
+}
+
+impl ConflictPolicy {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "skip" => Some(Self::Skip),
+            "merge" => Some(Self::Merge),
+            "rename" => Some(Self::Rename),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ImportOptions {
+    pub policy: ConflictPolicy,
+    pub dry_run: bool,
+    // fill in missing emotions with the emotion model; ignored on dry runs
+    pub classify_emotions: bool,
+}
+
+// the emotion model lives with the app, so callers hand in a classifier
+pub type EmotionClassifier = dyn Fn(&str) -> Result<String>;
+
+#[derive(Debug, Clone)]
+pub struct ImportedImage {
+    pub name: String,
+    pub bytes: Vec<u8>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ImportedEntry {
+    // file name or id in the source, used in reports
+    pub source_id: String,
+    pub date: NaiveDate,
+    pub title: Option<String>,
+    pub body: String,
+    pub emotion: Option<String>,
+    pub suggestions: Vec<String>,
+    pub images: Vec<ImportedImage>,
+    pub missing_images: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportAction {
+    Create,
+    Merge,
+    Rename,
+    Skip,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportItem {
+    pub source_id: String,
+    pub date: String,
+    // differs from date only when the rename policy moved the entry
+    pub target_date: String,
+    pub title: Option<String>,
+    pub action: ImportAction,
+    pub image_count: usize,
+    pub emotion: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportReport {
+    pub dry_run: bool,
+    pub items: Vec<ImportItem>,
+    pub created: usize,
+    pub merged: usize,
+    pub renamed: usize,
+    pub skipped: usize,
+    pub images_imported: usize,
+    pub warnings: Vec<String>,
+}
+
+// ---- source files ----
+
+// a zip archive or an extracted folder, addressed with '/'-separated relative names
+enum SourceFiles {
+    Zip(zip::ZipArchive<fs::File>),
+    Dir(PathBuf),
+}
+
+impl SourceFiles {
+    fn open(path: &Path) -> Result<Self> {
+        if path.is_dir() {
+            return Ok(SourceFiles::Dir(path.to_path_buf()));
+        }
+        let file = fs::File::open(path).map_err(|e| anyhow!("Failed to open import source {:?}: {}", path, e))?;
+        let archive = zip::ZipArchive::new(file)
+            .map_err(|e| AppError::InvalidFile(format!("{:?} is neither a folder nor a zip archive: {}", path, e)))?;
+        Ok(SourceFiles::Zip(archive))
+    }
+
+    fn names(&self) -> Result<Vec<String>> {
+        let mut names = match self {
+            SourceFiles::Zip(archive) => archive.file_names()
+                .filter(|name| !name.ends_with('/') && !name.starts_with("__MACOSX/"))
+                .map(|name| name.to_string())
+                .collect(),
+            SourceFiles::Dir(root) => {
+                let mut names = Vec::new();
+                collect_relative_files(root, root, &mut names)?;
+                names
+            }
+        };
+        names.sort();
+        Ok(names)
+    }
+
+    fn read(&mut self, name: &str) -> Result<Vec<u8>> {
+        match self {
+            SourceFiles::Zip(archive) => {
+                let mut file = archive.by_name(name).map_err(|e| anyhow!("Failed to find {} in archive: {}", name, e))?;
+                let mut bytes = Vec::new();
+                file.read_to_end(&mut bytes).map_err(|e| anyhow!("Failed to read {} from archive: {}", name, e))?;
+                Ok(bytes)
+            }
+            SourceFiles::Dir(root) => {
+                let full_path = root.join(name);
+                fs::read(&full_path).map_err(|e| anyhow!("Failed to read {:?}: {}", full_path, e))
+            }
+        }
+    }
+}
+
+fn collect_relative_files(dir: &Path, root: &Path, names: &mut Vec<String>) -> Result<()> {
+    for dir_entry in fs::read_dir(dir).map_err(|e| anyhow!("Failed to read directory {:?}: {}", dir, e))? {
+        let path = dir_entry?.path();
+        if path.is_dir() {
+            collect_relative_files(&path, root, names)?;
+        } else if let Ok(relative) = path.strip_prefix(root) {
+            names.push(relative.components()
+                .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                .collect::<Vec<_>>()
+                .join("/"));
+        }
+    }
+    Ok(())
+}
+
+fn has_extension(name: &str, extensions: &[&str]) -> bool {
+    Path::new(name)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| extensions.iter().any(|wanted| ext.eq_ignore_ascii_case(wanted)))
+}
+
+fn base_name(name: &str) -> &str {
+    name.rsplit('/').next().unwrap_or(name)
+}
+
+// resolves a link found in `from_file` to a source name, rejecting anything that leaves the source
+fn resolve_link(from_file: &str, link: &str) -> Option<String> {
+    let link = link.split(['?', '#']).next()?.replace("%20", " ");
+    let mut parts: Vec<&str> = from_file.split('/').collect();
+    parts.pop();
+    for part in link.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => { parts.pop()?; }
+            _ => parts.push(part),
+        }
+    }
+    Some(parts.join("/"))
+}
+
+fn split_title_line(text: &str) -> (Option<String>, String) {
+    let trimmed = text.trim_start();
+    if let Some(rest) = trimmed.strip_prefix("# ") {
+        let (title_line, body) = rest.split_once('\n').unwrap_or((rest, ""));
+        return (Some(title_line.trim().to_string()).filter(|t| !t.is_empty()), body.trim().to_string());
+    }
+    (None, text.trim().to_string())
+}
+
+fn local_date_from_utc(utc: DateTime<Utc>) -> NaiveDate {
+    utc.with_timezone(&Local).date_naive()
+}
+
+// ---- Day One ----
+
+// Day One zips hold one <Journal>.json per journal plus photos/<md5>.<type>
+pub fn read_day_one(path: &Path) -> Result<(Vec<ImportedEntry>, Vec<String>)> {
+    let mut source = SourceFiles::open(path)?;
+    let names = source.names()?;
+    let photos_by_md5: HashMap<String, String> = names.iter()
+        .filter(|name| name.contains("photos/"))
+        .filter_map(|name| Path::new(name).file_stem().map(|stem| (stem.to_string_lossy().into_owned(), name.clone())))
+        .collect();
+
+    let mut entries = Vec::new();
+    let mut warnings = Vec::new();
+    for json_name in names.iter().filter(|name| has_extension(name, &["json"])) {
+        let journal: Value = serde_json::from_slice(&source.read(json_name)?)
+            .map_err(|e| AppError::InvalidFile(format!("{} is not valid JSON: {}", json_name, e)))?;
+        let journal_entries = match journal.get("entries").and_then(Value::as_array) {
+            Some(journal_entries) => journal_entries,
+            None => {
+                warnings.push(format!("{} has no entries array and was ignored.", json_name));
+                continue;
+            }
+        };
+
+        for (index, item) in journal_entries.iter().enumerate() {
+            let source_id = item.get("uuid").and_then(Value::as_str)
+                .map(|s| s.to_string())
+                .unwrap_or_else(|| format!("{}#{}", json_name, index));
+            let date = match item.get("creationDate").and_then(Value::as_str).and_then(|s| DateTime::parse_from_rfc3339(s).ok()) {
+                Some(created) => local_date_from_utc(created.with_timezone(&Utc)),
+                None => {
+                    warnings.push(format!("{} has no valid creationDate and was ignored.", source_id));
+                    continue;
+                }
+            };
+
+            // photos are referenced inline as ![](dayone-moment://<identifier>)
+            let text = item.get("text").and_then(Value::as_str).unwrap_or("");
+            let text = text.lines()
+                .filter(|line| !line.trim_start().starts_with("![](dayone-moment://"))
+                .collect::<Vec<_>>()
+                .join("\n")
+                .replace("\\.", ".")
+                .replace("\\-", "-")
+                .replace("\\!", "!");
+            let (title, body) = split_title_line(&text);
+
+            let mut photos: Vec<&Value> = item.get("photos").and_then(Value::as_array).map(|p| p.iter().collect()).unwrap_or_default();
+            photos.sort_by_key(|photo| photo.get("orderInMoment").and_then(Value::as_i64).unwrap_or(0));
+            let mut images = Vec::new();
+            let mut missing_images = Vec::new();
+            for photo in photos {
+                let md5 = photo.get("md5").and_then(Value::as_str).unwrap_or_default();
+                match photos_by_md5.get(md5) {
+                    Some(name) => images.push(ImportedImage { name: base_name(name).to_string(), bytes: source.read(name)? }),
+                    None => missing_images.push(md5.to_string()),
+                }
+            }
+
+            entries.push(ImportedEntry {
+                source_id,
+                date,
+                title,
+                body,
+                emotion: None,
+                suggestions: Vec::new(),
+                images,
+                missing_images,
+            });
+        }
+    }
+    Ok((entries, warnings))
+}
+
+// ---- Journey ----
+
+fn html_to_text(html: &str) -> String {
+    let with_breaks = html
+        .replace("<br>", "\n")
+        .replace("<br/>", "\n")
+        .replace("<br />", "\n")
+        .replace("</p>", "\n\n")
+        .replace("</div>", "\n")
+        .replace("</li>", "\n");
+    let mut text = String::with_capacity(with_breaks.len());
+    let mut in_tag = false;
+    for c in with_breaks.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+        .trim()
+        .to_string()
+}
+
+// Journey exports one <id>.json per entry with its photos stored alongside
+pub fn read_journey(path: &Path) -> Result<(Vec<ImportedEntry>, Vec<String>)> {
+    let mut source = if path.is_file() && has_extension(&path.to_string_lossy(), &["json"]) {
+        let parent = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        SourceFiles::Dir(parent)
+    } else {
+        SourceFiles::open(path)?
+    };
+    let names: Vec<String> = match (&source, path.is_file()) {
+        (SourceFiles::Dir(_), true) => vec![base_name(&path.to_string_lossy()).to_string()],
+        _ => source.names()?,
+    };
+
+    let mut entries = Vec::new();
+    let mut warnings = Vec::new();
+    for json_name in names.iter().filter(|name| has_extension(name, &["json"])) {
+        let item: Value = match serde_json::from_slice(&source.read(json_name)?) {
+            Ok(item) => item,
+            Err(e) => {
+                warnings.push(format!("{} is not valid JSON and was ignored: {}", json_name, e));
+                continue;
+            }
+        };
+        let date = match item.get("date_journal").and_then(Value::as_i64).and_then(|ms| Utc.timestamp_millis_opt(ms).single()) {
+            Some(journal_time) => local_date_from_utc(journal_time),
+            None => {
+                warnings.push(format!("{} has no date_journal and was ignored.", json_name));
+                continue;
+            }
+        };
+
+        let raw_text = item.get("text").and_then(Value::as_str).unwrap_or("");
+        let text = if item.get("type").and_then(Value::as_str) == Some("html") { html_to_text(raw_text) } else { raw_text.to_string() };
+        let (title, body) = split_title_line(&text);
+
+        let mut images = Vec::new();
+        let mut missing_images = Vec::new();
+        for photo in item.get("photos").and_then(Value::as_array).into_iter().flatten().filter_map(Value::as_str) {
+            let candidate = resolve_link(json_name, photo).filter(|name| names.contains(name) || matches!(source, SourceFiles::Dir(_)));
+            match candidate.map(|name| source.read(&name)) {
+                Some(Ok(bytes)) => images.push(ImportedImage { name: base_name(photo).to_string(), bytes }),
+                _ => missing_images.push(photo.to_string()),
+            }
+        }
+
+        entries.push(ImportedEntry {
+            source_id: item.get("id").and_then(Value::as_str).map(|s| s.to_string()).unwrap_or_else(|| json_name.clone()),
+            date,
+            title,
+            body,
+            emotion: None,
+            suggestions: Vec::new(),
+            images,
+            missing_images,
+        });
+    }
+    Ok((entries, warnings))
+}
+
+// ---- Markdown / text ----
+
+fn date_in_file_name(name: &str) -> Option<NaiveDate> {
+    let file_name = base_name(name);
+    let bytes = file_name.as_bytes();
+    (0..bytes.len().saturating_sub(9))
+        .filter(|&start| file_name.is_char_boundary(start) && file_name.is_char_boundary(start + 10))
+        .find_map(|start| NaiveDate::parse_from_str(&file_name[start..start + 10], "%Y-%m-%d").ok())
+}
+
+fn front_matter_scalar(value: &str) -> Option<String> {
+    let value = value.trim();
+    if value.is_empty() || value == "null" || value == "~" {
+        return None;
+    }
+    if value.starts_with('"') {
+        return serde_json::from_str::<String>(value).ok();
+    }
+    Some(value.trim_matches('\'').to_string())
+}
+
+// understands the flat `key: value` / `- item` front matter written by the Markdown export
+fn split_front_matter(text: &str) -> (HashMap<String, String>, Vec<String>, &str) {
+    let mut fields = HashMap::new();
+    let mut suggestions = Vec::new();
+    let rest = match text.strip_prefix("---\n").or_else(|| text.strip_prefix("---\r\n")) {
+        Some(rest) => rest,
+        None => return (fields, suggestions, text),
+    };
+    let end = match rest.find("\n---") {
+        Some(end) => end,
+        None => return (fields, suggestions, text),
+    };
+
+    let mut current_key = String::new();
+    for line in rest[..end].lines() {
+        if let Some(item) = line.trim_start().strip_prefix("- ") {
+            if current_key == "suggestions" {
+                suggestions.extend(front_matter_scalar(item));
+            }
+        } else if let Some((key, value)) = line.split_once(':') {
+            current_key = key.trim().to_lowercase();
+            if let Some(value) = front_matter_scalar(value) {
+                fields.insert(current_key.clone(), value);
+            }
+        }
+    }
+    let body = rest[end + 4..].trim_start_matches(['\r', '\n']);
+    (fields, suggestions, body)
+}
+
+// pulls local ![alt](path) images out of the text; remote images stay in place as links
+fn extract_local_images(from_file: &str, body: &str, names: &[String]) -> (String, Vec<String>, Vec<String>) {
+    let mut kept_lines = Vec::new();
+    let mut image_names = Vec::new();
+    let mut missing = Vec::new();
+    for line in body.lines() {
+        let trimmed = line.trim();
+        let link = trimmed.strip_prefix("![")
+            .and_then(|rest| rest.split_once("](").map(|(_, after)| after))
+            .and_then(|after| after.strip_suffix(')'));
+        match link {
+            Some(link) if !link.contains("://") => {
+                match resolve_link(from_file, link).filter(|name| names.contains(name)) {
+                    Some(name) => image_names.push(name),
+                    None => missing.push(link.to_string()),
+                }
+            }
+            _ => kept_lines.push(line),
+        }
+    }
+    let mut text = kept_lines.join("\n");
+    while text.contains("\n\n\n") {
+        text = text.replace("\n\n\n", "\n\n");
+    }
+    (text.trim().to_string(), image_names, missing)
+}
+
+// .md/.markdown/.txt files dated by front matter or a YYYY-MM-DD in the file name
+pub fn read_markdown_folder(path: &Path) -> Result<(Vec<ImportedEntry>, Vec<String>)> {
+    let mut source = SourceFiles::open(path)?;
+    let names = source.names()?;
+
+    let mut entries = Vec::new();
+    let mut warnings = Vec::new();
+    for name in names.iter().filter(|name| has_extension(name, &["md", "markdown", "txt"])) {
+        let raw = String::from_utf8_lossy(&source.read(name)?).replace("\r\n", "\n");
+        let (fields, suggestions, text) = split_front_matter(&raw);
+
+        let date = fields.get("date")
+            .and_then(|d| NaiveDate::parse_from_str(d.get(..10).unwrap_or(d), "%Y-%m-%d").ok())
+            .or_else(|| date_in_file_name(name));
+        let date = match date {
+            Some(date) => date,
+            None => {
+                warnings.push(format!("{} has no date in its front matter or file name and was ignored.", name));
+                continue;
+            }
+        };
+
+        let (heading, text) = split_title_line(text);
+        // a body that still carries the app's emotion/suggestion markers is split back apart
+        let (text, marker_emotion, marker_suggestions) = export::parse_entry_content(&text);
+        let (body, image_names, missing_images) = extract_local_images(name, &text, &names);
+        let mut images = Vec::new();
+        for image_name in image_names {
+            images.push(ImportedImage { name: base_name(&image_name).to_string(), bytes: source.read(&image_name)? });
+        }
+
+        let fallback_title = Path::new(base_name(name))
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().replace(&date.format("%Y-%m-%d").to_string(), ""))
+            .map(|stem| stem.trim_matches(|c: char| c == '-' || c == '_' || c.is_whitespace()).replace(['-', '_'], " "))
+            .filter(|stem| !stem.is_empty());
+
+        entries.push(ImportedEntry {
+            source_id: name.clone(),
+            date,
+            title: fields.get("title").cloned().or(heading).or(fallback_title),
+            body,
+            emotion: fields.get("emotion").cloned().or(marker_emotion),
+            suggestions: if suggestions.is_empty() { marker_suggestions } else { suggestions },
+            images,
+            missing_images,
+        });
+    }
+    Ok((entries, warnings))
+}
+
+pub fn read_import_source(source: ImportSource, path: &Path) -> Result<(Vec<ImportedEntry>, Vec<String>)> {
+    if !path.exists() {
+        return Err(AppError::NotFound(format!("Import source {:?} does not exist.", path)).into());
+    }
+    match source {
+        ImportSource::DayOne => read_day_one(path),
+        ImportSource::Journey => read_journey(path),
+        ImportSource::Markdown => read_markdown_folder(path),
+    }
+}
+
+// ---- writing ----
+
+// rebuilds the stored content format the frontend writes: body, then the emotion and suggestion blocks
+pub fn compose_content(body: &str, emotion: Option<&str>, suggestions: &[String]) -> String {
+    let mut content = body.trim().to_string();
+    if let Some(emotion) = emotion.filter(|e| !e.trim().is_empty()) {
+        content.push_str(&format!("{} {}", EMOTION_MARKER, emotion.trim()));
+    }
+    if !suggestions.is_empty() {
+        let joined = suggestions.iter().map(|s| format!("- {}", s)).collect::<Vec<_>>().join("\n");
+        content.push_str(&format!("{} {}", SUGGESTION_MARKER, joined));
+    }
+    content
+}
+
+fn next_free_date(date: NaiveDate, taken: &HashSet<NaiveDate>) -> Option<NaiveDate> {
+    (1..=MAX_RENAME_DAYS)
+        .filter_map(|offset| date.checked_add_signed(chrono::Duration::days(offset)))
+        .find(|candidate| !taken.contains(candidate))
+}
+
+// images whose processed bytes match one in known_checksums are already attached to the entry and are
+// skipped, so importing the same source again does not store them twice
+fn store_images(base_dir: &Path, settings: &AppSettings, entry: &ImportedEntry, known_checksums: &mut HashSet<String>, report: &mut ImportReport) -> Vec<String> {
+    let mut stored = Vec::new();
+    for image in &entry.images {
+        let processed = match images::process_image(&image.bytes, settings) {
+            Ok(processed) => processed,
+            Err(e) => {
+                report.warnings.push(format!("{}: image {} was not imported: {}", entry.source_id, image.name, e));
+                continue;
+            }
+        };
+        if !known_checksums.insert(attachments::sha256_hex(&processed.bytes)) {
+            continue;
+        }
+        match images::store_image(base_dir, &processed) {
+            Ok(relative_path) => stored.push(relative_path),
+            Err(e) => report.warnings.push(format!("{}: image {} was not imported: {}", entry.source_id, image.name, e)),
+        }
+    }
+    report.images_imported += stored.len();
+    stored
+}
+
+fn write_entry_rows(db: &Database, base_dir: &Path, date: &str, stored: &[String], write_entry: impl FnOnce(&Connection) -> rusqlite::Result<()>) -> Result<()> {
+    let mut conn = db.conn()?;
+    let tx = conn.transaction()?;
+    write_entry(&tx)?;
+    for relative_path in stored {
+        db::insert_attachment_row(&tx, date, &attachments::describe_file(base_dir, relative_path)?)?;
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+// the entry and its attachment rows are written in one transaction; when that fails the images
+// stored for them are removed again, so nothing of the entry is left behind
+fn write_entry_with_attachments(db: &Database, base_dir: &Path, date: &str, stored: &[String], write_entry: impl FnOnce(&Connection) -> rusqlite::Result<()>) -> Result<()> {
+    let written = write_entry_rows(db, base_dir, date, stored, write_entry);
+    if written.is_err() {
+        for relative_path in stored {
+            let _ = attachments::remove_stored_file(base_dir, relative_path);
+        }
+    }
+    written
+}
+
+pub fn import_entries(
+    db: &Database,
+    base_dir: &Path,
+    settings: &AppSettings,
+    mut entries: Vec<ImportedEntry>,
+    options: &ImportOptions,
+    classify: Option<&EmotionClassifier>,
+) -> Result<ImportReport> {
+    let mut report = ImportReport { dry_run: options.dry_run, ..Default::default() };
+    let mut taken: HashSet<NaiveDate> = db.get_entries()?
+        .iter()
+        .filter_map(|entry| NaiveDate::parse_from_str(&entry.date, "%Y-%m-%d").ok())
+        .collect();
+    // a trashed entry still holds its date; it can be renamed around but not merged into
+    let trashed: HashSet<NaiveDate> = db.get_trashed_entries()?
+        .iter()
+        .filter_map(|entry| NaiveDate::parse_from_str(&entry.date, "%Y-%m-%d").ok())
+        .collect();
+    taken.extend(trashed.iter().copied());
+
+    entries.sort_by_key(|entry| entry.date);
+    for mut entry in entries {
+        for missing in &entry.missing_images {
+            report.warnings.push(format!("{}: image {} was not found in the import source.", entry.source_id, missing));
+        }
+
+        let (action, target_date) = if !taken.contains(&entry.date) {
+            (ImportAction::Create, entry.date)
+        } else {
+            match options.policy {
+                ConflictPolicy::Skip => (ImportAction::Skip, entry.date),
+                ConflictPolicy::Merge if trashed.contains(&entry.date) => {
+                    report.warnings.push(format!("{}: the entry for {} is in the trash and was not merged.", entry.source_id, entry.date));
+                    (ImportAction::Skip, entry.date)
+                }
+                ConflictPolicy::Merge => (ImportAction::Merge, entry.date),
+                ConflictPolicy::Rename => match next_free_date(entry.date, &taken) {
+                    Some(free_date) => (ImportAction::Rename, free_date),
+                    None => {
+                        report.warnings.push(format!("{}: no free date found near {}.", entry.source_id, entry.date));
+                        (ImportAction::Skip, entry.date)
+                    }
+                },
+            }
+        };
+
+        if !options.dry_run && options.classify_emotions && entry.emotion.is_none() && action != ImportAction::Skip && !entry.body.trim().is_empty() {
+            if let Some(classify) = classify {
+                match classify(&entry.body) {
+                    Ok(emotion) => entry.emotion = Some(emotion),
+                    Err(e) => report.warnings.push(format!("{}: emotion classification failed: {}", entry.source_id, e)),
+                }
+            }
+        }
+
+        let target = target_date.format("%Y-%m-%d").to_string();
+        let mut image_count = entry.images.len();
+        if !options.dry_run {
+            match action {
+                ImportAction::Create | ImportAction::Rename => {
+                    let stored = store_images(base_dir, settings, &entry, &mut HashSet::new(), &mut report);
+                    image_count = stored.len();
+                    let new_entry = Entry {
+                        date: target.clone(),
+                        title: Some(entry.title.clone().unwrap_or_else(|| DEFAULT_ENTRY_TITLE.to_string())),
+                        content: Some(compose_content(&entry.body, entry.emotion.as_deref(), &entry.suggestions)),
+                        password: None,
+                        image: stored.first().cloned(),
+                    };
+                    write_entry_with_attachments(db, base_dir, &target, &stored, |conn| db::insert_entry_row(conn, &new_entry))?;
+                }
+                ImportAction::Merge => {
+                    let existing = db.get_entry_by_date(&target)?
+                        .ok_or_else(|| AppError::NotFound(format!("Entry {} disappeared during import.", target)))?;
+                    let (existing_body, existing_emotion, existing_suggestions) = export::parse_entry_content(existing.content.as_deref().unwrap_or(""));
+                    // re-importing the same source must not duplicate text
+                    let body = if entry.body.is_empty() || existing_body.contains(entry.body.trim()) {
+                        existing_body
+                    } else if existing_body.is_empty() {
+                        entry.body.clone()
+                    } else {
+                        format!("{}{}{}", existing_body, MERGE_SEPARATOR, entry.body)
+                    };
+                    let suggestions = if existing_suggestions.is_empty() { entry.suggestions.clone() } else { existing_suggestions };
+                    let content = compose_content(&body, existing_emotion.or(entry.emotion.clone()).as_deref(), &suggestions);
+
+                    let mut known_checksums: HashSet<String> = db.get_attachments_for_entry(&target)?
+                        .into_iter()
+                        .filter_map(|attachment| attachment.checksum)
+                        .collect();
+                    let stored = store_images(base_dir, settings, &entry, &mut known_checksums, &mut report);
+                    image_count = stored.len();
+                    let title = existing.title.clone().filter(|t| !t.trim().is_empty()).or(entry.title.clone());
+                    let image = existing.image.clone().filter(|p| !p.trim().is_empty()).or(stored.first().cloned());
+                    write_entry_with_attachments(db, base_dir, &target, &stored, |conn| {
+                        db::update_entry_row(conn, &target, title.as_deref(), Some(&content), existing.password.as_deref(), image.as_deref()).map(|_| ())
+                    })?;
+                }
+                ImportAction::Skip => {}
+            }
+        }
+
+        match action {
+            ImportAction::Create => report.created += 1,
+            ImportAction::Merge => report.merged += 1,
+            ImportAction::Rename => report.renamed += 1,
+            ImportAction::Skip => report.skipped += 1,
+        }
+        if action == ImportAction::Skip {
+            image_count = 0;
+        }
+        taken.insert(target_date);
+        report.items.push(ImportItem {
+            source_id: entry.source_id,
+            date: entry.date.format("%Y-%m-%d").to_string(),
+            target_date: target,
+            title: entry.title,
+            action,
+            image_count,
+            emotion: entry.emotion,
+        });
+    }
+
+    log::info!(
+        "[import] {} created, {} merged, {} renamed, {} skipped, {} image(s), dry run: {}.",
+        report.created, report.merged, report.renamed, report.skipped, report.images_imported, report.dry_run
+    );
+    Ok(report)
+}
+
+pub fn import_journal(
+    db: &Database,
+    base_dir: &Path,
+    settings: &AppSettings,
+    source: ImportSource,
+    path: &Path,
+    options: &ImportOptions,
+    classify: Option<&EmotionClassifier>,
+) -> Result<ImportReport> {
+    let (entries, warnings) = read_import_source(source, path)?;
+    let mut report = import_entries(db, base_dir, settings, entries, options, classify)?;
+    report.warnings.splice(0..0, warnings);
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Database;
+    use std::io::Write;
+
+    fn get_test_dir(test_name: &str) -> PathBuf {
+        let mut path = std::env::temp_dir();
+        path.push("moodjourney_import_tests");
+        path.push(test_name);
+        if path.exists() {
+            let _ = fs::remove_dir_all(&path);
+        }
+        fs::create_dir_all(&path).expect("Failed to create temporary test directory for import");
+        path
+    }
+
+    fn tiny_png() -> Vec<u8> {
+        let img = image::DynamicImage::ImageRgb8(image::RgbImage::from_pixel(4, 4, image::Rgb([10, 200, 30])));
+        let mut bytes = Vec::new();
+        img.write_to(&mut std::io::Cursor::new(&mut bytes), image::ImageFormat::Png).expect("encode failed");
+        bytes
+    }
+
+    fn write_zip(path: &Path, files: &[(&str, Vec<u8>)]) {
+        let mut writer = zip::ZipWriter::new(fs::File::create(path).expect("create zip failed"));
+        for (name, bytes) in files {
+            writer.start_file(*name, zip::write::SimpleFileOptions::default()).expect("start file failed");
+            writer.write_all(bytes).expect("write failed");
+        }
+        writer.finish().expect("finish zip failed");
+    }
+
+    fn noon_utc(date: &str) -> String {
+        format!("{}T12:00:00Z", date)
+    }
+
+    fn setup_db(base_dir: &Path) -> Database {
+        let db = Database::open(&base_dir.join("entries.db")).expect("init failed");
+        db.add_entry(Entry {
+            date: "2024-01-02".to_string(),
+            title: Some("Existing".to_string()),
+            content: Some("Already here.\n\n🧠 Emotion: neutral".to_string()),
+            password: None,
+            image: None,
+        }).expect("add failed");
+        db
+    }
+
+    fn day_one_zip(base_dir: &Path) -> PathBuf {
+        let journal = serde_json::json!({
+            "metadata": { "version": "1.0" },
+            "entries": [
+                {
+                    "uuid": "A1",
+                    "creationDate": noon_utc("2024-01-01"),
+                    "text": "# New year\nStarted fresh\\.\n![](dayone-moment://P1)",
+                    "photos": [{ "identifier": "P1", "md5": "abc123", "type": "png", "orderInMoment": 0 }]
+                },
+                { "uuid": "A2", "creationDate": noon_utc("2024-01-02"), "text": "Second day notes" },
+                { "uuid": "A3", "text": "No date" }
+            ]
+        });
+        let zip_path = base_dir.join("dayone.zip");
+        write_zip(&zip_path, &[
+            ("Journal.json", serde_json::to_vec(&journal).unwrap()),
+            ("photos/abc123.png", tiny_png()),
+        ]);
+        zip_path
+    }
+
+    #[test]
+    fn test_day_one_dry_run_reports_without_writing() {
+        let base_dir = get_test_dir("day_one_dry_run");
+        let db = setup_db(&base_dir);
+        let zip_path = day_one_zip(&base_dir);
+
+        let options = ImportOptions { dry_run: true, ..Default::default() };
+        let report = import_journal(&db, &base_dir, &AppSettings::default(), ImportSource::DayOne, &zip_path, &options, None).expect("import failed");
+        assert_eq!((report.created, report.skipped), (1, 1));
+        assert_eq!(report.items[0].title.as_deref(), Some("New year"));
+        assert_eq!(report.items[0].image_count, 1);
+        assert!(report.warnings.iter().any(|w| w.contains("A3")), "Entries without a date should be reported: {:?}", report.warnings);
+        assert_eq!(db.get_entries().expect("list failed").len(), 1, "A dry run must not write entries.");
+        assert!(!base_dir.join(attachments::IMAGES_DIR_NAME).exists(), "A dry run must not store images.");
+
+        let _ = fs::remove_dir_all(base_dir);
+    }
+
+    #[test]
+    fn test_day_one_import_with_merge_and_classification() {
+        let base_dir = get_test_dir("day_one_merge");
+        let db = setup_db(&base_dir);
+        let zip_path = day_one_zip(&base_dir);
+
+        let classify = |_: &str| -> Result<String> { Ok("joy".to_string()) };
+        let options = ImportOptions { policy: ConflictPolicy::Merge, dry_run: false, classify_emotions: true };
+        let report = import_journal(&db, &base_dir, &AppSettings::default(), ImportSource::DayOne, &zip_path, &options, Some(&classify)).expect("import failed");
+        assert_eq!((report.created, report.merged), (1, 1));
+        assert_eq!(report.images_imported, 1);
+
+        let created = db.get_entry_by_date("2024-01-01").expect("get failed").expect("entry missing");
+        assert_eq!(created.title.as_deref(), Some("New year"));
+        assert_eq!(created.content.as_deref(), Some("Started fresh.\n\n🧠 Emotion: joy"));
+        let cover = created.image.expect("cover image missing");
+        assert!(base_dir.join(&cover).exists());
+        assert_eq!(db.get_attachments_for_entry("2024-01-01").expect("list failed").len(), 1);
+
+        let merged = db.get_entry_by_date("2024-01-02").expect("get failed").expect("entry missing");
+        assert_eq!(merged.title.as_deref(), Some("Existing"));
+        assert_eq!(merged.content.as_deref(), Some("Already here.\n\n---\n\nSecond day notes\n\n🧠 Emotion: neutral"),
+            "Merging keeps the existing emotion and appends the imported text.");
+
+        let again = import_journal(&db, &base_dir, &AppSettings::default(), ImportSource::DayOne, &zip_path, &options, Some(&classify)).expect("second import failed");
+        assert_eq!(again.merged, 2);
+        let merged_again = db.get_entry_by_date("2024-01-02").expect("get failed").expect("entry missing");
+        assert_eq!(merged_again.content, merged.content, "Re-importing must not duplicate merged text.");
+        assert_eq!(again.images_imported, 0, "Images already attached should not be stored again.");
+        assert_eq!(db.get_attachments_for_entry("2024-01-01").expect("list failed").len(), 1, "Re-importing must not duplicate attachments.");
+        let image_files = fs::read_dir(base_dir.join(attachments::IMAGES_DIR_NAME)).expect("read dir failed")
+            .filter(|e| e.as_ref().map(|e| e.path().is_file()).unwrap_or(false))
+            .count();
+        assert_eq!(image_files, 1, "Re-importing must not store duplicate image files.");
+
+        let _ = fs::remove_dir_all(base_dir);
+    }
+
+    #[test]
+    fn test_journey_import_with_rename() {
+        let base_dir = get_test_dir("journey_rename");
+        let db = setup_db(&base_dir);
+        let noon = Utc.with_ymd_and_hms(2024, 1, 2, 12, 0, 0).unwrap().timestamp_millis();
+        let entry = serde_json::json!({
+            "id": "J1",
+            "date_journal": noon,
+            "text": "<p>Walked &amp; talked</p><p>Good day</p>",
+            "type": "html",
+            "photos": ["J1-photo.png", "gone.jpg"]
+        });
+        let zip_path = base_dir.join("journey.zip");
+        write_zip(&zip_path, &[
+            ("J1.json", serde_json::to_vec(&entry).unwrap()),
+            ("J1-photo.png", tiny_png()),
+        ]);
+
+        let options = ImportOptions { policy: ConflictPolicy::Rename, ..Default::default() };
+        let report = import_journal(&db, &base_dir, &AppSettings::default(), ImportSource::Journey, &zip_path, &options, None).expect("import failed");
+        assert_eq!(report.renamed, 1);
+        assert_eq!(report.items[0].target_date, "2024-01-03");
+        assert!(report.warnings.iter().any(|w| w.contains("gone.jpg")));
+
+        let renamed = db.get_entry_by_date("2024-01-03").expect("get failed").expect("entry missing");
+        assert_eq!(renamed.content.as_deref(), Some("Walked & talked\n\nGood day"));
+        assert!(renamed.image.is_some());
+
+        let _ = fs::remove_dir_all(base_dir);
+    }
+
+    #[test]
+    fn test_markdown_folder_round_trips_export_output() {
+        let base_dir = get_test_dir("markdown_folder");
+        let db = setup_db(&base_dir);
+        let folder = base_dir.join("notes");
+        fs::create_dir_all(folder.join("images")).unwrap();
+        fs::write(folder.join("images").join("pic.png"), tiny_png()).unwrap();
+        fs::write(folder.join("2024-02-10.md"),
+            "---\ndate: 2024-02-10\ntitle: \"Snow \\\"day\\\"\"\nemotion: \"surprise\"\nsuggestions:\n  - \"Build a snowman\"\n---\n\n# Snow \"day\"\n\nIt snowed.\n\n![](images/pic.png)\n\n![](https://example.com/remote.png)\n").unwrap();
+        fs::write(folder.join("2024-02-11 quiet-evening.txt"), "Read a book.").unwrap();
+        fs::write(folder.join("undated.md"), "Who knows when.").unwrap();
+
+        let report = import_journal(&db, &base_dir, &AppSettings::default(), ImportSource::Markdown, &folder, &ImportOptions::default(), None).expect("import failed");
+        assert_eq!(report.created, 2);
+        assert!(report.warnings.iter().any(|w| w.contains("undated.md")));
+
+        let snow = db.get_entry_by_date("2024-02-10").expect("get failed").expect("entry missing");
+        assert_eq!(snow.title.as_deref(), Some("Snow \"day\""));
+        assert_eq!(snow.content.as_deref(), Some("It snowed.\n\n![](https://example.com/remote.png)\n\n🧠 Emotion: surprise\n\n💡 Suggestion: - Build a snowman"));
+        assert!(snow.image.is_some(), "Local images should be copied into journal_images.");
+
+        let quiet = db.get_entry_by_date("2024-02-11").expect("get failed").expect("entry missing");
+        assert_eq!(quiet.title.as_deref(), Some("quiet evening"));
+        assert_eq!(quiet.content.as_deref(), Some("Read a book."));
+
+        let _ = fs::remove_dir_all(base_dir);
+    }
+
+    #[test]
+    fn test_resolve_link_stays_inside_source() {
+        assert_eq!(resolve_link("notes/2024-01-01.md", "images/a.png").as_deref(), Some("notes/images/a.png"));
+        assert_eq!(resolve_link("notes/2024-01-01.md", "../shared/my%20pic.png").as_deref(), Some("shared/my pic.png"));
+        assert_eq!(resolve_link("2024-01-01.md", "../../etc/passwd"), None);
+    }
+}
@@ -1,17 +1,202 @@
-// Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
+pub mod attachments;
+pub mod backup;
+pub mod calendar;
+pub mod checkins;
+pub mod commands;
+mod config;
+pub mod context;
+pub mod dataset;
 pub mod db;
+pub mod dictation;
+pub mod drafts;
+pub mod emotion;
+pub mod error;
+pub mod export;
+pub mod images;
+pub mod importers;
+pub mod listing;
+pub mod maintenance;
 pub mod password;
+pub mod revisions;
+pub mod services;
+pub mod settings;
+pub mod snapshots;
+pub mod suggestion;
+pub mod tags;
+pub mod trash;
 
-#[tauri::command]
-fn greet(name: &str) -> String {
-    format!("Hello, {}! You've been greeted from Rust!", name)
+use context::AppContext;
+use db::Database;
+use dictation::DictationModel;
+use emotion::EmotionModel;
+use std::fs;
+use std::sync::Arc;
+use tauri::{App, AppHandle, Manager, path::BaseDirectory};
+
+// image directory, legacy image migration, orphaned chat messages and the optional startup cleanup
+fn prepare_local_data(ctx: &AppContext) {
+    let images_path = ctx.local_data_dir.join(attachments::IMAGES_DIR_NAME);
+    if !images_path.exists() {
+        if let Err(e) = fs::create_dir_all(&images_path) {
+            eprintln!("Could not create images directory on startup at {:?}: {}", images_path, e);
+        }
+        else {
+            println!("Images directory ensured at: {:?}", images_path);
+        }
+    }
+
+    match attachments::migrate_legacy_images(&ctx.db, &ctx.local_data_dir) {
+        Ok(0) => {}
+        Ok(count) => println!("Migrated {} legacy entry image(s) into attachments.", count),
+        Err(e) => eprintln!("Failed to migrate legacy entry images into attachments: {}", e),
+    }
+
+    // sessions deleted while foreign keys were off left their messages behind
+    match ctx.db.delete_orphaned_chat_messages() {
+        Ok(0) => {}
+        Ok(count) => println!("Removed {} orphaned chat message(s).", count),
+        Err(e) => eprintln!("Failed to remove orphaned chat messages: {}", e),
+    }
+
+    match services::purge_expired_trash(ctx) {
+        Ok(report) if report.purged_entries.is_empty() && report.purged_chat_sessions.is_empty() => {}
+        Ok(report) => println!(
+            "Purged {} entry(s) and {} chat session(s) from the trash.",
+            report.purged_entries.len(), report.purged_chat_sessions.len()
+        ),
+        Err(e) => eprintln!("Failed to purge the trash: {}", e),
+    }
+
+    // drafts are kept until they are saved or discarded; the app offers them again after unlocking
+    match ctx.db.get_drafts() {
+        Ok(drafts) if drafts.is_empty() => {}
+        Ok(drafts) => println!("Found {} unsaved draft(s) from an earlier session.", drafts.len()),
+        Err(e) => eprintln!("Failed to look for unsaved drafts: {}", e),
+    }
+
+    match services::prune_revisions(ctx, None) {
+        Ok(0) => {}
+        Ok(count) => println!("Removed {} entry revision(s) beyond the retention limit.", count),
+        Err(e) => eprintln!("Failed to prune entry revisions: {}", e),
+    }
+
+    if ctx.current_settings().image_gc_on_startup {
+        // runs while the app is still locked, so it goes around the services lock check
+        match maintenance::reconcile_images(&ctx.db, &ctx.local_data_dir, true) {
+            Ok(report) => println!(
+                "Startup image cleanup removed {} orphaned file(s) and {} dangling reference(s).",
+                report.removed_files, report.cleared_references
+            ),
+            Err(e) => eprintln!("Startup image cleanup failed: {}", e),
+        }
+    }
+}
+
+fn load_models(app: &App, ctx: AppContext) -> AppContext {
+    let resource_path = app.path().resolve("models", BaseDirectory::Resource)
+        .expect("ERROR: Failed to resolve 'models' resource directory. Check tauri.conf.json, ensure it exists in src-tauri/, and is in bundle resources.");
+
+    println!("Models resource path for ML models: {:?}", resource_path);
+    if !resource_path.exists() {
+        eprintln!("CRITICAL: Base 'models' directory for ML models does not exist at {:?}.", resource_path);
+        panic!("Base 'models' directory not found. Ensure models are bundled correctly.");
+    }
+
+    let emotion_model_base_path = resource_path.join("emotion");
+    println!("[lib.rs] Attempting to load emotion model from: {:?}", emotion_model_base_path);
+    let emotion_model_instance = EmotionModel::new(emotion_model_base_path)
+        .expect("CRITICAL: Failed to initialize EmotionModel. Check model files and paths.");
+    println!("[lib.rs] EmotionModel initialized.");
+
+    let dictation_model_name = dictation::DEFAULT_DICTATION_MODEL;
+    log::info!("[lib.rs] Attempting to load dictation model: {}", dictation_model_name);
+    let dictation_model_instance = dictation::find_model_file(&resource_path, dictation_model_name)
+        .and_then(|model_path| DictationModel::new(&model_path))
+        .expect("CRITICAL: Failed to initialize DictationModel. Check model file and paths.");
+    log::info!("[lib.rs] DictationModel ('{}') initialized.", dictation_model_name);
+
+    ctx.with_emotion_model(emotion_model_instance)
+        .with_dictation_model(dictation_model_instance)
+}
+
+// checks the backup schedule on launch and then hourly for as long as the app runs
+fn spawn_snapshot_scheduler(app_handle: AppHandle) {
+    std::thread::spawn(move || loop {
+        let ctx = app_handle.state::<Arc<AppContext>>();
+        if let Err(e) = services::run_scheduled_backup(&ctx) {
+            eprintln!("Scheduled backup failed: {}", e);
+        }
+        std::thread::sleep(std::time::Duration::from_secs(60 * 60));
+    });
 }
 
-#[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
-        .plugin(tauri_plugin_opener::init())
-        .invoke_handler(tauri::generate_handler![greet])
+        .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_fs::init())
+        .setup(|app| {
+            let config_dir = app.path().app_config_dir()
+                .expect("Failed to get app config directory");
+            fs::create_dir_all(&config_dir)
+                .expect("Failed to create directory for password file");
+
+            let app_data_dir_path = app.path().app_data_dir()
+                .expect("Failed to get app data directory for database");
+            if !app_data_dir_path.exists() {
+                fs::create_dir_all(&app_data_dir_path)
+                    .expect("Failed to create main app data directory");
+            }
+
+            let app_db_file_path = app_data_dir_path.join(context::DB_FILE_NAME);
+            let database = match Database::open(&app_db_file_path) {
+                Ok(database) => database,
+                Err(e) => {
+                    eprintln!("CRITICAL: Failed to initialize database at {:?}: {}", app_db_file_path, e);
+                    panic!("Database initialization failed. Application cannot continue.");
+                }
+            };
+
+            let local_data_dir = app.path().app_local_data_dir()
+                .expect("Could not resolve app local data directory");
+            let ctx = AppContext::new(database, local_data_dir, config_dir);
+            prepare_local_data(&ctx);
+
+            let ctx = load_models(app, ctx);
+            app.manage(Arc::new(ctx));
+
+            spawn_snapshot_scheduler(app.handle().clone());
+
+            Ok(())
+        })
+        .invoke_handler(tauri::generate_handler![
+            commands::is_locked_cmd, commands::check_password_attempt_cmd, commands::set_new_password_cmd,
+            commands::set_locked_explicit_cmd, commands::is_pin_set_cmd, commands::delete_pin_cmd,
+
+            commands::get_settings_cmd, commands::update_settings_cmd,
+
+            commands::create_entry, commands::read_entries, commands::list_entries, commands::get_entry, commands::update_entry, commands::delete_entry,
+            commands::list_entry_revisions_cmd, commands::diff_entry_revisions_cmd, commands::restore_entry_revision_cmd,
+            commands::list_tags_cmd, commands::get_entry_tags_cmd, commands::add_entry_tag_cmd, commands::remove_entry_tag_cmd,
+            commands::rename_tag_cmd, commands::merge_tags_cmd, commands::delete_tag_cmd, commands::tag_emotion_stats_cmd,
+            commands::record_checkin_cmd, commands::list_checkins_cmd, commands::delete_checkin_cmd, commands::compare_checkins_cmd,
+            commands::save_draft_cmd, commands::load_draft_cmd, commands::discard_draft_cmd, commands::list_stale_drafts_cmd, commands::promote_draft_cmd,
+            commands::list_trash_cmd, commands::restore_entry_cmd, commands::purge_entry_cmd,
+            commands::restore_chat_session_cmd, commands::purge_chat_session_cmd, commands::empty_trash_cmd,
+
+            commands::list_attachments_cmd, commands::add_attachment_cmd, commands::remove_attachment_cmd, commands::reorder_attachments_cmd,
+            commands::reconcile_images_cmd, commands::check_database_cmd,
+
+            commands::export_backup_cmd, commands::read_backup_manifest_cmd, commands::import_backup_cmd,
+            commands::list_snapshots_cmd, commands::create_snapshot_cmd, commands::restore_snapshot_cmd,
+            commands::export_journal_cmd, commands::import_journal_cmd, commands::export_dataset_cmd, commands::import_dataset_cmd,
+
+            commands::classify_emotion, commands::perform_dictation_cmd, commands::perform_dictation_report_cmd,
+            commands::upload_image_file, commands::get_image_thumbnail_cmd,
+            commands::generate_suggestion_cmd,
+
+            commands::chat_with_moodjourney_cmd, commands::load_chat_sessions,
+            commands::load_messages_for_session_cmd, commands::delete_chat_session_cmd
+        ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
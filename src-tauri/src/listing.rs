@@ -0,0 +1,202 @@
+use crate::db::{Database, EntryListRow, EntryQuery};
+use crate::error::AppError;
+use crate::export;
+use crate::tags;
+use anyhow::Result;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_PAGE_SIZE: usize = 50;
+pub const MAX_PAGE_SIZE: usize = 500;
+const EXCERPT_CHARS: usize = 160;
+
+// what the entry list shows; the full entry is loaded with get_entry when one is opened
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EntrySummary {
+    pub date: String,
+    pub title: String,
+    pub emotion: Option<String>,
+    pub excerpt: String,
+    pub has_image: bool,
+    pub protected: bool,
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EntryPage {
+    pub entries: Vec<EntrySummary>,
+    // pass back as the query cursor to get the next page; None on the last page
+    pub next_cursor: Option<String>,
+}
+
+fn validate_date(value: Option<&str>, label: &str) -> Result<Option<String>> {
+    match value.map(str::trim).filter(|v| !v.is_empty()) {
+        Some(v) => NaiveDate::parse_from_str(v, "%Y-%m-%d")
+            .map(|_| Some(v.to_string()))
+            .map_err(|_| AppError::InvalidDate(format!("The {} date must be in YYYY-MM-DD format, got '{}'.", label, v)).into()),
+        None => Ok(None),
+    }
+}
+
+// the text, emotion, excerpt and tags of a protected entry stay hidden, as in the CLI listing
+fn summarize(row: EntryListRow) -> EntrySummary {
+    let (body, emotion, _) = export::parse_entry_content(row.content.as_deref().unwrap_or(""));
+    EntrySummary {
+        title: row.title.filter(|t| !t.trim().is_empty()).unwrap_or_else(|| row.date.clone()),
+        date: row.date,
+        emotion: if row.protected { None } else { emotion },
+        excerpt: if row.protected { String::new() } else { export::excerpt(&body, EXCERPT_CHARS) },
+        has_image: row.has_image,
+        protected: row.protected,
+        tags: if row.protected { Vec::new() } else { row.tags },
+    }
+}
+
+pub fn list_entries(db: &Database, query: &EntryQuery) -> Result<EntryPage> {
+    let from_date = validate_date(query.from_date.as_deref(), "start")?;
+    let to_date = validate_date(query.to_date.as_deref(), "end")?;
+    if let (Some(from), Some(to)) = (&from_date, &to_date) {
+        if from > to {
+            return Err(AppError::InvalidDate(format!("The start date {} is after the end date {}.", from, to)).into());
+        }
+    }
+    let cursor = query.cursor.as_deref().map(str::trim).filter(|c| !c.is_empty());
+    if cursor.is_some_and(|c| NaiveDate::parse_from_str(c, "%Y-%m-%d").is_err()) {
+        return Err(AppError::InvalidInput("The cursor must be the date of an entry from the previous page.".to_string()).into());
+    }
+    let limit = match query.limit {
+        None | Some(0) => DEFAULT_PAGE_SIZE,
+        Some(limit) => limit.min(MAX_PAGE_SIZE),
+    };
+
+    let normalized = EntryQuery {
+        cursor: cursor.map(str::to_string),
+        limit: Some(limit),
+        from_date,
+        to_date,
+        emotions: query.emotions.iter().map(|e| e.trim().to_string()).filter(|e| !e.is_empty()).collect(),
+        has_image: query.has_image,
+        tags: query.tags.iter().map(|t| tags::normalize_tag_name(t)).collect::<Result<_>>()?,
+        order: query.order,
+    };
+    let mut rows = db.list_entry_rows(&normalized, limit)?;
+    let next_cursor = if rows.len() > limit {
+        rows.truncate(limit);
+        rows.last().map(|row| row.date.clone())
+    } else {
+        None
+    };
+    Ok(EntryPage { entries: rows.into_iter().map(summarize).collect(), next_cursor })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{Entry, NewAttachment, SortOrder};
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn setup(test_name: &str) -> (PathBuf, Database) {
+        let mut dir = std::env::temp_dir();
+        dir.push("moodjourney_listing_tests");
+        dir.push(test_name);
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).expect("Failed to create test directory");
+        let db = Database::open(&dir.join("journal.db")).expect("Failed to open database");
+
+        let emotions = ["joy", "sadness", "Joy", "anger", "joy"];
+        for (day, emotion) in emotions.iter().enumerate() {
+            db.add_entry(Entry {
+                date: format!("2025-03-{:02}", day + 1),
+                title: Some(format!("Day {}", day + 1)),
+                content: Some(format!("Wrote   about day {}.\n\n🧠 Emotion: {}\n\n💡 Suggestion: - Rest", day + 1, emotion)),
+                password: if day == 4 { Some("hash".to_string()) } else { None },
+                image: if day == 1 { Some("images/cover.png".to_string()) } else { None },
+            }).expect("Failed to add entry");
+        }
+        db.add_attachment("2025-03-03", &NewAttachment {
+            kind: "image".to_string(),
+            relative_path: "images/extra.png".to_string(),
+            mime: Some("image/png".to_string()),
+            size: 10,
+            checksum: None,
+        }).expect("Failed to add attachment");
+        (dir, db)
+    }
+
+    fn dates(page: &EntryPage) -> Vec<&str> {
+        page.entries.iter().map(|e| e.date.as_str()).collect()
+    }
+
+    #[test]
+    fn test_cursor_pages_through_every_entry() {
+        let (dir, db) = setup("cursor");
+        let mut query = EntryQuery { limit: Some(2), ..Default::default() };
+        let mut seen = Vec::new();
+        loop {
+            let page = list_entries(&db, &query).expect("list failed");
+            assert!(page.entries.len() <= 2, "A page should not exceed the limit.");
+            seen.extend(dates(&page).into_iter().map(str::to_string));
+            match page.next_cursor {
+                Some(cursor) => query.cursor = Some(cursor),
+                None => break,
+            }
+        }
+        assert_eq!(seen, vec!["2025-03-05", "2025-03-04", "2025-03-03", "2025-03-02", "2025-03-01"]);
+
+        let oldest = list_entries(&db, &EntryQuery { limit: Some(3), order: SortOrder::OldestFirst, ..Default::default() }).expect("list failed");
+        assert_eq!(dates(&oldest), vec!["2025-03-01", "2025-03-02", "2025-03-03"]);
+        assert_eq!(oldest.next_cursor.as_deref(), Some("2025-03-03"));
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_filters_and_summary_projection() {
+        let (dir, db) = setup("filters");
+
+        let joyful = list_entries(&db, &EntryQuery { emotions: vec!["JOY".to_string()], ..Default::default() }).expect("list failed");
+        assert_eq!(dates(&joyful), vec!["2025-03-03", "2025-03-01"], "Emotion should match case-insensitively and skip protected entries.");
+
+        let with_image = list_entries(&db, &EntryQuery { has_image: Some(true), ..Default::default() }).expect("list failed");
+        assert_eq!(dates(&with_image), vec!["2025-03-03", "2025-03-02"], "Cover images and image attachments should both count.");
+        let without_image = list_entries(&db, &EntryQuery { has_image: Some(false), ..Default::default() }).expect("list failed");
+        assert_eq!(without_image.entries.len(), 3);
+
+        let ranged = list_entries(&db, &EntryQuery {
+            from_date: Some("2025-03-02".to_string()),
+            to_date: Some("2025-03-04".to_string()),
+            ..Default::default()
+        }).expect("list failed");
+        assert_eq!(dates(&ranged), vec!["2025-03-04", "2025-03-03", "2025-03-02"]);
+
+        let first = &ranged.entries[2];
+        assert_eq!(first.title, "Day 2");
+        assert_eq!(first.emotion.as_deref(), Some("sadness"));
+        assert_eq!(first.excerpt, "Wrote about day 2.");
+        assert!(first.has_image);
+
+        let all = list_entries(&db, &EntryQuery::default()).expect("list failed");
+        let protected = &all.entries[0];
+        assert!(protected.protected);
+        assert!(protected.emotion.is_none() && protected.excerpt.is_empty(), "A protected entry should not leak its content.");
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_rejects_bad_dates_and_cursor() {
+        let (dir, db) = setup("invalid");
+        let err = list_entries(&db, &EntryQuery { from_date: Some("2025-02-30".to_string()), ..Default::default() }).unwrap_err();
+        assert_eq!(AppError::code_of(&err), "INVALID_DATE");
+        let err = list_entries(&db, &EntryQuery {
+            from_date: Some("2025-03-04".to_string()),
+            to_date: Some("2025-03-01".to_string()),
+            ..Default::default()
+        }).unwrap_err();
+        assert_eq!(AppError::code_of(&err), "INVALID_DATE");
+        let err = list_entries(&db, &EntryQuery { cursor: Some("page-2".to_string()), ..Default::default() }).unwrap_err();
+        assert_eq!(AppError::code_of(&err), "INVALID_INPUT");
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,309 @@
+use crate::attachments::{self, IMAGES_DIR_NAME};
+use crate::db::Database;
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DanglingReference {
+    pub entry_date: String,
+    pub relative_path: String,
+    // "entry" for the legacy image column, "attachment" for attachments rows
+    pub source: String,
+    pub attachment_id: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageGcReport {
+    pub applied: bool,
+    pub scanned_files: usize,
+    pub referenced_paths: usize,
+    pub orphaned_files: Vec<String>,
+    pub orphaned_bytes: u64,
+    pub dangling_references: Vec<DanglingReference>,
+    pub removed_files: usize,
+    pub cleared_references: usize,
+}
+
+impl ImageGcReport {
+    pub fn is_clean(&self) -> bool {
+        self.orphaned_files.is_empty() && self.dangling_references.is_empty()
+    }
+}
+
+fn collect_files(dir: &Path, base_dir: &Path, files: &mut Vec<(String, u64)>) -> Result<()> {
+    let read_dir = fs::read_dir(dir).map_err(|e| anyhow!("Failed to read directory {:?}: {}", dir, e))?;
+    for dir_entry in read_dir {
+        let dir_entry = dir_entry.map_err(|e| anyhow!("Failed to read directory entry in {:?}: {}", dir, e))?;
+        let path = dir_entry.path();
+        let metadata = dir_entry.metadata().map_err(|e| anyhow!("Failed to read metadata for {:?}: {}", path, e))?;
+        if metadata.is_dir() {
+            collect_files(&path, base_dir, files)?;
+        } else if let Ok(relative) = path.strip_prefix(base_dir) {
+            let relative_path = relative.components()
+                .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                .collect::<Vec<_>>()
+                .join("/");
+            files.push((relative_path, metadata.len()));
+        }
+    }
+    Ok(())
+}
+
+// compares journal_images against entries.image and the attachments table; with apply
+// set, orphaned files are deleted and references to missing files are dropped
+pub fn reconcile_images(db: &Database, base_dir: &Path, apply: bool) -> Result<ImageGcReport> {
+    let mut report = ImageGcReport { applied: apply, ..Default::default() };
+
+    // images of entries in the trash are still referenced until the entry is purged
+    let mut entry_images: Vec<(String, Option<String>)> = db.get_entries()?.into_iter().map(|e| (e.date, e.image)).collect();
+    entry_images.extend(db.get_trashed_entries()?.into_iter().map(|e| (e.date, e.image)));
+    let all_attachments = db.get_all_attachments()?;
+
+    let mut referenced: HashSet<String> = HashSet::new();
+    for (entry_date, image) in &entry_images {
+        if let Some(image_path) = image.as_deref().filter(|p| !p.trim().is_empty()) {
+            referenced.insert(image_path.to_string());
+            if !base_dir.join(image_path).exists() {
+                report.dangling_references.push(DanglingReference {
+                    entry_date: entry_date.clone(),
+                    relative_path: image_path.to_string(),
+                    source: "entry".to_string(),
+                    attachment_id: None,
+                });
+            }
+        }
+    }
+    for attachment in &all_attachments {
+        referenced.insert(attachment.relative_path.clone());
+        if !base_dir.join(&attachment.relative_path).exists() {
+            report.dangling_references.push(DanglingReference {
+                entry_date: attachment.entry_date.clone(),
+                relative_path: attachment.relative_path.clone(),
+                source: "attachment".to_string(),
+                attachment_id: Some(attachment.id),
+            });
+        }
+    }
+    report.referenced_paths = referenced.len();
+
+    let thumbnails: HashSet<String> = referenced.iter()
+        .map(|path| attachments::thumbnail_relative_path(path))
+        .collect();
+
+    let images_dir = base_dir.join(IMAGES_DIR_NAME);
+    let mut files: Vec<(String, u64)> = Vec::new();
+    if images_dir.exists() {
+        collect_files(&images_dir, base_dir, &mut files)?;
+    }
+    files.sort();
+    report.scanned_files = files.len();
+
+    for (relative_path, size) in files {
+        if !referenced.contains(&relative_path) && !thumbnails.contains(&relative_path) {
+            report.orphaned_bytes += size;
+            report.orphaned_files.push(relative_path);
+        }
+    }
+
+    if apply {
+        for relative_path in &report.orphaned_files {
+            let full_path = base_dir.join(relative_path);
+            match fs::remove_file(&full_path) {
+                Ok(()) => report.removed_files += 1,
+                Err(e) => log::warn!("[maintenance] Failed to delete orphaned file {:?}: {}", full_path, e),
+            }
+        }
+        for dangling in &report.dangling_references {
+            match dangling.attachment_id {
+                Some(attachment_id) => { db.delete_attachment(attachment_id)?; }
+                None => db.clear_entry_image(&dangling.entry_date)?,
+            }
+            report.cleared_references += 1;
+        }
+    }
+
+    log::info!(
+        "[maintenance] Image check: {} file(s) scanned, {} orphaned ({} bytes), {} dangling reference(s), applied: {}.",
+        report.scanned_files,
+        report.orphaned_files.len(),
+        report.orphaned_bytes,
+        report.dangling_references.len(),
+        apply
+    );
+    Ok(report)
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityReport {
+    pub repaired: bool,
+    // output of PRAGMA integrity_check, ["ok"] when the file is sound
+    pub integrity_check: Vec<String>,
+    pub orphaned_chat_messages: usize,
+    pub orphaned_attachments: usize,
+    pub invalid_dates: Vec<String>,
+    pub removed_chat_messages: usize,
+    pub removed_attachments: usize,
+}
+
+impl IntegrityReport {
+    pub fn is_healthy(&self) -> bool {
+        self.integrity_check.iter().all(|line| line == "ok")
+            && self.orphaned_chat_messages == 0
+            && self.orphaned_attachments == 0
+            && self.invalid_dates.is_empty()
+    }
+}
+
+// reports database health; with repair set, rows whose parent is gone are deleted.
+// invalid dates are only reported since the entry would have to be renamed by hand
+pub fn check_database(db: &Database, repair: bool) -> Result<IntegrityReport> {
+    let mut report = IntegrityReport {
+        repaired: repair,
+        integrity_check: db.integrity_check()?,
+        orphaned_chat_messages: db.count_orphaned_chat_messages()?,
+        orphaned_attachments: db.count_orphaned_attachments()?,
+        invalid_dates: db.get_invalid_entry_dates()?,
+        ..Default::default()
+    };
+
+    if repair {
+        report.removed_chat_messages = db.delete_orphaned_chat_messages()?;
+        report.removed_attachments = db.delete_orphaned_attachments()?;
+    }
+
+    log::info!(
+        "[maintenance] Database check: integrity {:?}, {} orphaned message(s), {} orphaned attachment(s), {} invalid date(s), repaired: {}.",
+        report.integrity_check,
+        report.orphaned_chat_messages,
+        report.orphaned_attachments,
+        report.invalid_dates.len(),
+        repair
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{Database, Entry, NewAttachment};
+    use std::path::PathBuf;
+
+    fn get_test_dir(test_name: &str) -> PathBuf {
+        let mut path = std::env::temp_dir();
+        path.push("moodjourney_maintenance_tests");
+        path.push(test_name);
+        if path.exists() {
+            let _ = fs::remove_dir_all(&path);
+        }
+        fs::create_dir_all(&path).expect("Failed to create temporary test directory for maintenance");
+        path
+    }
+
+    fn setup(test_name: &str) -> (PathBuf, Database, String) {
+        let base_dir = get_test_dir(test_name);
+        let db = Database::open(&base_dir.join("entries.db")).expect("init failed");
+
+        let kept = attachments::store_file(&base_dir, b"kept", "kept.png", "png").expect("store failed");
+        attachments::store_thumbnail(&base_dir, &kept, b"thumb").expect("thumbnail failed");
+        attachments::store_file(&base_dir, b"orphan", "orphan.png", "png").expect("store failed");
+
+        db.add_entry(Entry {
+            date: "2025-06-01".to_string(),
+            title: Some("Kept".to_string()),
+            content: None,
+            password: None,
+            image: Some(kept.clone()),
+        }).expect("add failed");
+        db.add_entry(Entry {
+            date: "2025-06-02".to_string(),
+            title: Some("Missing".to_string()),
+            content: None,
+            password: None,
+            image: Some("journal_images/missing.png".to_string()),
+        }).expect("add failed");
+        db.add_attachment("2025-06-01", &NewAttachment {
+            kind: "file".to_string(),
+            relative_path: "journal_images/gone.pdf".to_string(),
+            mime: None,
+            size: 0,
+            checksum: None,
+        }).expect("add attachment failed");
+
+        (base_dir, db, kept)
+    }
+
+    #[test]
+    fn test_report_only_does_not_modify_anything() {
+        let (base_dir, db, _kept) = setup("report_only");
+
+        let report = reconcile_images(&db, &base_dir, false).expect("reconcile failed");
+        assert_eq!(report.scanned_files, 3);
+        assert_eq!(report.orphaned_files.len(), 1);
+        assert_eq!(report.orphaned_bytes, 6);
+        assert_eq!(report.dangling_references.len(), 2);
+        assert_eq!(report.removed_files, 0);
+        assert!(!report.is_clean());
+        assert!(base_dir.join(&report.orphaned_files[0]).exists(), "Report-only run must not delete files.");
+
+        let _ = fs::remove_dir_all(base_dir);
+    }
+
+    #[test]
+    fn test_apply_cleans_orphans_and_dangling_references() {
+        let (base_dir, db, kept) = setup("apply");
+
+        let report = reconcile_images(&db, &base_dir, true).expect("reconcile failed");
+        assert_eq!(report.removed_files, 1);
+        assert_eq!(report.cleared_references, 2);
+        assert!(base_dir.join(&kept).exists(), "Referenced image must be kept.");
+        assert!(base_dir.join(attachments::thumbnail_relative_path(&kept)).exists(), "Thumbnail of a referenced image must be kept.");
+
+        let missing_entry = db.get_entry_by_date("2025-06-02").expect("get failed").expect("entry missing");
+        assert!(missing_entry.image.is_none());
+        assert!(db.get_all_attachments().expect("list failed").is_empty());
+
+        let second_pass = reconcile_images(&db, &base_dir, false).expect("reconcile failed");
+        assert!(second_pass.is_clean(), "Second pass should find nothing: {:?}", second_pass);
+
+        let _ = fs::remove_dir_all(base_dir);
+    }
+
+    #[test]
+    fn test_check_database_reports_and_repairs_orphans() {
+        let (base_dir, db, _kept) = setup("check_database");
+        let session_id = db.create_new_chat_session().expect("create failed");
+        db.save_chat_message(&session_id, "user", "Hello").expect("save failed");
+        {
+            let conn = rusqlite::Connection::open(base_dir.join("entries.db")).expect("open failed");
+            conn.pragma_update(None, "foreign_keys", "OFF").expect("pragma failed");
+            conn.execute("INSERT INTO assistant_chat_messages (session_id, sender, content) VALUES ('deleted', 'user', 'Lost')", []).expect("insert failed");
+            conn.execute("INSERT INTO attachments (entry_date, kind, relative_path) VALUES ('2025-06-09', 'image', 'journal_images/lost.png')", []).expect("insert failed");
+            conn.execute("INSERT INTO entries (date, title) VALUES ('2025-13-01', 'Bad date')", []).expect("insert failed");
+        }
+
+        let report = check_database(&db, false).expect("check failed");
+        assert_eq!(report.integrity_check, vec!["ok".to_string()]);
+        assert_eq!(report.orphaned_chat_messages, 1);
+        assert_eq!(report.orphaned_attachments, 1);
+        assert_eq!(report.invalid_dates, vec!["2025-13-01".to_string()]);
+        assert_eq!(report.removed_chat_messages, 0, "Report-only run must not delete rows.");
+        assert!(!report.is_healthy());
+
+        let repaired = check_database(&db, true).expect("repair failed");
+        assert_eq!(repaired.removed_chat_messages, 1);
+        assert_eq!(repaired.removed_attachments, 1);
+        assert_eq!(db.get_messages_for_session(&session_id).expect("list failed").len(), 1);
+
+        let second_pass = check_database(&db, false).expect("check failed");
+        assert_eq!(second_pass.orphaned_chat_messages + second_pass.orphaned_attachments, 0);
+        assert_eq!(second_pass.invalid_dates.len(), 1, "Invalid dates are reported, not removed.");
+
+        let _ = fs::remove_dir_all(base_dir);
+    }
+}
@@ -0,0 +1,167 @@
+use crate::db::{Database, EntryRevision};
+use crate::error::AppError;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use similar::{ChangeTag, TextDiff};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum DiffLineKind {
+    Unchanged,
+    Added,
+    Removed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffLine {
+    pub kind: DiffLineKind,
+    pub text: String,
+}
+
+// line diff of the content between two versions of an entry; a missing to_revision_id means the current entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RevisionDiff {
+    pub entry_date: String,
+    pub from_revision_id: i64,
+    pub to_revision_id: Option<i64>,
+    pub old_title: Option<String>,
+    pub new_title: Option<String>,
+    pub lines: Vec<DiffLine>,
+    // the same changes as a unified diff, for the CLI and for copying
+    pub unified: String,
+}
+
+fn find_revision(db: &Database, date: &str, revision_id: i64) -> Result<EntryRevision> {
+    db.get_entry_revision(revision_id)?
+        .filter(|revision| revision.entry_date == date)
+        .ok_or_else(|| AppError::NotFound(format!("Entry {} has no revision {}.", date, revision_id)).into())
+}
+
+pub fn diff_revisions(db: &Database, date: &str, from_revision_id: i64, to_revision_id: Option<i64>) -> Result<RevisionDiff> {
+    let from = find_revision(db, date, from_revision_id)?;
+    let (new_title, new_content, to_label) = match to_revision_id {
+        Some(to_revision_id) => {
+            let to = find_revision(db, date, to_revision_id)?;
+            (to.title, to.content, format!("revision {}", to_revision_id))
+        }
+        None => {
+            let entry = db.get_entry_by_date(date)?
+                .ok_or_else(|| AppError::NotFound(format!("Entry with this date does not exist: {}", date)))?;
+            (entry.title, entry.content, "current".to_string())
+        }
+    };
+
+    let old_text = from.content.clone().unwrap_or_default();
+    let new_text = new_content.unwrap_or_default();
+    let diff = TextDiff::from_lines(&old_text, &new_text);
+    let lines = diff.iter_all_changes()
+        .map(|change| DiffLine {
+            kind: match change.tag() {
+                ChangeTag::Equal => DiffLineKind::Unchanged,
+                ChangeTag::Insert => DiffLineKind::Added,
+                ChangeTag::Delete => DiffLineKind::Removed,
+            },
+            text: change.value().trim_end_matches('\n').to_string(),
+        })
+        .collect();
+    let unified = diff.unified_diff()
+        .header(&format!("revision {}", from_revision_id), &to_label)
+        .to_string();
+
+    Ok(RevisionDiff {
+        entry_date: date.to_string(),
+        from_revision_id,
+        to_revision_id,
+        old_title: from.title,
+        new_title,
+        lines,
+        unified,
+    })
+}
+
+// puts the title and content of the revision back; the version it replaces becomes a new revision,
+// so a restore can itself be undone. The password and image are left as they are.
+pub fn restore_revision(db: &Database, date: &str, revision_id: i64) -> Result<()> {
+    let revision = find_revision(db, date, revision_id)?;
+    let entry = db.get_entry_by_date(date)?
+        .ok_or_else(|| AppError::NotFound(format!("Entry with this date does not exist: {}", date)))?;
+    db.update_entry_by_date(date, revision.title.as_deref(), revision.content.as_deref(), entry.password.as_deref(), entry.image.as_deref())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Entry;
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn setup(test_name: &str) -> (PathBuf, Database) {
+        let mut dir = std::env::temp_dir();
+        dir.push("moodjourney_revision_tests");
+        dir.push(test_name);
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).expect("Failed to create test directory");
+        let db = Database::open(&dir.join("journal.db")).expect("Failed to open database");
+        db.add_entry(Entry {
+            date: "2025-04-01".to_string(),
+            title: Some("First".to_string()),
+            content: Some("Morning walk.\nRainy.".to_string()),
+            password: None,
+            image: None,
+        }).expect("Failed to add entry");
+        (dir, db)
+    }
+
+    #[test]
+    fn test_updates_record_revisions_and_prune_keeps_newest() {
+        let (dir, db) = setup("record");
+        db.update_entry_by_date("2025-04-01", Some("First"), Some("Morning walk.\nRainy."), Some("hash"), None).expect("update failed");
+        assert!(db.get_entry_revisions("2025-04-01").expect("list failed").is_empty(), "Changing only the password should not add a revision.");
+
+        for text in ["Second", "Third", "Fourth"] {
+            db.update_entry_by_date("2025-04-01", Some(text), Some(text), None, None).expect("update failed");
+        }
+        let revisions = db.get_entry_revisions("2025-04-01").expect("list failed");
+        let titles: Vec<_> = revisions.iter().map(|r| r.title.as_deref().unwrap_or("")).collect();
+        assert_eq!(titles, vec!["Third", "Second", "First"]);
+
+        assert_eq!(db.prune_entry_revisions(Some("2025-04-01"), 2).expect("prune failed"), 1);
+        let titles: Vec<_> = db.get_entry_revisions("2025-04-01").expect("list failed").into_iter().filter_map(|r| r.title).collect();
+        assert_eq!(titles, vec!["Third", "Second"]);
+
+        db.delete_entry_by_date("2025-04-01").expect("delete failed");
+        assert!(db.get_entry_revisions("2025-04-01").expect("list failed").is_empty());
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_diff_and_restore() {
+        let (dir, db) = setup("restore");
+        db.update_entry_by_date("2025-04-01", Some("Edited"), Some("Morning walk.\nSunny."), None, None).expect("update failed");
+        let original = db.get_entry_revisions("2025-04-01").expect("list failed").remove(0);
+
+        let diff = diff_revisions(&db, "2025-04-01", original.id, None).expect("diff failed");
+        assert_eq!(diff.old_title.as_deref(), Some("First"));
+        assert_eq!(diff.new_title.as_deref(), Some("Edited"));
+        assert_eq!(diff.lines, vec![
+            DiffLine { kind: DiffLineKind::Unchanged, text: "Morning walk.".to_string() },
+            DiffLine { kind: DiffLineKind::Removed, text: "Rainy.".to_string() },
+            DiffLine { kind: DiffLineKind::Added, text: "Sunny.".to_string() },
+        ]);
+        assert!(diff.unified.contains("-Rainy.") && diff.unified.contains("+Sunny."));
+
+        restore_revision(&db, "2025-04-01", original.id).expect("restore failed");
+        let entry = db.get_entry_by_date("2025-04-01").expect("get failed").expect("entry missing");
+        assert_eq!(entry.title.as_deref(), Some("First"));
+        assert_eq!(entry.content.as_deref(), Some("Morning walk.\nRainy."));
+        let latest = db.get_entry_revisions("2025-04-01").expect("list failed").remove(0);
+        assert_eq!(latest.title.as_deref(), Some("Edited"), "The replaced version should become a revision.");
+
+        let err = restore_revision(&db, "2025-04-02", original.id).unwrap_err();
+        assert_eq!(AppError::code_of(&err), "NOT_FOUND", "A revision of another entry should not be found.");
+        let _ = fs::remove_dir_all(dir);
+    }
+}
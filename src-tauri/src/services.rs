@@ -0,0 +1,731 @@
+use crate::attachments;
+use crate::backup::{self, BackupManifest};
+use crate::checkins::{self, CheckinInput, CheckinQuery, MoodComparisonReport};
+use crate::context::AppContext;
+use crate::dataset::{self, Dataset, DatasetFileFormat, DatasetImportOptions, DatasetImportReport};
+use crate::db::{Attachment, ChatMessage, ChatSession, Checkin, Draft, Entry, EntryQuery, EntryRevision, Tag};
+use crate::dictation::{DictationOptions, DictationReport};
+use crate::drafts;
+use crate::error::{AppError, Result};
+use crate::export::{self, ExportFormat, ExportOptions, ExportReport};
+use crate::images;
+use crate::importers::{self, EmotionClassifier, ImportOptions, ImportReport, ImportSource};
+use crate::listing::{self, EntryPage};
+use crate::maintenance::{self, ImageGcReport, IntegrityReport};
+use crate::password;
+use crate::revisions::{self, RevisionDiff};
+use crate::settings::{self, AppSettings};
+use crate::snapshots::{self, SnapshotInfo};
+use crate::suggestion::{self, ChatCompletionResponse};
+use crate::tags::{self, TagEmotionStats, TagMergeReport};
+use crate::trash::{self, Trash, TrashPurgeReport};
+use anyhow::Context;
+use chrono::{Local, Utc};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub fn is_locked(ctx: &AppContext) -> bool {
+    password::is_locked(&ctx.password)
+}
+
+pub fn check_password_attempt(ctx: &AppContext, password_str: &str) -> bool {
+    password::check_password(&ctx.password, password_str)
+}
+
+pub fn set_new_password(ctx: &AppContext, password_str: &str) {
+    password::set_password(&ctx.password, password_str);
+}
+
+pub fn set_locked(ctx: &AppContext, locked: bool) {
+    password::set_locked(&ctx.password, locked);
+}
+
+pub fn is_pin_set(ctx: &AppContext) -> bool {
+    password::get_is_pin_set(&ctx.password)
+}
+
+pub fn delete_pin(ctx: &AppContext) {
+    password::do_delete_pin(&ctx.password);
+}
+
+pub fn get_settings(ctx: &AppContext) -> AppSettings {
+    ctx.current_settings()
+}
+
+pub fn update_settings(ctx: &AppContext, new_settings: AppSettings) -> Result<AppSettings> {
+    settings::update_settings(&ctx.settings, new_settings).map_err(AppError::Io)
+}
+
+// automatically creates entry with current local date
+// content and password are optional
+pub fn create_entry(ctx: &AppContext, title: &str, content: Option<&str>, password: Option<&str>, image: Option<&str>) -> Result<()> {
+    let date = Local::now().format("%Y-%m-%d").to_string();
+    create_entry_for_date(ctx, &date, title, content, password, image)?;
+    clear_draft(ctx, drafts::NEW_ENTRY_SLOT)
+}
+
+// an entry deleted earlier still holds its date; add_entry reports it as a duplicate so the
+// caller can offer to restore or purge it
+pub fn create_entry_for_date(ctx: &AppContext, date: &str, title: &str, content: Option<&str>, password: Option<&str>, image: Option<&str>) -> Result<()> {
+    ensure_unlocked(ctx)?;
+    ctx.db.add_entry(Entry {
+        date: date.to_string(),
+        title: Some(title.to_string()),
+        content: content.map(|s| s.to_string()),
+        password: password.map(|s| s.to_string()),
+        image: image.map(|s| s.to_string()),
+    })?;
+
+    // the entry is saved at this point; migrate_legacy_images adds a missing attachment row on the next launch
+    if let Err(e) = attachments::sync_legacy_image(&ctx.db, &ctx.local_data_dir, date, None, image) {
+        eprintln!("[create_entry] Failed to add the image of {} to its attachments: {}", date, e);
+    }
+    Ok(())
+}
+
+// the journal cannot be read or changed until the PIN unlocks the app; the launch and
+// scheduled maintenance tasks run regardless
+fn ensure_unlocked(ctx: &AppContext) -> Result<()> {
+    if is_locked(ctx) {
+        return Err(AppError::Locked("The journal is locked. Enter the PIN to unlock it.".to_string()));
+    }
+    Ok(())
+}
+
+pub fn read_entries(ctx: &AppContext) -> Result<Vec<Entry>> {
+    ensure_unlocked(ctx)?;
+    ctx.db.get_entries()
+}
+
+pub fn list_entries(ctx: &AppContext, query: &EntryQuery) -> Result<EntryPage> {
+    ensure_unlocked(ctx)?;
+    Ok(listing::list_entries(&ctx.db, query)?)
+}
+
+pub fn get_entry(ctx: &AppContext, date: &str) -> Result<Option<Entry>> {
+    ensure_unlocked(ctx)?;
+    ctx.db.get_entry_by_date(date)
+}
+
+// replacing or clearing the image deletes the previous file
+pub fn update_entry(
+    ctx: &AppContext,
+    date: &str,
+    new_title: &str,
+    new_content: Option<&str>,
+    new_password: Option<&str>,
+    new_image: Option<&str>,
+) -> Result<()> {
+    ensure_unlocked(ctx)?;
+    let previous_image = match ctx.db.get_entry_by_date(date)? {
+        Some(current_entry) => current_entry.image,
+        None => return Err(AppError::NotFound(format!("Entry with this date does not exist: {}", date))),
+    };
+
+    ctx.db.update_entry_by_date(date, Some(new_title), new_content, new_password, new_image)?;
+    // the replaced image file is only removed once the entry no longer points at it
+    if let Some(old_image_relative_path) = previous_image.as_deref() {
+        let delete_old_image = match new_image {
+            Some(new_image_path_str) => new_image_path_str != old_image_relative_path || new_image_path_str.is_empty(),
+            None => !old_image_relative_path.trim().is_empty(),
+        };
+        if !old_image_relative_path.is_empty() && delete_old_image {
+            if let Err(e) = attachments::remove_stored_file(&ctx.local_data_dir, old_image_relative_path) {
+                eprintln!("[update_entry] Failed to delete old image file {}: {}", old_image_relative_path, e);
+            }
+        }
+    }
+    clear_draft(ctx, date)?;
+    prune_revisions(ctx, Some(date))?;
+    Ok(attachments::sync_legacy_image(&ctx.db, &ctx.local_data_dir, date, previous_image.as_deref(), new_image)?)
+}
+
+// applies the max_revisions_per_entry setting; returns the number of revisions removed
+pub fn prune_revisions(ctx: &AppContext, date: Option<&str>) -> Result<usize> {
+    match ctx.current_settings().revisions_to_keep() {
+        Some(keep) => ctx.db.prune_entry_revisions(date, keep),
+        None => Ok(0),
+    }
+}
+
+pub fn list_entry_revisions(ctx: &AppContext, date: &str) -> Result<Vec<EntryRevision>> {
+    ensure_unlocked(ctx)?;
+    ctx.db.get_entry_revisions(date)
+}
+
+pub fn diff_entry_revisions(ctx: &AppContext, date: &str, from_revision_id: i64, to_revision_id: Option<i64>) -> Result<RevisionDiff> {
+    ensure_unlocked(ctx)?;
+    Ok(revisions::diff_revisions(&ctx.db, date, from_revision_id, to_revision_id)?)
+}
+
+pub fn restore_entry_revision(ctx: &AppContext, date: &str, revision_id: i64) -> Result<()> {
+    ensure_unlocked(ctx)?;
+    revisions::restore_revision(&ctx.db, date, revision_id)?;
+    prune_revisions(ctx, Some(date))?;
+    Ok(())
+}
+
+// moves the entry to the trash; its files stay until it is purged
+pub fn delete_entry(ctx: &AppContext, date: &str) -> Result<()> {
+    ensure_unlocked(ctx)?;
+    if !ctx.db.trash_entry(date)? {
+        return Err(AppError::NotFound(format!("Entry with this date does not exist: {}", date)));
+    }
+    Ok(())
+}
+
+pub fn list_tags(ctx: &AppContext) -> Result<Vec<Tag>> {
+    ensure_unlocked(ctx)?;
+    ctx.db.get_tags()
+}
+
+// a password protected entry only has the tags added to it by hand, see db::sync_hashtag_tags
+pub fn get_entry_tags(ctx: &AppContext, date: &str) -> Result<Vec<String>> {
+    ensure_unlocked(ctx)?;
+    ctx.db.get_entry_tags(date)
+}
+
+// the tag functions return the entry's tags afterwards
+pub fn add_entry_tag(ctx: &AppContext, date: &str, name: &str) -> Result<Vec<String>> {
+    ensure_unlocked(ctx)?;
+    Ok(tags::add_tag(&ctx.db, date, name)?)
+}
+
+pub fn remove_entry_tag(ctx: &AppContext, date: &str, name: &str) -> Result<Vec<String>> {
+    ensure_unlocked(ctx)?;
+    Ok(tags::remove_tag(&ctx.db, date, name)?)
+}
+
+pub fn rename_tag(ctx: &AppContext, name: &str, new_name: &str) -> Result<String> {
+    ensure_unlocked(ctx)?;
+    Ok(tags::rename_tag(&ctx.db, name, new_name)?)
+}
+
+pub fn merge_tags(ctx: &AppContext, sources: &[String], target: &str) -> Result<TagMergeReport> {
+    ensure_unlocked(ctx)?;
+    Ok(tags::merge_tags(&ctx.db, sources, target)?)
+}
+
+pub fn delete_tag(ctx: &AppContext, name: &str) -> Result<()> {
+    ensure_unlocked(ctx)?;
+    Ok(tags::delete_tag(&ctx.db, name)?)
+}
+
+pub fn tag_emotion_stats(ctx: &AppContext) -> Result<Vec<TagEmotionStats>> {
+    ensure_unlocked(ctx)?;
+    Ok(tags::tag_emotion_stats(&ctx.db)?)
+}
+
+pub fn record_checkin(ctx: &AppContext, input: &CheckinInput) -> Result<Checkin> {
+    ensure_unlocked(ctx)?;
+    Ok(checkins::record_checkin(&ctx.db, input, Local::now())?)
+}
+
+pub fn list_checkins(ctx: &AppContext, query: &CheckinQuery) -> Result<Vec<Checkin>> {
+    ensure_unlocked(ctx)?;
+    Ok(checkins::list_checkins(&ctx.db, query)?)
+}
+
+pub fn delete_checkin(ctx: &AppContext, id: i64) -> Result<()> {
+    ensure_unlocked(ctx)?;
+    Ok(checkins::delete_checkin(&ctx.db, id)?)
+}
+
+// entries without a saved emotion and notes are classified when the emotion model is loaded
+pub fn compare_checkins(ctx: &AppContext, query: &CheckinQuery) -> Result<MoodComparisonReport> {
+    ensure_unlocked(ctx)?;
+    let emotion_model = ctx.emotion_model().ok();
+    let classify = emotion_model.map(|model| move |text: &str| model.0.classify(text));
+    let classifier = classify.as_ref().map(|c| c as &EmotionClassifier);
+    Ok(checkins::compare_with_predictions(&ctx.db, query, classifier)?)
+}
+
+// debounced autosave: a save waits for SAVE_DEBOUNCE and is dropped when a newer one for the slot
+// comes in. Returns whether this save was the one written. Blank content discards the draft.
+pub async fn save_draft(ctx: &AppContext, slot: &str, title: Option<&str>, content: &str) -> Result<bool> {
+    ensure_unlocked(ctx)?;
+    drafts::validate_slot(slot)?;
+    if content.trim().is_empty() {
+        discard_draft(ctx, slot)?;
+        return Ok(false);
+    }
+    let (generation, overdue) = ctx.drafts.begin_save(slot);
+    if !overdue {
+        tokio::time::sleep(drafts::SAVE_DEBOUNCE).await;
+        if !ctx.drafts.is_current(slot, generation) {
+            return Ok(false);
+        }
+    }
+    ctx.db.save_draft(slot, title.filter(|t| !t.trim().is_empty()), content)?;
+    ctx.drafts.mark_written(slot);
+    Ok(true)
+}
+
+pub fn load_draft(ctx: &AppContext, slot: &str) -> Result<Option<Draft>> {
+    ensure_unlocked(ctx)?;
+    drafts::validate_slot(slot)?;
+    ctx.db.get_draft(slot)
+}
+
+pub fn list_drafts(ctx: &AppContext) -> Result<Vec<Draft>> {
+    ensure_unlocked(ctx)?;
+    ctx.db.get_drafts()
+}
+
+// drafts written before this launch, which were neither saved nor discarded
+pub fn list_stale_drafts(ctx: &AppContext) -> Result<Vec<Draft>> {
+    ensure_unlocked(ctx)?;
+    Ok(ctx.db.get_drafts()?.into_iter().filter(|draft| drafts::is_stale(draft, ctx.drafts.started_at)).collect())
+}
+
+pub fn discard_draft(ctx: &AppContext, slot: &str) -> Result<()> {
+    ensure_unlocked(ctx)?;
+    drafts::validate_slot(slot)?;
+    clear_draft(ctx, slot)
+}
+
+fn clear_draft(ctx: &AppContext, slot: &str) -> Result<()> {
+    ctx.drafts.invalidate(slot);
+    ctx.db.delete_draft(slot)?;
+    Ok(())
+}
+
+// saves the draft into its entry without running the emotion model; returns the entry date
+pub fn promote_draft(ctx: &AppContext, slot: &str) -> Result<String> {
+    ensure_unlocked(ctx)?;
+    ctx.drafts.invalidate(slot);
+    let today = Local::now().format("%Y-%m-%d").to_string();
+    let date = drafts::promote_draft(&ctx.db, slot, &today)?;
+    prune_revisions(ctx, Some(&date))?;
+    Ok(date)
+}
+
+pub fn list_trash(ctx: &AppContext) -> Result<Trash> {
+    ensure_unlocked(ctx)?;
+    Ok(trash::list_trash(&ctx.db)?)
+}
+
+pub fn restore_entry(ctx: &AppContext, date: &str) -> Result<()> {
+    ensure_unlocked(ctx)?;
+    if !ctx.db.restore_trashed_entry(date)? {
+        return Err(AppError::NotFound(format!("There is no entry for {} in the trash.", date)));
+    }
+    Ok(())
+}
+
+// deletes the trashed entry for good, with its image and attachment files; returns the files removed
+pub fn purge_entry(ctx: &AppContext, date: &str) -> Result<Vec<String>> {
+    ensure_unlocked(ctx)?;
+    Ok(trash::purge_entry(&ctx.db, &ctx.local_data_dir, date)?)
+}
+
+pub fn restore_chat_session(ctx: &AppContext, session_id: &str) -> Result<()> {
+    ensure_unlocked(ctx)?;
+    if !ctx.db.restore_trashed_chat_session(session_id)? {
+        return Err(AppError::NotFound(format!("There is no chat session {} in the trash.", session_id)));
+    }
+    Ok(())
+}
+
+pub fn purge_chat_session(ctx: &AppContext, session_id: &str) -> Result<()> {
+    ensure_unlocked(ctx)?;
+    Ok(trash::purge_chat_session(&ctx.db, session_id)?)
+}
+
+pub fn empty_trash(ctx: &AppContext) -> Result<TrashPurgeReport> {
+    ensure_unlocked(ctx)?;
+    Ok(trash::empty_trash(&ctx.db, &ctx.local_data_dir)?)
+}
+
+// applies the trash_retention_days setting; run on launch
+pub fn purge_expired_trash(ctx: &AppContext) -> Result<TrashPurgeReport> {
+    match ctx.current_settings().trash_retention_days {
+        0 => Ok(TrashPurgeReport::default()),
+        days => Ok(trash::purge_expired(&ctx.db, &ctx.local_data_dir, days, Utc::now())?),
+    }
+}
+
+pub fn list_attachments(ctx: &AppContext, date: &str) -> Result<Vec<Attachment>> {
+    ensure_unlocked(ctx)?;
+    ctx.db.get_attachments_for_entry(date)
+}
+
+// images are resized and thumbnailed like uploads, anything else is stored as is
+pub fn add_attachment(ctx: &AppContext, date: &str, file_bytes: &[u8], original_file_name: &str) -> Result<Attachment> {
+    ensure_unlocked(ctx)?;
+    let current_settings = ctx.current_settings();
+    images::check_upload_size(file_bytes.len(), current_settings.max_upload_bytes)?;
+
+    let relative_path = if images::sniff_image_format(file_bytes).is_some() {
+        images::store_processed_image(&ctx.local_data_dir, file_bytes, &current_settings)?
+    } else {
+        attachments::store_file(&ctx.local_data_dir, file_bytes, original_file_name, "bin")?
+    };
+
+    let new_attachment = attachments::describe_file(&ctx.local_data_dir, &relative_path)?;
+    match ctx.db.add_attachment(date, &new_attachment) {
+        Ok(attachment) => Ok(attachment),
+        Err(e) => {
+            let _ = attachments::remove_stored_file(&ctx.local_data_dir, &relative_path);
+            Err(e)
+        }
+    }
+}
+
+pub fn remove_attachment(ctx: &AppContext, attachment_id: i64) -> Result<()> {
+    ensure_unlocked(ctx)?;
+    if let Some(attachment) = ctx.db.delete_attachment(attachment_id)? {
+        attachments::remove_stored_file(&ctx.local_data_dir, &attachment.relative_path)?;
+    }
+    Ok(())
+}
+
+pub fn reorder_attachments(ctx: &AppContext, date: &str, ordered_ids: &[i64]) -> Result<()> {
+    ensure_unlocked(ctx)?;
+    ctx.db.reorder_attachments(date, ordered_ids)
+}
+
+pub fn upload_image(ctx: &AppContext, image_bytes: &[u8], original_file_name: &str) -> Result<String> {
+    ensure_unlocked(ctx)?;
+    let current_settings = ctx.current_settings();
+    images::check_upload_size(image_bytes.len(), current_settings.max_upload_bytes)?;
+    let relative_path = images::store_processed_image(&ctx.local_data_dir, image_bytes, &current_settings)?;
+    log::info!("Image '{}' saved to: {:?}", original_file_name, ctx.local_data_dir.join(&relative_path));
+    Ok(relative_path)
+}
+
+// generates the thumbnail on first use for images uploaded before thumbnails existed
+pub fn get_image_thumbnail(ctx: &AppContext, relative_path: &str) -> Result<String> {
+    ensure_unlocked(ctx)?;
+    let image_path = attachments::stored_file_path(&ctx.local_data_dir, relative_path)?;
+    let thumbnail_path = attachments::thumbnail_relative_path(relative_path);
+    if ctx.local_data_dir.join(&thumbnail_path).exists() {
+        return Ok(thumbnail_path);
+    }
+
+    let image_bytes = fs::read(image_path)
+        .with_context(|| format!("Failed to read image {}", relative_path))?;
+    let thumbnail_bytes = images::thumbnail_from_bytes(&image_bytes, ctx.current_settings().thumbnail_size)?;
+    Ok(attachments::store_thumbnail(&ctx.local_data_dir, relative_path, &thumbnail_bytes)?)
+}
+
+pub fn reconcile_images(ctx: &AppContext, apply: bool) -> Result<ImageGcReport> {
+    ensure_unlocked(ctx)?;
+    Ok(maintenance::reconcile_images(&ctx.db, &ctx.local_data_dir, apply)?)
+}
+
+pub fn check_database(ctx: &AppContext, repair: bool) -> Result<IntegrityReport> {
+    ensure_unlocked(ctx)?;
+    Ok(maintenance::check_database(&ctx.db, repair)?)
+}
+
+pub fn export_backup(ctx: &AppContext, archive_path: &Path, passphrase: Option<&str>) -> Result<BackupManifest> {
+    ensure_unlocked(ctx)?;
+    Ok(backup::export_backup(&ctx.backup_paths(), archive_path, passphrase)?)
+}
+
+pub fn read_backup_manifest(archive_path: &Path, passphrase: Option<&str>) -> Result<BackupManifest> {
+    Ok(backup::read_backup_manifest(archive_path, passphrase)?)
+}
+
+// the restored settings.json takes effect immediately
+pub fn import_backup(ctx: &AppContext, archive_path: &Path, passphrase: Option<&str>) -> Result<BackupManifest> {
+    ensure_unlocked(ctx)?;
+    let manifest = backup::import_backup(&ctx.backup_paths(), archive_path, passphrase)?;
+    ctx.reload_settings();
+    Ok(manifest)
+}
+
+pub fn export_journal(ctx: &AppContext, format: ExportFormat, output_path: &Path, options: &ExportOptions) -> Result<ExportReport> {
+    ensure_unlocked(ctx)?;
+    Ok(export::export_journal(&ctx.db, &ctx.local_data_dir, format, output_path, options)?)
+}
+
+// imported entries are classified when the emotion model is loaded
+pub fn import_journal(ctx: &AppContext, source: ImportSource, path: &Path, options: &ImportOptions) -> Result<ImportReport> {
+    ensure_unlocked(ctx)?;
+    let emotion_model = ctx.emotion_model().ok();
+    let classify = emotion_model.map(|model| move |text: &str| model.0.classify(text));
+    let classifier = classify.as_ref().map(|c| c as &EmotionClassifier);
+    Ok(importers::import_journal(&ctx.db, &ctx.local_data_dir, &ctx.current_settings(), source, path, options, classifier)?)
+}
+
+pub fn read_dataset(ctx: &AppContext) -> Result<Dataset> {
+    ensure_unlocked(ctx)?;
+    Ok(dataset::export_dataset(&ctx.db)?)
+}
+
+pub fn export_dataset(ctx: &AppContext, output_path: &Path, format: DatasetFileFormat) -> Result<Dataset> {
+    ensure_unlocked(ctx)?;
+    Ok(dataset::write_dataset(&ctx.db, output_path, format)?)
+}
+
+pub fn import_dataset(ctx: &AppContext, input_path: &Path, options: &DatasetImportOptions) -> Result<DatasetImportReport> {
+    ensure_unlocked(ctx)?;
+    Ok(dataset::import_dataset_file(&ctx.db, input_path, DatasetFileFormat::from_path(input_path), options)?)
+}
+
+fn snapshot_directory(ctx: &AppContext) -> Result<PathBuf> {
+    ctx.current_settings().auto_backup.directory
+        .filter(|d| !d.trim().is_empty())
+        .map(PathBuf::from)
+        .ok_or_else(|| AppError::NotConfigured("Choose a backup directory in settings first.".to_string()))
+}
+
+pub fn list_snapshots(ctx: &AppContext) -> Result<Vec<SnapshotInfo>> {
+    ensure_unlocked(ctx)?;
+    Ok(snapshots::list_snapshots(&snapshot_directory(ctx)?)?)
+}
+
+// takes a snapshot immediately, regardless of the schedule, and applies retention
+pub fn create_snapshot(ctx: &AppContext) -> Result<SnapshotInfo> {
+    ensure_unlocked(ctx)?;
+    let directory = snapshot_directory(ctx)?;
+    let auto_backup = ctx.current_settings().auto_backup;
+    let snapshot = snapshots::create_snapshot(&ctx.backup_paths(), &directory, Local::now().naive_local())?;
+    snapshots::prune_snapshots(&directory, auto_backup.keep_last, auto_backup.keep_monthly)?;
+    Ok(snapshot)
+}
+
+pub fn restore_snapshot(ctx: &AppContext, file_name: &str) -> Result<BackupManifest> {
+    ensure_unlocked(ctx)?;
+    let directory = snapshot_directory(ctx)?;
+    let manifest = snapshots::restore_snapshot(&ctx.backup_paths(), &directory, file_name)?;
+    ctx.reload_settings();
+    Ok(manifest)
+}
+
+// one pass of the backup schedule; returns None when no snapshot was due
+pub fn run_scheduled_backup(ctx: &AppContext) -> Result<Option<SnapshotInfo>> {
+    let auto_backup = ctx.current_settings().auto_backup;
+    if auto_backup.frequency == snapshots::BackupFrequency::Off {
+        return Ok(None);
+    }
+    Ok(snapshots::run_scheduled_backup(&ctx.backup_paths(), &auto_backup, Local::now().naive_local())?)
+}
+
+pub fn classify_emotion(ctx: &AppContext, text: &str) -> Result<String> {
+    Ok(ctx.emotion_model()?.0.classify(text).context("Failed to classify emotion")?)
+}
+
+pub fn transcribe(ctx: &AppContext, audio_file_path: &str, options: &DictationOptions) -> Result<String> {
+    Ok(ctx.dictation_model()?.0.transcribe(audio_file_path, options)?)
+}
+
+pub fn transcribe_with_report(ctx: &AppContext, audio_file_path: &str, options: &DictationOptions) -> Result<DictationReport> {
+    Ok(ctx.dictation_model()?.0.transcribe_with_report(audio_file_path, options)?)
+}
+
+pub async fn generate_suggestion(entry_title: Option<&str>, entry_content: Option<&str>) -> Result<String> {
+    Ok(suggestion::generate_entry_suggestion(entry_title, entry_content).await?)
+}
+
+pub async fn chat(ctx: &AppContext, user_message: &str, session_id: Option<String>) -> Result<ChatCompletionResponse> {
+    ensure_unlocked(ctx)?;
+    Ok(suggestion::chat_with_moodjourney(&ctx.db, user_message, session_id).await?)
+}
+
+pub fn list_chat_sessions(ctx: &AppContext) -> Result<Vec<ChatSession>> {
+    ensure_unlocked(ctx)?;
+    ctx.db.get_all_chat_sessions()
+}
+
+pub fn list_chat_messages(ctx: &AppContext, session_id: &str) -> Result<Vec<ChatMessage>> {
+    ensure_unlocked(ctx)?;
+    ctx.db.get_messages_for_session(session_id)
+}
+
+// moves the session to the trash
+pub fn delete_chat_session(ctx: &AppContext, session_id: &str) -> Result<()> {
+    ensure_unlocked(ctx)?;
+    if !ctx.db.trash_chat_session(session_id)? {
+        return Err(AppError::NotFound(format!("No chat session {}.", session_id)));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Database;
+
+    fn test_context(test_name: &str) -> AppContext {
+        let mut dir = std::env::temp_dir();
+        dir.push("moodjourney_services_tests");
+        dir.push(test_name);
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).expect("Failed to create temporary test directory for services");
+        let db = Database::open(&dir.join("entries.db")).expect("Failed to initialize test database");
+        AppContext::new(db, dir.join("data"), dir.join("config"))
+    }
+
+    #[test]
+    fn test_entry_lifecycle_through_services() {
+        let ctx = test_context("entry_lifecycle");
+        let today = Local::now().format("%Y-%m-%d").to_string();
+
+        create_entry(&ctx, "Morning", Some("Slept well."), None, None).expect("Failed to create entry");
+        update_entry(&ctx, &today, "Evening", Some("Long day."), None, None).expect("Failed to update entry");
+        let entry = get_entry(&ctx, &today).expect("Failed to read entry").expect("Entry should exist.");
+        assert_eq!(entry.title.as_deref(), Some("Evening"));
+        assert_eq!(entry.content.as_deref(), Some("Long day."));
+
+        delete_entry(&ctx, &today).expect("Failed to delete entry");
+        assert!(read_entries(&ctx).expect("Failed to list entries").is_empty(), "Entry should be gone after delete.");
+        assert_eq!(list_trash(&ctx).expect("Failed to list the trash").entries.len(), 1);
+
+        restore_entry(&ctx, &today).expect("Failed to restore entry");
+        assert_eq!(read_entries(&ctx).expect("Failed to list entries").len(), 1, "A restored entry should be listed again.");
+
+        delete_entry(&ctx, &today).expect("Failed to delete entry");
+        purge_entry(&ctx, &today).expect("Failed to purge entry");
+        assert!(list_trash(&ctx).expect("Failed to list the trash").entries.is_empty());
+        assert_eq!(restore_entry(&ctx, &today).expect_err("A purged entry cannot be restored.").code(), "NOT_FOUND");
+    }
+
+    #[test]
+    fn test_create_entry_keeps_a_trashed_entry_for_the_date() {
+        let ctx = test_context("create_over_trash");
+        let today = Local::now().format("%Y-%m-%d").to_string();
+        create_entry(&ctx, "Morning", Some("First try."), None, None).expect("Failed to create entry");
+        delete_entry(&ctx, &today).expect("Failed to delete entry");
+
+        let err = create_entry(&ctx, "Evening", Some("Second try."), None, None).expect_err("A trashed entry should still hold its date.");
+        assert_eq!(err.code(), "DUPLICATE");
+        assert_eq!(list_trash(&ctx).expect("Failed to list the trash").entries.len(), 1, "The trashed entry should be kept.");
+
+        restore_entry(&ctx, &today).expect("Failed to restore entry");
+        let entry = get_entry(&ctx, &today).expect("Failed to read entry").expect("Entry should exist.");
+        assert_eq!(entry.content.as_deref(), Some("First try."));
+    }
+
+    #[test]
+    fn test_create_entry_links_the_image_to_the_saved_date() {
+        let ctx = test_context("create_with_image");
+        fs::create_dir_all(ctx.local_data_dir.join("journal_images")).expect("Failed to create image directory");
+        fs::write(ctx.local_data_dir.join("journal_images/cover.png"), b"png").expect("Failed to write image");
+
+        create_entry(&ctx, "Morning", Some("Sunny."), None, Some("journal_images/cover.png")).expect("Failed to create entry");
+        let date = read_entries(&ctx).expect("Failed to list entries")[0].date.clone();
+        let attachments = list_attachments(&ctx, &date).expect("Failed to list attachments");
+        assert_eq!(attachments.len(), 1, "The image should be attached to the date the entry was saved under.");
+        assert_eq!(attachments[0].relative_path, "journal_images/cover.png");
+    }
+
+    #[test]
+    fn test_update_entry_keeps_the_image_unless_the_update_is_saved() {
+        let ctx = test_context("update_image");
+        let today = Local::now().format("%Y-%m-%d").to_string();
+        fs::create_dir_all(ctx.local_data_dir.join("journal_images")).expect("Failed to create image directory");
+        fs::write(ctx.local_data_dir.join("journal_images/cover.png"), b"png").expect("Failed to write image");
+        create_entry(&ctx, "Morning", Some("Sunny."), None, Some("journal_images/cover.png")).expect("Failed to create entry");
+
+        delete_entry(&ctx, &today).expect("Failed to delete entry");
+        let err = update_entry(&ctx, &today, "Morning", Some("Rainy."), None, None).expect_err("A trashed entry cannot be updated.");
+        assert_eq!(err.code(), "NOT_FOUND");
+        assert!(ctx.local_data_dir.join("journal_images/cover.png").exists(), "A failed update should keep the image file.");
+
+        restore_entry(&ctx, &today).expect("Failed to restore entry");
+        update_entry(&ctx, &today, "Morning", Some("Rainy."), None, None).expect("Failed to update entry");
+        assert!(!ctx.local_data_dir.join("journal_images/cover.png").exists(), "A removed image should be deleted once the update is saved.");
+        assert!(list_attachments(&ctx, &today).expect("Failed to list attachments").is_empty());
+    }
+
+    #[test]
+    fn test_thumbnails_are_only_made_for_stored_images() {
+        let ctx = test_context("thumbnail_paths");
+        for path in ["../entries.db", "/etc/passwd", "journal_images/../../entries.db"] {
+            let err = get_image_thumbnail(&ctx, path).expect_err("A path outside the images directory should be rejected.");
+            assert_eq!(err.code(), "INVALID_INPUT");
+        }
+    }
+
+    #[test]
+    fn test_create_entry_succeeds_when_the_image_cannot_be_attached() {
+        let ctx = test_context("create_with_missing_image");
+        create_entry(&ctx, "Morning", Some("Sunny."), None, Some("journal_images/missing.png"))
+            .expect("A saved entry should not be reported as failed.");
+        assert_eq!(read_entries(&ctx).expect("Failed to list entries").len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_draft_saves_are_debounced_and_cleared_on_save() {
+        let ctx = test_context("drafts");
+        assert!(save_draft(&ctx, drafts::NEW_ENTRY_SLOT, None, "Dear").await.expect("Failed to save draft"), "The first save should be written.");
+
+        let (first, second) = tokio::join!(
+            save_draft(&ctx, drafts::NEW_ENTRY_SLOT, None, "Dear diary"),
+            save_draft(&ctx, drafts::NEW_ENTRY_SLOT, None, "Dear diary, today"),
+        );
+        assert!(!first.expect("Failed to save draft"), "A save followed by a newer one should be dropped.");
+        assert!(second.expect("Failed to save draft"));
+        let draft = load_draft(&ctx, drafts::NEW_ENTRY_SLOT).expect("Failed to load draft").expect("Draft should exist.");
+        assert_eq!(draft.content, "Dear diary, today");
+        assert!(list_stale_drafts(&ctx).expect("Failed to list drafts").is_empty(), "Drafts from this run are not stale.");
+
+        create_entry(&ctx, "Morning", Some("Dear diary, today"), None, None).expect("Failed to create entry");
+        assert!(load_draft(&ctx, drafts::NEW_ENTRY_SLOT).expect("Failed to load draft").is_none(), "Saving the entry should clear its draft.");
+    }
+
+    #[test]
+    fn test_missing_models_are_reported() {
+        let ctx = test_context("missing_models");
+        let error = classify_emotion(&ctx, "A calm day.").expect_err("Classifying without a model should fail.");
+        assert_eq!(error.code(), "MODEL_UNAVAILABLE", "Unexpected error: {}", error);
+        let error = transcribe(&ctx, "missing.wav", &DictationOptions::default()).expect_err("Transcribing without a model should fail.");
+        assert_eq!(error.code(), "MODEL_UNAVAILABLE", "Unexpected error: {}", error);
+    }
+
+    #[test]
+    fn test_locked_journal_hides_entries() {
+        let ctx = test_context("locked_journal");
+        create_entry(&ctx, "Private", Some("Only after the PIN."), None, None).expect("Failed to create entry");
+        set_new_password(&ctx, "2468");
+        assert!(is_locked(&ctx), "Setting a PIN locks the app.");
+
+        let error = read_entries(&ctx).expect_err("Entries should not be listed while locked.");
+        assert_eq!(error, AppError::Locked("The journal is locked. Enter the PIN to unlock it.".to_string()));
+        assert!(check_password_attempt(&ctx, "2468"));
+        assert_eq!(read_entries(&ctx).expect("Failed to list entries").len(), 1);
+    }
+
+    #[test]
+    fn test_locked_journal_rejects_changes_and_chat_history() {
+        let ctx = test_context("locked_writes");
+        let today = Local::now().format("%Y-%m-%d").to_string();
+        create_entry(&ctx, "Private", Some("Only after the PIN."), None, None).expect("Failed to create entry");
+        set_new_password(&ctx, "2468");
+
+        let results = [
+            update_entry(&ctx, &today, "Changed", Some("Changed"), None, None),
+            delete_entry(&ctx, &today),
+            add_entry_tag(&ctx, &today, "work").map(|_| ()),
+            purge_entry(&ctx, &today).map(|_| ()),
+            empty_trash(&ctx).map(|_| ()),
+            list_attachments(&ctx, &today).map(|_| ()),
+            record_checkin(&ctx, &CheckinInput { mood: 3, ..CheckinInput::default() }).map(|_| ()),
+            list_chat_sessions(&ctx).map(|_| ()),
+            list_chat_messages(&ctx, "session").map(|_| ()),
+        ];
+        for result in results {
+            let error = result.expect_err("A locked journal should not be changed or read.");
+            assert_eq!(error.code(), "LOCKED", "Unexpected error: {}", error);
+        }
+
+        // the launch tasks still run while locked
+        purge_expired_trash(&ctx).expect("Trash retention should run while locked.");
+        prune_revisions(&ctx, None).expect("Revision pruning should run while locked.");
+
+        assert!(check_password_attempt(&ctx, "2468"));
+        let entry = get_entry(&ctx, &today).expect("Failed to read entry").expect("The entry should still exist.");
+        assert_eq!(entry.title.as_deref(), Some("Private"));
+    }
+
+    #[test]
+    fn test_snapshots_require_a_directory() {
+        let ctx = test_context("snapshot_directory");
+        let error = create_snapshot(&ctx).expect_err("Snapshots need a configured directory.");
+        assert_eq!(error.code(), "NOT_CONFIGURED", "Unexpected error: {}", error);
+        assert!(run_scheduled_backup(&ctx).expect("Scheduled backups are off by default.").is_none());
+    }
+}
@@ -0,0 +1,127 @@
+use crate::snapshots::AutoBackupSettings;
+use serde::{Serialize, Deserialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AppSettings {
+    pub max_upload_bytes: u64,
+    pub max_image_dimension: u32,
+    pub thumbnail_size: u32,
+    // delete orphaned images and drop dangling references on launch
+    pub image_gc_on_startup: bool,
+    // earlier versions kept per entry; 0 keeps every revision
+    pub max_revisions_per_entry: u32,
+    // days deleted entries and chat sessions stay in the trash; 0 keeps them until purged by hand
+    pub trash_retention_days: u32,
+    pub auto_backup: AutoBackupSettings,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            max_upload_bytes: 20 * 1024 * 1024,
+            max_image_dimension: 2560,
+            thumbnail_size: 320,
+            image_gc_on_startup: false,
+            max_revisions_per_entry: 50,
+            trash_retention_days: 30,
+            auto_backup: AutoBackupSettings::default(),
+        }
+    }
+}
+
+impl AppSettings {
+    // None when every revision is kept
+    pub fn revisions_to_keep(&self) -> Option<usize> {
+        (self.max_revisions_per_entry > 0).then_some(self.max_revisions_per_entry as usize)
+    }
+}
+
+#[derive(Debug)]
+pub struct SettingsState {
+    pub path: PathBuf,
+    pub settings: AppSettings,
+}
+
+impl SettingsState {
+    pub fn load_from_path(specific_path: PathBuf) -> Self {
+        if let Some(parent_dir) = specific_path.parent() {
+            let _ = fs::create_dir_all(parent_dir);
+        }
+
+        let settings = fs::read_to_string(&specific_path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+
+        SettingsState {
+            path: specific_path,
+            settings,
+        }
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let data = serde_json::to_string_pretty(&self.settings)
+            .map_err(|e| format!("Error serializing settings: {}", e))?;
+        fs::write(&self.path, data)
+            .map_err(|e| format!("Error writing settings file {:?}: {}", self.path, e))
+    }
+}
+
+pub fn get_settings(state_mutex: &Mutex<SettingsState>) -> AppSettings {
+    let state_guard = state_mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    state_guard.settings.clone()
+}
+
+pub fn update_settings(state_mutex: &Mutex<SettingsState>, new_settings: AppSettings) -> Result<AppSettings, String> {
+    let mut state_guard = state_mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    state_guard.settings = new_settings;
+    state_guard.save()?;
+    Ok(state_guard.settings.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_test_settings_file_path(test_name: &str) -> PathBuf {
+        let mut path = std::env::temp_dir();
+        path.push("moodjourney_settings_tests");
+        fs::create_dir_all(&path).expect("Failed to create temporary test directory for settings");
+        path.push(format!("settings_for_{}.json", test_name));
+        if path.exists() {
+            let _ = fs::remove_file(&path);
+        }
+        path
+    }
+
+    #[test]
+    fn test_missing_file_loads_defaults() {
+        let path = get_test_settings_file_path("missing_file");
+        let state = SettingsState::load_from_path(path);
+        assert_eq!(state.settings, AppSettings::default());
+    }
+
+    #[test]
+    fn test_update_persists_and_partial_file_keeps_defaults() {
+        let path = get_test_settings_file_path("update_persists");
+        let state_mutex = Mutex::new(SettingsState::load_from_path(path.clone()));
+
+        let updated = update_settings(&state_mutex, AppSettings { max_upload_bytes: 1024, ..AppSettings::default() })
+            .expect("Failed to update settings");
+        assert_eq!(updated.max_upload_bytes, 1024);
+
+        let reloaded = SettingsState::load_from_path(path.clone());
+        assert_eq!(reloaded.settings.max_upload_bytes, 1024);
+
+        fs::write(&path, r#"{"thumbnailSize": 128}"#).expect("Failed to write partial settings");
+        let partial = SettingsState::load_from_path(path.clone());
+        assert_eq!(partial.settings.thumbnail_size, 128);
+        assert_eq!(partial.settings.max_upload_bytes, AppSettings::default().max_upload_bytes);
+
+        let _ = fs::remove_file(path);
+    }
+}
@@ -0,0 +1,297 @@
+use crate::backup::{self, BackupManifest, BackupPaths, BACKUP_FILE_EXTENSION};
+use crate::error::AppError;
+use anyhow::{Result, anyhow};
+use chrono::{Datelike, Duration, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const SNAPSHOT_PREFIX: &str = "moodjourney-";
+const SNAPSHOT_TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S";
+const CREATED_AT_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum BackupFrequency {
+    #[default]
+    Off,
+    Daily,
+    Weekly,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AutoBackupSettings {
+    pub frequency: BackupFrequency,
+    pub directory: Option<String>,
+    // newest snapshots that are always kept
+    pub keep_last: u32,
+    // additionally keep the newest snapshot of each of this many recent months
+    pub keep_monthly: u32,
+}
+
+impl Default for AutoBackupSettings {
+    fn default() -> Self {
+        Self {
+            frequency: BackupFrequency::Off,
+            directory: None,
+            keep_last: 7,
+            keep_monthly: 6,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotInfo {
+    pub file_name: String,
+    pub path: PathBuf,
+    // local time the snapshot was taken, as YYYY-MM-DDTHH:MM:SS
+    pub created_at: String,
+    pub size: u64,
+}
+
+impl SnapshotInfo {
+    fn created_at_time(&self) -> Option<NaiveDateTime> {
+        NaiveDateTime::parse_from_str(&self.created_at, CREATED_AT_FORMAT).ok()
+    }
+}
+
+pub fn snapshot_file_name(created_at: NaiveDateTime) -> String {
+    format!("{}{}.{}", SNAPSHOT_PREFIX, created_at.format(SNAPSHOT_TIMESTAMP_FORMAT), BACKUP_FILE_EXTENSION)
+}
+
+fn parse_snapshot_file_name(file_name: &str) -> Option<NaiveDateTime> {
+    let timestamp = file_name
+        .strip_prefix(SNAPSHOT_PREFIX)?
+        .strip_suffix(&format!(".{}", BACKUP_FILE_EXTENSION))?;
+    NaiveDateTime::parse_from_str(timestamp, SNAPSHOT_TIMESTAMP_FORMAT).ok()
+}
+
+// newest first; files that do not follow the snapshot naming scheme are ignored
+pub fn list_snapshots(directory: &Path) -> Result<Vec<SnapshotInfo>> {
+    if !directory.exists() {
+        return Ok(Vec::new());
+    }
+    let mut snapshots = Vec::new();
+    for dir_entry in fs::read_dir(directory).map_err(|e| anyhow!("Failed to read backup directory {:?}: {}", directory, e))? {
+        let dir_entry = dir_entry?;
+        let file_name = dir_entry.file_name().to_string_lossy().into_owned();
+        if let Some(created_at) = parse_snapshot_file_name(&file_name) {
+            snapshots.push(SnapshotInfo {
+                file_name,
+                path: dir_entry.path(),
+                created_at: created_at.format(CREATED_AT_FORMAT).to_string(),
+                size: dir_entry.metadata()?.len(),
+            });
+        }
+    }
+    snapshots.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    Ok(snapshots)
+}
+
+pub fn is_snapshot_due(last_snapshot: Option<NaiveDateTime>, frequency: BackupFrequency, now: NaiveDateTime) -> bool {
+    let interval = match frequency {
+        BackupFrequency::Off => return false,
+        BackupFrequency::Daily => Duration::days(1),
+        BackupFrequency::Weekly => Duration::weeks(1),
+    };
+    match last_snapshot {
+        Some(last) => now - last >= interval,
+        None => true,
+    }
+}
+
+// snapshots must be sorted newest first, as returned by list_snapshots
+pub fn select_snapshots_to_prune(snapshots: &[SnapshotInfo], keep_last: u32, keep_monthly: u32) -> Vec<SnapshotInfo> {
+    let mut keep: HashSet<usize> = (0..snapshots.len().min(keep_last as usize)).collect();
+
+    let mut months_seen: Vec<(i32, u32)> = Vec::new();
+    for (index, snapshot) in snapshots.iter().enumerate() {
+        let created_at = match snapshot.created_at_time() {
+            Some(created_at) => created_at,
+            None => continue,
+        };
+        let month = (created_at.year(), created_at.month());
+        if months_seen.contains(&month) {
+            continue;
+        }
+        if months_seen.len() >= keep_monthly as usize {
+            break;
+        }
+        months_seen.push(month);
+        keep.insert(index);
+    }
+
+    snapshots.iter()
+        .enumerate()
+        .filter(|(index, _)| !keep.contains(index))
+        .map(|(_, snapshot)| snapshot.clone())
+        .collect()
+}
+
+pub fn create_snapshot(paths: &BackupPaths, directory: &Path, now: NaiveDateTime) -> Result<SnapshotInfo> {
+    fs::create_dir_all(directory)
+        .map_err(|e| anyhow!("Failed to create backup directory {:?}: {}", directory, e))?;
+    let archive_path = directory.join(snapshot_file_name(now));
+    backup::export_backup(paths, &archive_path, None)?;
+    Ok(SnapshotInfo {
+        file_name: snapshot_file_name(now),
+        size: fs::metadata(&archive_path)?.len(),
+        path: archive_path,
+        created_at: now.format(CREATED_AT_FORMAT).to_string(),
+    })
+}
+
+pub fn prune_snapshots(directory: &Path, keep_last: u32, keep_monthly: u32) -> Result<Vec<SnapshotInfo>> {
+    let snapshots = list_snapshots(directory)?;
+    let to_prune = select_snapshots_to_prune(&snapshots, keep_last.max(1), keep_monthly);
+    for snapshot in &to_prune {
+        fs::remove_file(&snapshot.path)
+            .map_err(|e| anyhow!("Failed to delete old snapshot {:?}: {}", snapshot.path, e))?;
+    }
+    Ok(to_prune)
+}
+
+// creates a snapshot when one is due and applies retention; returns the new snapshot if any
+pub fn run_scheduled_backup(paths: &BackupPaths, auto_backup: &AutoBackupSettings, now: NaiveDateTime) -> Result<Option<SnapshotInfo>> {
+    let directory = match auto_backup.directory.as_deref().filter(|d| !d.trim().is_empty()) {
+        Some(directory) => PathBuf::from(directory),
+        None => return Ok(None),
+    };
+
+    let last_snapshot = list_snapshots(&directory)?.first().and_then(|s| s.created_at_time());
+    if !is_snapshot_due(last_snapshot, auto_backup.frequency, now) {
+        return Ok(None);
+    }
+
+    let snapshot = create_snapshot(paths, &directory, now)?;
+    let pruned = prune_snapshots(&directory, auto_backup.keep_last, auto_backup.keep_monthly)?;
+    log::info!("[snapshots] Created {} and pruned {} old snapshot(s).", snapshot.file_name, pruned.len());
+    Ok(Some(snapshot))
+}
+
+// only snapshots inside the configured directory can be restored by name
+pub fn restore_snapshot(paths: &BackupPaths, directory: &Path, file_name: &str) -> Result<BackupManifest> {
+    let snapshot = list_snapshots(directory)?
+        .into_iter()
+        .find(|s| s.file_name == file_name)
+        .ok_or_else(|| AppError::NotFound(format!("Snapshot {} does not exist in {:?}.", file_name, directory)))?;
+    backup::import_backup(paths, &snapshot.path, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{Database, Entry};
+    use chrono::NaiveDate;
+
+    fn get_test_dir(test_name: &str) -> PathBuf {
+        let mut path = std::env::temp_dir();
+        path.push("moodjourney_snapshot_tests");
+        path.push(test_name);
+        if path.exists() {
+            let _ = fs::remove_dir_all(&path);
+        }
+        fs::create_dir_all(&path).expect("Failed to create temporary test directory for snapshots");
+        path
+    }
+
+    fn at(year: i32, month: u32, day: u32, hour: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap().and_hms_opt(hour, 0, 0).unwrap()
+    }
+
+    fn fake_snapshot(created_at: NaiveDateTime) -> SnapshotInfo {
+        SnapshotInfo {
+            file_name: snapshot_file_name(created_at),
+            path: PathBuf::from(snapshot_file_name(created_at)),
+            created_at: created_at.format(CREATED_AT_FORMAT).to_string(),
+            size: 0,
+        }
+    }
+
+    #[test]
+    fn test_snapshot_names_round_trip() {
+        let created_at = at(2025, 3, 4, 5);
+        let file_name = snapshot_file_name(created_at);
+        assert_eq!(file_name, "moodjourney-20250304-050000.mjbackup");
+        assert_eq!(parse_snapshot_file_name(&file_name), Some(created_at));
+        assert_eq!(parse_snapshot_file_name("notes.txt"), None);
+    }
+
+    #[test]
+    fn test_is_snapshot_due() {
+        let now = at(2025, 3, 10, 12);
+        assert!(!is_snapshot_due(None, BackupFrequency::Off, now));
+        assert!(is_snapshot_due(None, BackupFrequency::Daily, now));
+        assert!(!is_snapshot_due(Some(at(2025, 3, 10, 1)), BackupFrequency::Daily, now));
+        assert!(is_snapshot_due(Some(at(2025, 3, 9, 12)), BackupFrequency::Daily, now));
+        assert!(!is_snapshot_due(Some(at(2025, 3, 5, 12)), BackupFrequency::Weekly, now));
+        assert!(is_snapshot_due(Some(at(2025, 3, 3, 12)), BackupFrequency::Weekly, now));
+    }
+
+    #[test]
+    fn test_retention_keeps_last_and_monthly() {
+        // newest first: three in March, two in February, one each in January and December
+        let snapshots = vec![
+            fake_snapshot(at(2025, 3, 20, 0)),
+            fake_snapshot(at(2025, 3, 13, 0)),
+            fake_snapshot(at(2025, 3, 6, 0)),
+            fake_snapshot(at(2025, 2, 27, 0)),
+            fake_snapshot(at(2025, 2, 20, 0)),
+            fake_snapshot(at(2025, 1, 30, 0)),
+            fake_snapshot(at(2024, 12, 26, 0)),
+        ];
+        let pruned = select_snapshots_to_prune(&snapshots, 2, 3);
+        let pruned_dates: Vec<NaiveDateTime> = pruned.iter().filter_map(|s| s.created_at_time()).collect();
+        assert_eq!(pruned_dates, vec![at(2025, 3, 6, 0), at(2025, 2, 20, 0), at(2024, 12, 26, 0)]);
+
+        assert!(select_snapshots_to_prune(&snapshots, 10, 0).is_empty());
+    }
+
+    #[test]
+    fn test_scheduled_backup_creates_prunes_and_restores() {
+        let root = get_test_dir("scheduled");
+        let paths = BackupPaths {
+            db_file_path: root.join("data").join("entries.db"),
+            app_local_data_dir: root.join("data"),
+            settings_file_path: None,
+        };
+        fs::create_dir_all(&paths.app_local_data_dir).unwrap();
+        let db = Database::open(&paths.db_file_path).expect("init failed");
+        db.add_entry(Entry {
+            date: "2025-03-01".to_string(),
+            title: Some("Snapshot me".to_string()),
+            content: None,
+            password: None,
+            image: None,
+        }).expect("add failed");
+
+        let backup_dir = root.join("backups");
+        let auto_backup = AutoBackupSettings {
+            frequency: BackupFrequency::Daily,
+            directory: Some(backup_dir.to_string_lossy().into_owned()),
+            keep_last: 2,
+            keep_monthly: 0,
+        };
+
+        for day in 1..=4 {
+            let created = run_scheduled_backup(&paths, &auto_backup, at(2025, 3, day, 9)).expect("backup failed");
+            assert!(created.is_some(), "A snapshot should be due on day {}.", day);
+        }
+        assert!(run_scheduled_backup(&paths, &auto_backup, at(2025, 3, 4, 10)).expect("backup failed").is_none(),
+            "No snapshot should be due an hour after the last one.");
+
+        let snapshots = list_snapshots(&backup_dir).expect("list failed");
+        assert_eq!(snapshots.iter().map(|s| s.created_at.as_str()).collect::<Vec<_>>(), vec!["2025-03-04T09:00:00", "2025-03-03T09:00:00"]);
+        assert!(!fs::read_dir(&backup_dir).unwrap().any(|e| e.unwrap().file_name().to_string_lossy().ends_with(".partial")));
+
+        db.delete_entry_by_date("2025-03-01").expect("delete failed");
+        restore_snapshot(&paths, &backup_dir, &snapshots[0].file_name).expect("restore failed");
+        assert_eq!(db.get_entries().expect("list failed").len(), 1);
+        assert!(restore_snapshot(&paths, &backup_dir, "../entries.db").is_err());
+
+        let _ = fs::remove_dir_all(root);
+    }
+}
@@ -1,7 +1,10 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 use anyhow::{Result, anyhow};
+use chrono::Local;
 use crate::config::GEMINI_API_KEY;
+use crate::db::{ChatMessage, Database, Entry};
+use crate::error::AppError;
 const GEMINI_API_URL_BASE: &str = "https://generativelanguage.googleapis.com/v1beta/models/gemini-2.0-flash:generateContent";
 
 #[derive(Deserialize, Debug)]
@@ -29,13 +32,23 @@ struct GeminiResponse {
     _prompt_feedback: Option<serde_json::Value>,
 }
 
+// 429 means the quota ran out, which the UI reports differently from an outage
+fn api_status_error(status: reqwest::StatusCode, body: &str) -> AppError {
+    let message = format!("Gemini API request failed with status {}: {}", status, body);
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        AppError::RateLimited(message)
+    } else {
+        AppError::Network(message)
+    }
+}
+
 pub async fn generate_suggestion_via_api(prompt: &str) -> Result<String> {
+    if prompt.trim().is_empty() {
+        return Err(AppError::InvalidInput("Prompt cannot be empty.".to_string()).into());
+    }
     if GEMINI_API_KEY == "YOUR_GEMINI_API_KEY_GOES_HERE" {
         log::error!("[API Suggestion] Gemini API key is not configured in src-tauri/src/config.rs. Please follow instructions in src-tauri/src/config_template.rs.");
-        return Err(anyhow!("API_KEY_NOT_CONFIGURED: The Gemini API key is not set in src-tauri/src/config.rs. Please follow instructions in src-tauri/src/config_template.rs."));
-    }
-    if prompt.trim().is_empty() {
-        return Err(anyhow!("Prompt cannot be empty."));
+        return Err(AppError::ApiKeyMissing("The Gemini API key is not set in src-tauri/src/config.rs. Please follow instructions in src-tauri/src/config_template.rs.".to_string()).into());
     }
 
     let client = reqwest::Client::new();
@@ -55,7 +68,7 @@ pub async fn generate_suggestion_via_api(prompt: &str) -> Result<String> {
 
     log::info!("[API Suggestion] Sending prompt (first 100 chars): '{}'", prompt.chars().take(100).collect::<String>());
     log::debug!("[API Suggestion] Full prompt: {}", prompt);
-    log::debug!("[API Suggestion] Request body to Gemini: {}", request_body.to_string());
+    log::debug!("[API Suggestion] Request body to Gemini: {}", request_body);
 
 
     let res = client.post(&api_url)
@@ -65,7 +78,7 @@ pub async fn generate_suggestion_via_api(prompt: &str) -> Result<String> {
         .await
         .map_err(|e| {
             log::error!("[API Suggestion] Failed to send request to Gemini API: {}", e);
-            anyhow!("Network request to Gemini API failed: {}", e)
+            AppError::Network(format!("Network request to Gemini API failed: {}", e))
         })?;
 
     let response_status = res.status();
@@ -73,7 +86,7 @@ pub async fn generate_suggestion_via_api(prompt: &str) -> Result<String> {
 
     if !response_status.is_success() {
         log::error!("[API Suggestion] Gemini API Error ({}): {}", response_status, response_body_text);
-        return Err(anyhow!("Gemini API request failed with status {}: {}", response_status, response_body_text));
+        return Err(api_status_error(response_status, &response_body_text).into());
     }
     
     log::debug!("[API Suggestion] Gemini Raw Response: {}", response_body_text);
@@ -85,8 +98,8 @@ pub async fn generate_suggestion_via_api(prompt: &str) -> Result<String> {
             anyhow!("Failed to parse Gemini API response: {}. Body: {}", e, response_body_text)
         })?;
 
-    if let Some(candidate) = response_data.candidates.get(0) {
-        if let Some(part) = candidate.content.parts.get(0) {
+    if let Some(candidate) = response_data.candidates.first() {
+        if let Some(part) = candidate.content.parts.first() {
             let suggestion_text = part.text.trim().to_string();
             log::info!("[API Suggestion] Received suggestion: {}", suggestion_text);
             if suggestion_text.is_empty() {
@@ -102,12 +115,12 @@ pub async fn generate_suggestion_via_api(prompt: &str) -> Result<String> {
 }
 
 pub async fn generate_chat_response_via_api(api_contents: &Vec<serde_json::Value>) -> Result<String> {
+    if api_contents.is_empty() {
+        return Err(AppError::InvalidInput("Chat contents for API cannot be empty.".to_string()).into());
+    }
     if GEMINI_API_KEY == "YOUR_GEMINI_API_KEY_GOES_HERE" {
         log::error!("[API Chat] Gemini API key is not configured in src-tauri/src/config.rs. Please follow instructions in src-tauri/src/config_template.rs.");
-        return Err(anyhow!("API_KEY_NOT_CONFIGURED: The Gemini API key is not set in src-tauri/src/config.rs. Please follow instructions in src-tauri/src/config_template.rs."));
-    }
-    if api_contents.is_empty() {
-        return Err(anyhow!("Chat contents for API cannot be empty."));
+        return Err(AppError::ApiKeyMissing("The Gemini API key is not set in src-tauri/src/config.rs. Please follow instructions in src-tauri/src/config_template.rs.".to_string()).into());
     }
 
     let client = reqwest::Client::new();
@@ -140,7 +153,7 @@ pub async fn generate_chat_response_via_api(api_contents: &Vec<serde_json::Value
         .await
         .map_err(|e| {
             log::error!("[API Chat] Failed to send request to Gemini API: {}", e);
-            anyhow!("Network request to Gemini API failed: {}", e)
+            AppError::Network(format!("Network request to Gemini API failed: {}", e))
         })?;
 
     let response_status = res.status();
@@ -148,7 +161,7 @@ pub async fn generate_chat_response_via_api(api_contents: &Vec<serde_json::Value
 
     if !response_status.is_success() {
         log::error!("[API Chat] Gemini API Error ({}): {}", response_status, response_body_text);
-        return Err(anyhow!("Gemini API request failed with status {}: {}", response_status, response_body_text));
+        return Err(api_status_error(response_status, &response_body_text).into());
     }
 
     log::debug!("[API Chat] Gemini Raw Response: {}", response_body_text);
@@ -159,8 +172,8 @@ pub async fn generate_chat_response_via_api(api_contents: &Vec<serde_json::Value
             anyhow!("Failed to parse Gemini API response: {}. Body: {}", e, response_body_text)
         })?;
 
-    if let Some(candidate) = response_data.candidates.get(0) {
-        if let Some(part) = candidate.content.parts.get(0) {
+    if let Some(candidate) = response_data.candidates.first() {
+        if let Some(part) = candidate.content.parts.first() {
             let chat_response_text = part.text.trim().to_string();
             log::info!("[API Chat] Received chat response (first 100 chars): {}", chat_response_text.chars().take(100).collect::<String>());
             if chat_response_text.is_empty() {
@@ -174,6 +187,203 @@ pub async fn generate_chat_response_via_api(api_contents: &Vec<serde_json::Value
     Err(anyhow!("No chat response content found in Gemini API response."))
 }
 
+const SUGGESTION_PROMPT_INTRO: &str = r#"
+    You are a helpful journaling assistant called "MoodJourney".
+    Your goal is to provide three concise, actionable, and encouraging suggestions based on the user's journal entry.
+    The suggestions should be things the user could consider doing or thinking about related to their entry.
+
+    Here's an example of how to respond to an entry corresponding to the emotion "sadness":
+    --- Example Start ---
+    Journal Entry: Today was a bit overwhelming. I had a lot of meetings and didn't get as much done on my main project as I hoped. Feeling a little drained now.
+    Suggestions:
+    - Perhaps take 15 minutes for a quick walk or some stretching to clear your head and recharge.
+    - Before tomorrow, could you identify one or two key tasks for your main project to focus on first? This might help you feel more accomplished.
+    - Remember to acknowledge what you *did* manage today, even with many meetings. It's okay for some days to be less project-focused.
+    --- Example End ---
+
+    Here's an example of how to respond to an entry corresponding to the emotion "joy":
+    --- Example Start ---
+    Journal Entry: I had such a wonderful day! Spent the afternoon hiking with friends, and the weather was perfect. We even saw a deer. Feeling so refreshed and grateful.
+    Suggestions:
+    - That sounds amazing! Maybe you could plan another hike soon to keep the good vibes going?
+    - Consider sharing a photo from your hike or telling another friend about your adventure to spread the joy.
+    - Take a moment to jot down one specific thing about the hike that made you feel most refreshed – it's a great memory to hold onto.
+    --- Example End ---
+
+    Here's an example of how to respond to an entry corresponding to the emotion "anger":
+    --- Example Start ---
+    Journal Entry: I'm so furious right now! My coworker completely threw me under the bus in the team meeting, taking credit for my work and blaming me for their mistake. I feel so disrespected and unappreciated.
+    Suggestions:
+    - It's completely understandable to feel furious and disrespected. Allow yourself to feel that anger without judgment for a bit.
+    - When you feel a bit calmer, consider writing down the specific points you want to address with your coworker or manager, focusing on the facts and how it impacted you.
+    - Is there a quick activity that usually helps you release frustration, like listening to loud music, going for a brisk walk (if possible), or scribbling on a piece of paper?
+    --- Example End ---
+
+    Do not output "Suggestions:" before the three suggestions. Simply output the three suggestions themselves.
+    Now, here is the user's actual journal entry:"#;
+
+const MAX_SUGGESTION_CONTENT_CHARS: usize = 700;
+const EMOTION_MARKER: &str = "\n\n🧠 Emotion:";
+const SUGGESTION_MARKER: &str = "\n\n💡 Suggestion:";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ChatCompletionResponse {
+    pub assistant_response: String,
+    pub session_id: String,
+}
+
+pub fn build_suggestion_prompt(entry_title: Option<&str>, entry_content: Option<&str>) -> Result<String> {
+    let title = entry_title.map(str::trim).filter(|t| !t.is_empty());
+    let content = entry_content.map(str::trim).filter(|c| !c.is_empty());
+    if title.is_none() && content.is_none() {
+        return Err(AppError::InvalidInput("Cannot generate suggestion: Journal entry title and content are both empty.".to_string()).into());
+    }
+
+    let mut prompt_parts: Vec<String> = vec![SUGGESTION_PROMPT_INTRO.to_string()];
+    if let Some(title_str) = title {
+        prompt_parts.push(format!("Title: {}", title_str));
+    }
+    match content {
+        Some(content_str) => {
+            let content_to_use = if content_str.chars().count() > MAX_SUGGESTION_CONTENT_CHARS {
+                content_str.chars().take(MAX_SUGGESTION_CONTENT_CHARS).collect::<String>() + "..."
+            }
+            else {
+                content_str.to_string()
+            };
+            prompt_parts.push(format!("Content: {}", content_to_use));
+        }
+        None => prompt_parts.push("Content: (No specific content provided for this entry)".to_string()),
+    }
+    prompt_parts.push("\nSuggestion:".to_string());
+    Ok(prompt_parts.join("\n"))
+}
+
+// the suggestions the journal shows under an entry, as returned by the API
+pub async fn generate_entry_suggestion(entry_title: Option<&str>, entry_content: Option<&str>) -> Result<String> {
+    let final_prompt = build_suggestion_prompt(entry_title, entry_content)?;
+    log::info!("[suggestion] Final prompt for Gemini API (first 200 chars): {}", final_prompt.chars().take(200).collect::<String>());
+
+    match generate_suggestion_via_api(&final_prompt).await {
+        Ok(suggestion) if suggestion.trim().is_empty() => {
+            Err(anyhow!("The AI generated an empty suggestion. Try rephrasing or adding more detail to your entry."))
+        }
+        Ok(suggestion) => Ok(suggestion),
+        Err(e) => {
+            log::error!("[suggestion] Error generating suggestion via API: {}", e);
+            Err(e.context("Failed to generate suggestion"))
+        }
+    }
+}
+
+// entry text handed to the chat as context: the suggestion is dropped, the emotion kept without its emoji
+pub fn main_content_for_context(full_content: &str) -> String {
+    let end_of_relevant_content = full_content.find(SUGGESTION_MARKER)
+        .unwrap_or(full_content.len());
+    full_content[0..end_of_relevant_content]
+        .replace(EMOTION_MARKER, "\n\nEmotion:")
+        .trim()
+        .to_string()
+}
+
+// stores a freshly generated suggestion in place of the entry's previous one, keeping text and emotion
+pub fn replace_suggestion(full_content: &str, suggestion: &str) -> String {
+    let end_of_main = full_content.find(SUGGESTION_MARKER).unwrap_or(full_content.len());
+    format!("{}{} {}", full_content[..end_of_main].trim_end(), SUGGESTION_MARKER, suggestion.trim())
+}
+
+// Gemini contents for a chat session; the first user turn carries the instructions and the journal as context
+pub fn build_chat_contents(messages: &[ChatMessage], journal_entries: &[Entry], current_date_str: &str) -> Vec<serde_json::Value> {
+    let mut api_request_contents: Vec<serde_json::Value> = Vec::new();
+
+    for (index, db_msg) in messages.iter().enumerate() {
+        let mut current_turn_text_for_api = db_msg.content.clone();
+        if index == 0 && db_msg.sender == "user" {
+            let mut system_and_first_user_message_parts: Vec<String> = Vec::new();
+
+            let initial_prompt_block = format!(
+                r#"
+                You are a helpful journaling assistant called "MoodJourney". The user wants to chat with you.
+                The current date is {}.
+                You have access to the user's past journal entries for context. Refer to them when relevant to provide
+                insightful and understanding responses. Be conversational and concise. Do not explicitly state 'Based on your entry
+                from (Month) (Day), (Year)...' unless it feels natural and helpful.
+                The following is the start of our conversation:"#,
+                current_date_str
+            );
+
+            system_and_first_user_message_parts.push(initial_prompt_block);
+
+            if !journal_entries.is_empty() {
+                system_and_first_user_message_parts.push("\n\nHere are the user's past journal entries as general context:\n---\n".to_string());
+                for entry in journal_entries.iter() {
+                    let main_content = main_content_for_context(entry.content.as_deref().unwrap_or_default());
+                    system_and_first_user_message_parts.push(format!("Date: {}\nContent: {}\n---\n",
+                        entry.date,
+                        main_content
+                    ));
+                }
+                system_and_first_user_message_parts.push("End of past journal entries.\n".to_string());
+            }
+
+            system_and_first_user_message_parts.push(format!("\nUser: {}", db_msg.content));
+            current_turn_text_for_api = system_and_first_user_message_parts.join("\n");
+        }
+        api_request_contents.push(json!({
+            "role": if db_msg.sender == "user" { "user" } else { "model" },
+            "parts": [{"text": current_turn_text_for_api}]
+        }));
+    }
+    api_request_contents
+}
+
+// saves the user's message, asks the model with the whole session as history and saves the reply;
+// a new session is created when no id is given
+pub async fn chat_with_moodjourney(db: &Database, user_message: &str, session_id_option: Option<String>) -> Result<ChatCompletionResponse> {
+    let current_session_id = match session_id_option {
+        Some(id) => id,
+        None => db.create_new_chat_session().map_err(|e| {
+            log::error!("Failed to create new chat session: {}", e);
+            e
+        })?,
+    };
+
+    db.save_chat_message(&current_session_id, "user", user_message).map_err(|e| {
+        log::error!("Failed to save user message for session {}: {}", current_session_id, e);
+        e
+    })?;
+
+    let all_messages_for_session_from_db = db.get_messages_for_session(&current_session_id)
+        .map_err(|e| anyhow!("Failed to retrieve messages for session {}: {}", current_session_id, e))?;
+    let all_journal_entries = db.get_entries().unwrap_or_default();
+    let current_date_str = Local::now().format("%A, %B %d, %Y").to_string();
+    let api_request_contents = build_chat_contents(&all_messages_for_session_from_db, &all_journal_entries, &current_date_str);
+
+    let last_content_for_log = api_request_contents.last()
+        .and_then(|c| c.get("parts").and_then(|p| p.get(0)).and_then(|t| t.get("text")).and_then(|s| s.as_str()))
+        .map(|s| s.chars().take(300).collect::<String>())
+        .unwrap_or_else(|| "N/A".to_string());
+    log::info!("[suggestion] Last turn content being sent to API (first 300 chars): {}", last_content_for_log);
+
+    match generate_chat_response_via_api(&api_request_contents).await {
+        Ok(response_text) if response_text.trim().is_empty() => Err(anyhow!("The AI generated an empty response.")),
+        Ok(response_text) => {
+            db.save_chat_message(&current_session_id, "assistant", &response_text).map_err(|e| {
+                log::error!("Failed to save assistant message for session {}: {}", current_session_id, e);
+                e
+            })?;
+            Ok(ChatCompletionResponse {
+                assistant_response: response_text,
+                session_id: current_session_id,
+            })
+        }
+        Err(e) => {
+            log::error!("[suggestion] Error generating chat response: {}", e);
+            Err(e.context("Failed to get response from MoodJourney"))
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -185,7 +395,7 @@ mod tests {
             let result = generate_suggestion_via_api("test prompt").await;
             assert!(result.is_err(), "Expected error.");
             if let Err(e) = result {
-                assert!(e.to_string().contains("API_KEY_NOT_CONFIGURED"), "Error should mention API key.");
+                assert_eq!(AppError::code_of(&e), "API_KEY_MISSING", "Error should report the missing API key.");
             }
         }
         else {
@@ -200,7 +410,7 @@ mod tests {
             let result = generate_chat_response_via_api(&contents).await;
             assert!(result.is_err(), "Expected error.");
             if let Err(e) = result {
-                assert!(e.to_string().contains("API_KEY_NOT_CONFIGURED"), "Error should mention API key.");
+                assert_eq!(AppError::code_of(&e), "API_KEY_MISSING", "Error should report the missing API key.");
             }
         }
         else {
@@ -225,4 +435,58 @@ mod tests {
             assert!(e.to_string().contains("Chat contents for API cannot be empty"), "Error should say chat content is empty.");
         }
     }
+
+    fn message(sender: &str, content: &str) -> ChatMessage {
+        ChatMessage {
+            id: 0,
+            session_id: "session".to_string(),
+            sender: sender.to_string(),
+            content: content.to_string(),
+            timestamp: "2025-03-01T10:00:00+00:00".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_build_suggestion_prompt() {
+        assert!(build_suggestion_prompt(Some("  "), None).is_err(), "Empty title and content should be rejected.");
+
+        let prompt = build_suggestion_prompt(Some(" Long day "), Some(&"a".repeat(800))).expect("Failed to build prompt");
+        assert!(prompt.starts_with(SUGGESTION_PROMPT_INTRO));
+        assert!(prompt.contains("\nTitle: Long day\n"));
+        assert!(prompt.contains(&format!("Content: {}...", "a".repeat(MAX_SUGGESTION_CONTENT_CHARS))), "Content should be cut at 700 characters.");
+        assert!(prompt.ends_with("\n\nSuggestion:"));
+
+        let untitled = build_suggestion_prompt(None, Some("Slept well.")).expect("Failed to build prompt");
+        assert!(!untitled.contains("Title:"));
+        assert!(untitled.contains("Content: Slept well."));
+    }
+
+    #[test]
+    fn test_build_chat_contents_adds_journal_to_first_turn() {
+        let entries = vec![Entry {
+            date: "2025-02-28".to_string(),
+            title: Some("Journal Entry".to_string()),
+            content: Some("Finished the move.\n\n🧠 Emotion: joy\n\n💡 Suggestion: - Rest".to_string()),
+            password: None,
+            image: None,
+        }];
+        let messages = vec![message("user", "How was my week?"), message("assistant", "Busy!"), message("user", "Thanks")];
+        let contents = build_chat_contents(&messages, &entries, "Saturday, March 01, 2025");
+
+        assert_eq!(contents.len(), 3);
+        let first_turn = contents[0]["parts"][0]["text"].as_str().unwrap();
+        assert!(first_turn.contains("The current date is Saturday, March 01, 2025."));
+        assert!(first_turn.contains("Date: 2025-02-28\nContent: Finished the move.\n\nEmotion: joy\n---"));
+        assert!(!first_turn.contains("Rest"), "Suggestions should not be sent as context.");
+        assert!(first_turn.ends_with("\nUser: How was my week?"));
+        assert_eq!(contents[1]["role"], "model");
+        assert_eq!(contents[2]["parts"][0]["text"], "Thanks");
+    }
+
+    #[test]
+    fn test_replace_suggestion_keeps_text_and_emotion() {
+        let original = "Went running.\n\n🧠 Emotion: joy\n\n💡 Suggestion: - Old advice";
+        assert_eq!(replace_suggestion(original, "- New advice\n"), "Went running.\n\n🧠 Emotion: joy\n\n💡 Suggestion: - New advice");
+        assert_eq!(replace_suggestion("Plain text\n", "- Tip"), "Plain text\n\n💡 Suggestion: - Tip");
+    }
 }
@@ -0,0 +1,308 @@
+use crate::db::Database;
+use crate::error::AppError;
+use crate::export;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+pub const MAX_TAG_CHARS: usize = 50;
+
+// trimmed, without a leading '#', lowercased and with inner whitespace collapsed,
+// so "#Work", "work" and " WORK " are the same tag
+pub fn normalize_tag_name(name: &str) -> Result<String> {
+    let normalized = name.trim().trim_start_matches('#').split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+    if normalized.is_empty() {
+        return Err(AppError::InvalidInput("A tag name cannot be empty.".to_string()).into());
+    }
+    if normalized.chars().count() > MAX_TAG_CHARS {
+        return Err(AppError::InvalidInput(format!("A tag name can be at most {} characters long.", MAX_TAG_CHARS)).into());
+    }
+    // commas separate tags in the CSV dataset
+    if normalized.chars().any(|c| c == ',' || c.is_control()) {
+        return Err(AppError::InvalidInput("A tag name cannot contain commas or control characters.".to_string()).into());
+    }
+    Ok(normalized)
+}
+
+fn is_tag_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '-'
+}
+
+// #hashtags in the text the user wrote, normalized and without duplicates, in order of appearance.
+// A '#' must start a word ("C#" and "page#2" are not tags) and the tag needs a letter ("#1" is not one);
+// the emotion and suggestions stored after the text are not searched.
+pub fn extract_hashtags(content: &str) -> Vec<String> {
+    let (body, _, _) = export::parse_entry_content(content);
+    let mut hashtags: Vec<String> = Vec::new();
+    let mut previous: Option<char> = None;
+    for (index, c) in body.char_indices() {
+        if c == '#' && !previous.is_some_and(|p| is_tag_char(p) || p == '#') {
+            let rest = &body[index + 1..];
+            let word: String = rest.chars().take_while(|c| is_tag_char(*c)).collect();
+            let word = word.trim_end_matches(['-', '_']);
+            if word.chars().any(char::is_alphabetic) {
+                if let Ok(name) = normalize_tag_name(word) {
+                    if !hashtags.contains(&name) {
+                        hashtags.push(name);
+                    }
+                }
+            }
+        }
+        previous = Some(c);
+    }
+    hashtags
+}
+
+fn tag_not_found(name: &str) -> anyhow::Error {
+    AppError::NotFound(format!("There is no tag named '{}'.", name)).into()
+}
+
+// returns the tags of the entry afterwards
+pub fn add_tag(db: &Database, date: &str, name: &str) -> Result<Vec<String>> {
+    let name = normalize_tag_name(name)?;
+    db.add_entry_tag(date, &name)?;
+    Ok(db.get_entry_tags(date)?)
+}
+
+// a tag that came from a #hashtag comes back when the entry is saved with the hashtag still in it
+pub fn remove_tag(db: &Database, date: &str, name: &str) -> Result<Vec<String>> {
+    let name = normalize_tag_name(name)?;
+    if !db.remove_entry_tag(date, &name)? {
+        return Err(AppError::NotFound(format!("Entry {} has no tag '{}'.", date, name)).into());
+    }
+    Ok(db.get_entry_tags(date)?)
+}
+
+pub fn rename_tag(db: &Database, name: &str, new_name: &str) -> Result<String> {
+    let name = normalize_tag_name(name)?;
+    let new_name = normalize_tag_name(new_name)?;
+    let renamed = if name == new_name { db.get_tag_id(&name)?.is_some() } else { db.rename_tag(&name, &new_name)? };
+    if !renamed {
+        return Err(tag_not_found(&name));
+    }
+    Ok(new_name)
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagMergeReport {
+    pub target: String,
+    pub merged_tags: Vec<String>,
+    // entries that did not carry the target tag before
+    pub entries_retagged: usize,
+}
+
+pub fn merge_tags(db: &Database, sources: &[String], target: &str) -> Result<TagMergeReport> {
+    let target = normalize_tag_name(target)?;
+    let mut merged_tags: Vec<String> = Vec::new();
+    for source in sources {
+        let source = normalize_tag_name(source)?;
+        if db.get_tag_id(&source)?.is_none() {
+            return Err(tag_not_found(&source));
+        }
+        if source != target && !merged_tags.contains(&source) {
+            merged_tags.push(source);
+        }
+    }
+    if merged_tags.is_empty() {
+        return Err(AppError::InvalidInput("Name at least one tag other than the target to merge.".to_string()).into());
+    }
+    let entries_retagged = db.merge_tags(&merged_tags, &target)?;
+    Ok(TagMergeReport { target, merged_tags, entries_retagged })
+}
+
+pub fn delete_tag(db: &Database, name: &str) -> Result<()> {
+    let name = normalize_tag_name(name)?;
+    if !db.delete_tag(&name)? {
+        return Err(tag_not_found(&name));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EmotionCount {
+    pub emotion: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TagEmotionStats {
+    pub tag: String,
+    pub entry_count: usize,
+    // most frequent first; entries without a classified emotion are only counted in entry_count
+    pub emotions: Vec<EmotionCount>,
+    // the most frequent emotion other than "unknown"
+    pub dominant_emotion: Option<String>,
+}
+
+// how the entries of each tag were classified; protected entries are left out
+pub fn tag_emotion_stats(db: &Database) -> Result<Vec<TagEmotionStats>> {
+    let mut stats: Vec<TagEmotionStats> = Vec::new();
+    for row in db.get_tag_emotion_rows()? {
+        if stats.last().is_none_or(|last| last.tag != row.tag) {
+            stats.push(TagEmotionStats { tag: row.tag.clone(), entry_count: 0, emotions: Vec::new(), dominant_emotion: None });
+        }
+        let current = stats.last_mut().expect("a tag was just pushed");
+        current.entry_count += row.count;
+        if let Some(emotion) = row.emotion {
+            current.emotions.push(EmotionCount { emotion, count: row.count });
+        }
+    }
+    for tag_stats in &mut stats {
+        tag_stats.emotions.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.emotion.cmp(&b.emotion)));
+        tag_stats.dominant_emotion = tag_stats.emotions.iter()
+            .find(|e| e.emotion != "unknown")
+            .map(|e| e.emotion.clone());
+    }
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{Entry, EntryQuery};
+    use crate::listing;
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn setup(test_name: &str) -> (PathBuf, Database) {
+        let mut dir = std::env::temp_dir();
+        dir.push("moodjourney_tag_tests");
+        dir.push(test_name);
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).expect("Failed to create test directory");
+        let db = Database::open(&dir.join("journal.db")).expect("Failed to open database");
+        let entries = [
+            ("2025-07-01", "Long day at #work, then #Running.\n\n🧠 Emotion: sadness", None),
+            ("2025-07-02", "Great #run with friends.\n\n🧠 Emotion: joy\n\n💡 Suggestion: - #rest", None),
+            ("2025-07-03", "Back at #work.\n\n🧠 Emotion: joy", None),
+            ("2025-07-04", "Private #work notes.\n\n🧠 Emotion: anger", Some("hash".to_string())),
+        ];
+        for (date, content, password) in entries {
+            db.add_entry(Entry {
+                date: date.to_string(),
+                title: Some(format!("Entry {}", date)),
+                content: Some(content.to_string()),
+                password,
+                image: None,
+            }).expect("Failed to add entry");
+        }
+        (dir, db)
+    }
+
+    #[test]
+    fn test_extract_hashtags() {
+        assert_eq!(
+            extract_hashtags("Met Ana at the #Café-Corner, wrote C# and read page#2. #1 #work #WORK (#travel_plans)."),
+            vec!["café-corner", "work", "travel_plans"]
+        );
+        assert_eq!(extract_hashtags("Quiet day.\n\n🧠 Emotion: joy\n\n💡 Suggestion: - Try #yoga"), Vec::<String>::new());
+        assert_eq!(AppError::code_of(&normalize_tag_name(" # ").unwrap_err()), "INVALID_INPUT");
+        assert_eq!(normalize_tag_name("  #Work   Trip ").expect("normalize failed"), "work trip");
+    }
+
+    #[test]
+    fn test_hashtags_follow_the_text_and_manual_tags_stay() {
+        let (dir, db) = setup("sync");
+        assert_eq!(db.get_entry_tags("2025-07-01").expect("tags failed"), vec!["running", "work"]);
+        assert_eq!(db.get_entry_tags("2025-07-02").expect("tags failed"), vec!["run"], "Suggestions should not add tags.");
+
+        add_tag(&db, "2025-07-01", "Family").expect("add failed");
+        db.update_entry_by_date("2025-07-01", Some("Entry"), Some("Only #chores today."), None, None).expect("update failed");
+        assert_eq!(db.get_entry_tags("2025-07-01").expect("tags failed"), vec!["chores", "family"]);
+
+        let err = remove_tag(&db, "2025-07-01", "work").unwrap_err();
+        assert_eq!(AppError::code_of(&err), "NOT_FOUND");
+        assert_eq!(remove_tag(&db, "2025-07-01", "#Family").expect("remove failed"), vec!["chores"]);
+        let err = add_tag(&db, "2025-08-01", "work").unwrap_err();
+        assert_eq!(AppError::code_of(&err), "NOT_FOUND");
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_protected_entries_only_keep_manual_tags() {
+        let (dir, db) = setup("protected");
+        assert!(db.get_entry_tags("2025-07-04").expect("tags failed").is_empty(), "The text of a protected entry should not be read for hashtags.");
+
+        add_tag(&db, "2025-07-04", "secret").expect("add failed");
+        assert_eq!(db.get_entry_tags("2025-07-04").expect("tags failed"), vec!["secret"], "Tags added by hand stay with the entry.");
+        let names: Vec<_> = db.get_tags().expect("tags failed").into_iter().map(|t| t.name).collect();
+        assert_eq!(names, vec!["run", "running", "work"], "A tag only a protected entry carries should not be listed.");
+
+        db.update_entry_by_date("2025-07-01", Some("Entry"), Some("Long day at #work, then #Running."), Some("hash"), None).expect("update failed");
+        assert!(db.get_entry_tags("2025-07-01").expect("tags failed").is_empty(), "Protecting an entry should drop its hashtags.");
+        let names: Vec<_> = db.get_tags().expect("tags failed").into_iter().map(|t| t.name).collect();
+        assert_eq!(names, vec!["run", "work"], "The hashtag only the protected entry used should be gone.");
+
+        db.update_entry_by_date("2025-07-04", Some("Entry"), Some("Private #work notes."), None, None).expect("update failed");
+        assert_eq!(db.get_entry_tags("2025-07-04").expect("tags failed"), vec!["secret", "work"], "Removing the password should bring the hashtags back.");
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_upgrade_drops_hashtags_of_protected_entries() {
+        let (dir, db) = setup("upgrade");
+        {
+            let conn = db.conn().expect("conn failed");
+            conn.execute("INSERT INTO tags (name, created_at) VALUES ('private', '2025-07-04T00:00:00Z')", []).expect("insert failed");
+            conn.execute(
+                "INSERT INTO entry_tags (entry_date, tag_id, source) SELECT '2025-07-04', id, 'hashtag' FROM tags WHERE name IN ('private', 'work')",
+                [],
+            ).expect("insert failed");
+            conn.pragma_update(None, "user_version", 7).expect("pragma failed");
+        }
+        drop(db);
+
+        let db = Database::open(&dir.join("journal.db")).expect("Failed to reopen database");
+        assert!(db.get_entry_tags("2025-07-04").expect("tags failed").is_empty(), "The upgrade should drop the protected entry's hashtags.");
+        assert_eq!(db.get_tag_id("private").expect("lookup failed"), None, "A tag left without entries should be dropped.");
+        assert_eq!(db.get_entry_tags("2025-07-03").expect("tags failed"), vec!["work"]);
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_rename_merge_and_delete() {
+        let (dir, db) = setup("rename");
+        let err = rename_tag(&db, "run", "work").unwrap_err();
+        assert_eq!(AppError::code_of(&err), "DUPLICATE", "Renaming onto an existing tag should ask for a merge.");
+        assert_eq!(rename_tag(&db, "Run", "jogging").expect("rename failed"), "jogging");
+
+        let report = merge_tags(&db, &["running".to_string(), "jogging".to_string()], "exercise").expect("merge failed");
+        assert_eq!(report.merged_tags, vec!["running", "jogging"]);
+        assert_eq!(report.entries_retagged, 2);
+        let names: Vec<_> = db.get_tags().expect("tags failed").into_iter().map(|t| (t.name, t.entry_count)).collect();
+        assert_eq!(names, vec![("exercise".to_string(), 2), ("work".to_string(), 2)], "Protected entries should not be counted.");
+
+        db.update_entry_by_date("2025-07-02", Some("Entry"), Some("Great #run with friends, again."), None, None).expect("update failed");
+        assert_eq!(db.get_entry_tags("2025-07-02").expect("tags failed"), vec!["exercise", "run"], "A merged tag should survive a save.");
+
+        delete_tag(&db, "work").expect("delete failed");
+        assert!(db.get_entry_tags("2025-07-03").expect("tags failed").is_empty());
+        assert_eq!(AppError::code_of(&delete_tag(&db, "work").unwrap_err()), "NOT_FOUND");
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_tag_filter_and_emotion_stats() {
+        let (dir, db) = setup("stats");
+        let page = listing::list_entries(&db, &EntryQuery { tags: vec!["#Work".to_string()], ..Default::default() }).expect("list failed");
+        let dates: Vec<_> = page.entries.iter().map(|e| e.date.as_str()).collect();
+        assert_eq!(dates, vec!["2025-07-03", "2025-07-01"], "Protected entries should not match a tag filter.");
+        assert_eq!(page.entries[1].tags, vec!["running", "work"]);
+
+        let both = listing::list_entries(&db, &EntryQuery { tags: vec!["work".to_string(), "running".to_string()], ..Default::default() })
+            .expect("list failed");
+        assert_eq!(both.entries.len(), 1, "Every tag of the filter should be required.");
+
+        let stats = tag_emotion_stats(&db).expect("stats failed");
+        let work = stats.iter().find(|s| s.tag == "work").expect("work stats missing");
+        assert_eq!(work.entry_count, 2);
+        assert_eq!(work.emotions, vec![
+            EmotionCount { emotion: "joy".to_string(), count: 1 },
+            EmotionCount { emotion: "sadness".to_string(), count: 1 },
+        ]);
+        assert_eq!(work.dominant_emotion.as_deref(), Some("joy"));
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,230 @@
+use crate::attachments;
+use crate::db::{Database, TrashedChatSession, TrashedEntry};
+use crate::error::AppError;
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Trash {
+    pub entries: Vec<TrashedEntry>,
+    pub chat_sessions: Vec<TrashedChatSession>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashPurgeReport {
+    pub purged_entries: Vec<String>,
+    pub purged_chat_sessions: Vec<String>,
+    // image and attachment files removed with the entries, relative to the app local data dir
+    pub removed_files: Vec<String>,
+}
+
+pub fn list_trash(db: &Database) -> Result<Trash> {
+    Ok(Trash {
+        entries: db.get_trashed_entries()?,
+        chat_sessions: db.get_trashed_chat_sessions()?,
+    })
+}
+
+// files are only removed here, never when an entry is moved to the trash
+pub fn purge_entry(db: &Database, base_dir: &Path, date: &str) -> Result<Vec<String>> {
+    let entry = db.get_trashed_entry(date)?
+        .ok_or_else(|| AppError::NotFound(format!("There is no entry for {} in the trash.", date)))?;
+
+    let mut files_to_remove: Vec<String> = entry.image.into_iter().filter(|p| !p.is_empty()).collect();
+    for attachment in db.get_attachments_for_entry(date)? {
+        if !files_to_remove.contains(&attachment.relative_path) {
+            files_to_remove.push(attachment.relative_path);
+        }
+    }
+
+    db.delete_entry_by_date(date)?;
+
+    // remove_stored_file refuses paths outside journal_images, which an imported entry may still point at
+    let mut removed_files = Vec::new();
+    for relative_path in files_to_remove {
+        match attachments::remove_stored_file(base_dir, &relative_path) {
+            Ok(()) => removed_files.push(relative_path),
+            Err(e) => eprintln!("[purge_entry] {}", e),
+        }
+    }
+    Ok(removed_files)
+}
+
+pub fn purge_chat_session(db: &Database, session_id: &str) -> Result<()> {
+    if !db.get_trashed_chat_sessions()?.iter().any(|s| s.id == session_id) {
+        return Err(AppError::NotFound(format!("There is no chat session {} in the trash.", session_id)).into());
+    }
+    db.delete_chat_session(session_id)?;
+    Ok(())
+}
+
+fn purge_where(db: &Database, base_dir: &Path, keep: impl Fn(&str) -> bool) -> Result<TrashPurgeReport> {
+    let mut report = TrashPurgeReport::default();
+    for entry in db.get_trashed_entries()? {
+        if keep(&entry.deleted_at) {
+            continue;
+        }
+        report.removed_files.extend(purge_entry(db, base_dir, &entry.date)?);
+        report.purged_entries.push(entry.date);
+    }
+    for session in db.get_trashed_chat_sessions()? {
+        if keep(&session.deleted_at) {
+            continue;
+        }
+        db.delete_chat_session(&session.id)?;
+        report.purged_chat_sessions.push(session.id);
+    }
+    Ok(report)
+}
+
+pub fn empty_trash(db: &Database, base_dir: &Path) -> Result<TrashPurgeReport> {
+    purge_where(db, base_dir, |_| false)
+}
+
+// purges whatever was deleted more than retention_days before now
+pub fn purge_expired(db: &Database, base_dir: &Path, retention_days: u32, now: DateTime<Utc>) -> Result<TrashPurgeReport> {
+    let cutoff = now - Duration::days(retention_days as i64);
+    purge_where(db, base_dir, |deleted_at| {
+        // an unreadable timestamp is kept rather than purged by mistake
+        DateTime::parse_from_rfc3339(deleted_at).map_or(true, |deleted_at| deleted_at >= cutoff)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{Entry, NewAttachment};
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn setup(test_name: &str) -> (PathBuf, Database) {
+        let mut dir = std::env::temp_dir();
+        dir.push("moodjourney_trash_tests");
+        dir.push(test_name);
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("journal_images")).expect("Failed to create test directory");
+        let db = Database::open(&dir.join("journal.db")).expect("Failed to open database");
+        for (date, image) in [("2025-05-01", "journal_images/cover.png"), ("2025-05-02", "journal_images/other.png")] {
+            fs::write(dir.join(image), b"png").expect("Failed to write image");
+            db.add_entry(Entry {
+                date: date.to_string(),
+                title: Some(format!("Entry {}", date)),
+                content: Some("Text".to_string()),
+                password: None,
+                image: Some(image.to_string()),
+            }).expect("Failed to add entry");
+        }
+        fs::write(dir.join("journal_images/extra.png"), b"png").expect("Failed to write image");
+        db.add_attachment("2025-05-01", &NewAttachment {
+            kind: "image".to_string(),
+            relative_path: "journal_images/extra.png".to_string(),
+            mime: Some("image/png".to_string()),
+            size: 3,
+            checksum: None,
+        }).expect("Failed to add attachment");
+        (dir, db)
+    }
+
+    #[test]
+    fn test_trash_hides_entries_and_restore_brings_them_back() {
+        let (dir, db) = setup("restore");
+        assert!(db.trash_entry("2025-05-01").expect("trash failed"));
+        assert!(!db.trash_entry("2025-05-01").expect("trash failed"), "An entry can only be trashed once.");
+
+        assert_eq!(db.get_entries().expect("list failed").len(), 1);
+        assert!(db.get_entry_by_date("2025-05-01").expect("get failed").is_none());
+        assert!(dir.join("journal_images/cover.png").exists(), "Images should stay until the entry is purged.");
+        let trash = list_trash(&db).expect("list trash failed");
+        assert_eq!(trash.entries.len(), 1);
+        assert_eq!(trash.entries[0].date, "2025-05-01");
+
+        let err = db.add_entry(Entry {
+            date: "2025-05-01".to_string(),
+            title: Some("Again".to_string()),
+            content: None,
+            password: None,
+            image: None,
+        }).unwrap_err();
+        assert_eq!(err.code(), "DUPLICATE");
+        assert!(err.message().contains("trash"), "The error should point at the trash.");
+
+        assert!(db.restore_trashed_entry("2025-05-01").expect("restore failed"));
+        assert!(db.get_entry_by_date("2025-05-01").expect("get failed").is_some());
+        assert_eq!(db.get_attachments_for_entry("2025-05-01").expect("attachments failed").len(), 1);
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_purge_removes_rows_and_files() {
+        let (dir, db) = setup("purge");
+        let err = purge_entry(&db, &dir, "2025-05-01").unwrap_err();
+        assert_eq!(AppError::code_of(&err), "NOT_FOUND", "Only entries in the trash can be purged.");
+
+        db.trash_entry("2025-05-01").expect("trash failed");
+        let mut removed = purge_entry(&db, &dir, "2025-05-01").expect("purge failed");
+        removed.sort();
+        assert_eq!(removed, vec!["journal_images/cover.png", "journal_images/extra.png"]);
+        assert!(!dir.join("journal_images/cover.png").exists() && !dir.join("journal_images/extra.png").exists());
+        assert!(dir.join("journal_images/other.png").exists());
+        assert!(db.get_trashed_entry("2025-05-01").expect("get failed").is_none());
+        assert!(db.get_all_attachments().expect("attachments failed").iter().all(|a| a.entry_date != "2025-05-01"));
+
+        let session_id = db.create_new_chat_session().expect("session failed");
+        db.save_chat_message(&session_id, "user", "Hello").expect("message failed");
+        db.trash_chat_session(&session_id).expect("trash failed");
+        assert!(db.get_all_chat_sessions().expect("sessions failed").is_empty());
+        purge_chat_session(&db, &session_id).expect("purge failed");
+        assert!(db.get_messages_for_session(&session_id).expect("messages failed").is_empty());
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_purge_leaves_files_outside_the_images_directory() {
+        let (dir, db) = setup("outside");
+        let outside = dir.join("keep.png");
+        fs::write(&outside, b"png").expect("Failed to write file");
+        db.add_entry(Entry {
+            date: "2025-05-03".to_string(),
+            title: Some("Imported".to_string()),
+            content: None,
+            password: None,
+            image: Some(outside.to_string_lossy().into_owned()),
+        }).expect("Failed to add entry");
+        db.add_attachment("2025-05-03", &NewAttachment {
+            kind: "image".to_string(),
+            relative_path: "journal_images/../keep.png".to_string(),
+            mime: Some("image/png".to_string()),
+            size: 3,
+            checksum: None,
+        }).expect("Failed to add attachment");
+
+        db.trash_entry("2025-05-03").expect("trash failed");
+        let removed = purge_entry(&db, &dir, "2025-05-03").expect("purge failed");
+        assert!(removed.is_empty(), "Only files inside the images directory should be removed.");
+        assert!(outside.exists());
+        assert!(db.get_trashed_entry("2025-05-03").expect("get failed").is_none(), "The entry itself should still be purged.");
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_purge_expired_keeps_recent_items() {
+        let (dir, db) = setup("expired");
+        db.trash_entry("2025-05-01").expect("trash failed");
+        let session_id = db.create_new_chat_session().expect("session failed");
+        db.trash_chat_session(&session_id).expect("trash failed");
+
+        let report = purge_expired(&db, &dir, 30, Utc::now()).expect("purge failed");
+        assert!(report.purged_entries.is_empty() && report.purged_chat_sessions.is_empty(), "Fresh items should be kept.");
+
+        let report = purge_expired(&db, &dir, 30, Utc::now() + Duration::days(31)).expect("purge failed");
+        assert_eq!(report.purged_entries, vec!["2025-05-01"]);
+        assert_eq!(report.purged_chat_sessions, vec![session_id]);
+        assert_eq!(report.removed_files.len(), 2);
+        assert!(list_trash(&db).expect("list trash failed").entries.is_empty());
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -1,20 +1,24 @@
-import React, { useState, useEffect, useMemo, useCallback } from 'react';
+import React, { useState, useEffect, useMemo, useCallback, useRef } from 'react';
 import { invoke } from "@tauri-apps/api/core";
 import { ThemeProvider, CssBaseline, Box, Toolbar, Typography, Snackbar, Alert as MuiAlert, CircularProgress, createTheme } from '@mui/material';
 import { alpha } from '@mui/material/styles';
 import MenuIcon from '@mui/icons-material/Menu';
 import IconButton from '@mui/material/IconButton';
 import DeleteIcon from '@mui/icons-material/Delete';
+import DeleteForeverIcon from '@mui/icons-material/DeleteForever';
+import RestoreIcon from '@mui/icons-material/Restore';
 
 import { lightTheme, darkTheme, girlbossTheme, AppBar as StyledAppBar } from './theme';
 import {
-    formatDate, getEmotionColor, getContentForEditing, ALERT_TIMEOUT_DURATION
+    formatDate, getEmotionColor, getContentForEditing, ALERT_TIMEOUT_DURATION, NEW_ENTRY_DRAFT_SLOT, ENTRY_PAGE_SIZE,
+    DELETE_ENTRY_CONFIRMATION
 } from './utils';
 
 import AppDrawer from './components/AppDrawer';
 import SettingsPage from './components/SettingsPage';
 import InsightsPage from './components/InsightsPage';
 import AssistantPage from './components/AssistantPage';
+import TrashPage from './components/TrashPage';
 import JournalEntryView from './components/JournalEntryView';
 import NewEntryForm from './components/NewEntryForm';
 import PinModal from './components/PinModal';
@@ -26,6 +30,8 @@ function App() {
     const [configuredUserName, setConfiguredUserName] = useState(() => localStorage.getItem('appConfiguredUserName') || 'Michael');
     const [isDarkModeActive, setIsDarkModeActive] = useState(false);
     const [entries, setEntries] = useState([]);
+    const [nextEntriesCursor, setNextEntriesCursor] = useState(null);
+    const [loadingMoreEntries, setLoadingMoreEntries] = useState(false);
     const [selectedEntry, setSelectedEntry] = useState(null);
     const [loading, setLoading] = useState(true);
     const [status, setStatus] = useState({ message: "", severity: "info" });
@@ -43,6 +49,8 @@ function App() {
     const [isFileDictating, setIsFileDictating] = useState(false);
     const [deleteConfirmOpen, setDeleteConfirmOpen] = useState(false);
     const [entryToDelete, setEntryToDelete] = useState(null);
+    // date of a trashed entry that blocks saving a new entry for the same day
+    const [trashedEntryConflict, setTrashedEntryConflict] = useState(null);
     const [isPinSet, setIsPinSet] = useState(false);
     const [showPinModal, setShowPinModal] = useState(false);
     const [pinInput, setPinInput] = useState("");
@@ -212,21 +220,73 @@ function App() {
         return `${date.getFullYear()}-${String(date.getMonth() + 1).padStart(2, '0')}-${String(date.getDate()).padStart(2, '0')}`;
     };
 
+    // the list holds entry summaries, newest first; the full entry is loaded with get_entry when it is opened
     const refreshEntriesList = useCallback(async () => {
         setLoading(true);
         try {
-            const freshEntries = (await invoke("read_entries")) || [];
-            const sorted = freshEntries.sort((a, b) => new Date(b.date) - new Date(a.date));
-            setEntries(sorted); return sorted;
+            const page = await invoke("list_entries", { query: { limit: ENTRY_PAGE_SIZE } });
+            setEntries(page.entries); setNextEntriesCursor(page.nextCursor); return page.entries;
         } catch (err) {
             console.error("Error refreshing entries list:", err);
             setStatus({ message: `Error refreshing entries: ${err.message || String(err)}`, severity: "error" });
-            setEntries([]); return [];
+            setEntries([]); setNextEntriesCursor(null); return [];
         } finally { setLoading(false); }
     }, []);
 
+    const loadMoreEntries = async () => {
+        if (!nextEntriesCursor || loadingMoreEntries) return;
+        setLoadingMoreEntries(true);
+        try {
+            const page = await invoke("list_entries", { query: { cursor: nextEntriesCursor, limit: ENTRY_PAGE_SIZE } });
+            setEntries(current => [...current, ...page.entries]); setNextEntriesCursor(page.nextCursor);
+        } catch (err) {
+            console.error("Error loading more entries:", err);
+            setStatus({ message: `Error loading more entries: ${err.message || String(err)}`, severity: "error" });
+        } finally { setLoadingMoreEntries(false); }
+    };
+
     useEffect(() => { if (!isAppLocked) refreshEntriesList(); }, [isAppLocked, refreshEntriesList]);
 
+    // drafts left over from the last session: the new entry text is put back, edits come back when that entry is edited again
+    useEffect(() => {
+        if (isAppLocked) return;
+        invoke("list_stale_drafts_cmd").then((staleDrafts) => {
+            if (!staleDrafts || staleDrafts.length === 0) return;
+            const newEntryDraft = staleDrafts.find(draft => draft.slot === NEW_ENTRY_DRAFT_SLOT);
+            if (newEntryDraft) setEntryText(current => current || newEntryDraft.content);
+            const editCount = staleDrafts.length - (newEntryDraft ? 1 : 0);
+            const parts = [];
+            if (newEntryDraft) parts.push("Restored your unsaved entry from last time.");
+            if (editCount > 0) parts.push(`${editCount} unsaved edit(s) will be restored when you edit ${editCount === 1 ? "that entry" : "those entries"} again.`);
+            setStatus({ message: parts.join(" "), severity: "info" });
+        }).catch(err => console.error("Error loading unsaved drafts:", err));
+    }, [isAppLocked]);
+
+    // autosave; the backend debounces, so every change can be sent. Clearing the text discards the draft,
+    // but only for slots autosaved in this session, so an empty page never drops a draft before it is restored
+    const autosavedDraftSlots = useRef(new Set());
+    const autosaveDraft = useCallback((slot, content) => {
+        if (content.trim()) {
+            autosavedDraftSlots.current.add(slot);
+            invoke("save_draft_cmd", { slot, title: null, content })
+                .catch(err => console.error("Error autosaving draft:", err));
+        } else if (autosavedDraftSlots.current.delete(slot)) {
+            invoke("discard_draft_cmd", { slot })
+                .catch(err => console.error("Error discarding draft:", err));
+        }
+    }, []);
+
+    useEffect(() => {
+        if (isAppLocked || saving) return;
+        autosaveDraft(NEW_ENTRY_DRAFT_SLOT, entryText);
+    }, [entryText, isAppLocked, saving, autosaveDraft]);
+
+    useEffect(() => {
+        if (isAppLocked || saving || !isEditingSelectedEntry || !selectedEntry) return;
+        if (editedContentText === getContentForEditing(selectedEntry.content)) return;
+        autosaveDraft(selectedEntry.date, editedContentText);
+    }, [editedContentText, isEditingSelectedEntry, selectedEntry, isAppLocked, saving, autosaveDraft]);
+
     const handleOpenImageUploadModal = (entry) => {
         setEntryForImageUpload(entry);
         setImageUploadModalOpen(true);
@@ -272,10 +332,9 @@ function App() {
             });
             setStatus({ message: "Image uploaded and entry updated successfully!", severity: "success" });
             handleCloseImageUploadModal();
-            const updatedEntries = await refreshEntriesList();
+            await refreshEntriesList();
             if (selectedEntry && selectedEntry.date === entryForImageUpload.date) {
-                const newlySelectedEntry = updatedEntries.find(entry => entry.date === entryForImageUpload.date);
-                setSelectedEntry(newlySelectedEntry || null);
+                setSelectedEntry(await invoke("get_entry", { date: entryForImageUpload.date }));
             }
         } catch (error) {
             console.error("Failed to upload image or update entry:", error);
@@ -412,18 +471,15 @@ function App() {
         } catch (err) {
             console.error("Error during file upload dictation:", err);
             let errorMessage = "Failed to transcribe audio file.";
-            if (typeof err === 'string') {
-                if (err.includes("Failed to load dictation model")) {
-                    errorMessage = "Error: Could not load the transcription model.";
-                } else if (err.includes("Unsupported audio sample rate")) {
-                    errorMessage = "Dictation failed: Unsupported audio sample rate (16kHz required).";
-                } else if (err.includes("Unsupported audio channel count")) {
-                    errorMessage = "Dictation failed: Unsupported audio channel count (mono required).";
-                } else {
-                    errorMessage = err;
-                }
-            } else if (err.message) { 
-                errorMessage = err.message;
+            const message = typeof err === 'string' ? err : err?.message;
+            if (err?.code === "MODEL_UNAVAILABLE") {
+                errorMessage = "Error: Could not load the transcription model.";
+            } else if (message?.includes("Unsupported audio sample rate")) {
+                errorMessage = "Dictation failed: Unsupported audio sample rate (16kHz required).";
+            } else if (message?.includes("Unsupported audio channel count")) {
+                errorMessage = "Dictation failed: Unsupported audio channel count (mono required).";
+            } else if (message) {
+                errorMessage = message;
             }
             setStatus({ message: errorMessage, severity: "error" });
         } finally {
@@ -442,18 +498,20 @@ function App() {
         catch (suggestionError) { statusMessage += `AI suggestion failed.`; statusSeverity = "warning"; }
         const contentToSave = `${currentEntryText}\n\n🧠 Emotion: ${classifiedEmotion}\n\n💡 Suggestion: ${generatedSuggestion}`;
         const currentDate = getCurrentDateString();
-        const existingEntryForToday = entries.find(entry => entry.date === currentDate);
-        const operation = existingEntryForToday ? "update_entry" : "create_entry";
-        const payload = existingEntryForToday ? { date: currentDate, newTitle: existingEntryForToday.title || "Journal Entry", newContent: contentToSave, newPassword: existingEntryForToday.password, newImage: existingEntryForToday.image } : { title: "Journal Entry", content: contentToSave, password: null, image: null };
+        let operation = "create_entry";
         try {
+            const existingEntryForToday = await invoke("get_entry", { date: currentDate });
+            operation = existingEntryForToday ? "update_entry" : "create_entry";
+            const payload = existingEntryForToday ? { date: currentDate, newTitle: existingEntryForToday.title || "Journal Entry", newContent: contentToSave, newPassword: existingEntryForToday.password, newImage: existingEntryForToday.image } : { title: "Journal Entry", content: contentToSave, password: null, image: null };
             await invoke(operation, payload);
+            if (operation === "update_entry") await invoke("discard_draft_cmd", { slot: NEW_ENTRY_DRAFT_SLOT });
             const verb = operation === "create_entry" ? "saved" : "updated";
             statusMessage = statusSeverity !== "warning" ? `Entry ${verb} successfully!` : `Entry ${verb} with issues: ${statusMessage}`;
             statusSeverity = statusSeverity !== "warning" ? "success" : statusSeverity;
             if (classifiedEmotion && classifiedEmotion.toLowerCase() !== "unknown") { statusMessage += ` Detected Emotion: ${classifiedEmotion.toUpperCase()}`; flashBackground(classifiedEmotion); }
             setEntryText(""); setShowAllEntriesInDrawer(false);
-            const updatedEntries = await refreshEntriesList();
-            const newOrUpdatedEntry = updatedEntries.find(e => e.date === currentDate);
+            await refreshEntriesList();
+            const newOrUpdatedEntry = await invoke("get_entry", { date: currentDate });
             if (newOrUpdatedEntry) {
                 setSelectedEntry(newOrUpdatedEntry);
                 setCurrentView('main');
@@ -461,12 +519,51 @@ function App() {
                 setExpandedSuggestionIndices([0, 1, 2]);
             }
             else { statusMessage = `Entry ${verb}, but couldn't auto-select. Find it in the list.`; statusSeverity = "info"; }
-        } catch (err) { statusMessage = `Failed to ${operation === "create_entry" ? 'save' : 'update'} entry: ${err.message || String(err)}`; statusSeverity = "error"; }
+        } catch (err) {
+            if (operation === "create_entry" && err?.code === "DUPLICATE") {
+                setTrashedEntryConflict(currentDate);
+                statusMessage = "Today's entry is in the Trash. Restore it or delete it for good to save this one.";
+                statusSeverity = "warning";
+            } else { statusMessage = `Failed to ${operation === "create_entry" ? 'save' : 'update'} entry: ${err.message || String(err)}`; statusSeverity = "error"; }
+        }
         finally { setStatus({ message: statusMessage, severity: statusSeverity }); setSaving(false); }
     };
 
-    const handleStartEditSelectedEntry = () => { if (selectedEntry) { setEditedContentText(getContentForEditing(selectedEntry.content)); setIsEditingSelectedEntry(true); setStatus({ message: "", severity: "info" }); setLastDetectedEmotion(""); } };
-    const handleCancelEditSelectedEntry = () => { setIsEditingSelectedEntry(false); setEditedContentText(""); setStatus({ message: "Edit cancelled.", severity: "info" }); };
+    // the typed text stays in the new entry form either way; after a restore, saving it updates the restored entry
+    const handleRestoreTrashedEntry = async () => {
+        const date = trashedEntryConflict;
+        setTrashedEntryConflict(null);
+        if (!date) return;
+        try {
+            await invoke("restore_entry_cmd", { date });
+            await refreshEntriesList();
+            setStatus({ message: `Entry for ${formatDate(date)} restored. Save again to add your text to it.`, severity: "success" });
+        } catch (err) { setStatus({ message: `Failed to restore entry: ${err.message || String(err)}`, severity: "error" }); }
+    };
+
+    const handlePurgeTrashedEntryAndSave = async () => {
+        const date = trashedEntryConflict;
+        setTrashedEntryConflict(null);
+        if (!date) return;
+        try { await invoke("purge_entry_cmd", { date }); }
+        catch (err) { setStatus({ message: `Failed to delete the trashed entry: ${err.message || String(err)}`, severity: "error" }); return; }
+        await handleSaveEntry();
+    };
+
+    const handleStartEditSelectedEntry = async () => {
+        if (!selectedEntry) return;
+        let draft = null;
+        try { draft = await invoke("load_draft_cmd", { slot: selectedEntry.date }); }
+        catch (err) { console.error("Error loading draft:", err); }
+        setEditedContentText(draft ? draft.content : getContentForEditing(selectedEntry.content));
+        setIsEditingSelectedEntry(true);
+        setStatus(draft ? { message: "Restored your unsaved changes to this entry.", severity: "info" } : { message: "", severity: "info" });
+        setLastDetectedEmotion("");
+    };
+    const handleCancelEditSelectedEntry = () => {
+        if (selectedEntry) invoke("discard_draft_cmd", { slot: selectedEntry.date }).catch(err => console.error("Error discarding draft:", err));
+        setIsEditingSelectedEntry(false); setEditedContentText(""); setStatus({ message: "Edit cancelled.", severity: "info" });
+    };
 
     const handleConfirmUpdateSelectedEntry = async () => {
         const currentEditedContent = editedContentText.trim();
@@ -483,8 +580,8 @@ function App() {
             statusMessage = statusSeverity !== "warning" ? "Entry updated successfully!" : `Entry updated with issues: ${statusMessage}`;
             statusSeverity = statusSeverity !== "warning" ? "success" : statusSeverity;
             if (classifiedEmotion && classifiedEmotion.toLowerCase() !== "unknown") { statusMessage += ` Detected Emotion: ${classifiedEmotion.toUpperCase()}`; flashBackground(classifiedEmotion); }
-            const updatedEntries = await refreshEntriesList();
-            const newlySelectedEntry = updatedEntries.find(entry => entry.date === selectedEntry.date) || null;
+            await refreshEntriesList();
+            const newlySelectedEntry = await invoke("get_entry", { date: selectedEntry.date });
             setSelectedEntry(newlySelectedEntry);
             if (newlySelectedEntry) {
                 setExpandedSuggestionIndices([0, 1, 2]);
@@ -522,14 +619,13 @@ function App() {
                 setIsEditingSelectedEntry(false);
                 setEditedContentText("");
             }
-            await refreshEntriesList();
+            const remainingEntries = await refreshEntriesList();
             setShowAllEntriesInDrawer(false);
             if (selectedEntry?.date === entryToDelete.date) {
                 setSelectedEntry(null);
                 handleNewEntryClick();
             }
-            const currentEntries = await invoke("read_entries");
-            if (currentEntries.length === 0) {
+            if (remainingEntries.length === 0) {
                 handleNewEntryClick();
             }
         } catch (err) {
@@ -545,8 +641,19 @@ function App() {
     const handleDrawerHoverOpen = () => !drawerOpen && setHoverOpen(true);
     const handleDrawerHoverClose = () => setHoverOpen(false);
 
-    const handleEntrySelect = (entry) => {
-        setSelectedEntry(entry);
+    const handleEntrySelect = async (entry) => {
+        let fullEntry = null;
+        try { fullEntry = await invoke("get_entry", { date: entry.date }); }
+        catch (err) {
+            setStatus({ message: `Failed to open entry: ${err.message || String(err)}`, severity: "error" });
+            return;
+        }
+        if (!fullEntry) {
+            setStatus({ message: "This entry no longer exists.", severity: "warning" });
+            refreshEntriesList();
+            return;
+        }
+        setSelectedEntry(fullEntry);
         setIsEditingSelectedEntry(false);
         setEditedContentText("");
         setEntryText("");
@@ -560,6 +667,9 @@ function App() {
         setIsEditingSelectedEntry(false);
         setEditedContentText("");
         setEntryText("");
+        invoke("load_draft_cmd", { slot: NEW_ENTRY_DRAFT_SLOT })
+            .then(draft => { if (draft) setEntryText(current => current || draft.content); })
+            .catch(err => console.error("Error loading draft:", err));
         setStatus({ message: "", severity: "info" });
         setLastDetectedEmotion("");
         setCurrentView('main');
@@ -590,6 +700,14 @@ function App() {
         setLastDetectedEmotion("");
         setExpandedSuggestionIndices([]);
     };
+    const handleTrashClick = () => {
+        setCurrentView('trash');
+        setSelectedEntry(null);
+        setIsEditingSelectedEntry(false);
+        setStatus({ message: "", severity: "info" });
+        setLastDetectedEmotion("");
+        setExpandedSuggestionIndices([]);
+    };
 
     const handleToggleSuggestionExpand = (index) => {
         setExpandedSuggestionIndices(prevIndices => {
@@ -697,6 +815,9 @@ function App() {
             case 'insights':
                 return <InsightsPage
                     entries={entries}
+                    hasMoreEntries={!!nextEntriesCursor}
+                    loadingMoreEntries={loadingMoreEntries}
+                    onLoadMoreEntries={loadMoreEntries}
                     onBack={handleNewEntryClick}
                     handleEntrySelect={handleEntrySelect}
                 />;
@@ -706,6 +827,12 @@ function App() {
                     setStatus={setStatus}
                     onBack={handleNewEntryClick}
                 />;
+            case 'trash':
+                return <TrashPage
+                    setStatus={setStatus}
+                    onBack={handleNewEntryClick}
+                    onTrashChanged={refreshEntriesList}
+                />;
             case 'main':
             default:
                 if (selectedEntry) {
@@ -773,8 +900,9 @@ function App() {
                                     {currentView === 'settings' ? 'Settings'
                                         : currentView === 'insights' ? 'Insights'
                                             : currentView === 'assistant' ? 'Assistant'
-                                                : selectedEntry ? (isEditingSelectedEntry ? `Editing: ${formatDate(selectedEntry.date)}` : formatDate(selectedEntry.date))
-                                                    : "MoodJourney"}
+                                                : currentView === 'trash' ? 'Trash'
+                                                    : selectedEntry ? (isEditingSelectedEntry ? `Editing: ${formatDate(selectedEntry.date)}` : formatDate(selectedEntry.date))
+                                                        : "MoodJourney"}
                                 </Typography>
                             </Toolbar>
                         </StyledAppBar>
@@ -794,8 +922,12 @@ function App() {
                             handleEntrySelect={handleEntrySelect}
                             showAllEntriesInDrawer={showAllEntriesInDrawer}
                             handleToggleShowEntries={handleToggleShowEntries}
+                            hasMoreEntries={!!nextEntriesCursor}
+                            loadingMoreEntries={loadingMoreEntries}
+                            handleLoadMoreEntries={loadMoreEntries}
                             handleAssistantClick={handleAssistantClick}
                             handleInsightsClick={handleInsightsClick}
+                            handleTrashClick={handleTrashClick}
                             handleSettingsClick={handleSettingsClick}
                         />
                     </>
@@ -839,11 +971,24 @@ function App() {
                     onClose={handleCloseDeleteConfirm}
                     onConfirm={handleConfirmDeleteEntry}
                     title="Confirm Deletion"
-                    contentText="Are you sure you want to delete this journal entry? This action cannot be undone."
+                    contentText={DELETE_ENTRY_CONFIRMATION}
                     confirmButtonText="Delete"
                     confirmButtonColor="error"
                     ConfirmButtonIcon={DeleteIcon}
                 />
+                <ConfirmationDialog
+                    open={!!trashedEntryConflict}
+                    onClose={() => setTrashedEntryConflict(null)}
+                    onConfirm={handlePurgeTrashedEntryAndSave}
+                    title="Entry in Trash"
+                    contentText={`The entry for ${trashedEntryConflict ? formatDate(trashedEntryConflict) : 'today'} is in the Trash. Restore it, or delete it for good with its images and attachments and save this one instead.`}
+                    confirmButtonText="Delete and Save"
+                    confirmButtonColor="error"
+                    ConfirmButtonIcon={DeleteForeverIcon}
+                    secondaryButtonText="Restore"
+                    onSecondary={handleRestoreTrashedEntry}
+                    SecondaryButtonIcon={RestoreIcon}
+                />
                 <Snackbar
                     open={!!(status.message && status.severity !== "info")}
                     autoHideDuration={ALERT_TIMEOUT_DURATION}
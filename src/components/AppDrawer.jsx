@@ -12,6 +12,7 @@ import ExpandLessIcon from '@mui/icons-material/ExpandLess';
 import AutoAwesomeIcon from '@mui/icons-material/AutoAwesome';
 import ShowChartIcon from '@mui/icons-material/ShowChart';
 import SettingsIcon from '@mui/icons-material/Settings';
+import DeleteOutlineIcon from '@mui/icons-material/DeleteOutline';
 import { formatDate, scrollbarStyles, INITIAL_VISIBLE_ENTRIES } from '../utils';
 import { Drawer as StyledDrawer } from '../theme';
 
@@ -31,8 +32,12 @@ function AppDrawer({
     handleEntrySelect,
     showAllEntriesInDrawer,
     handleToggleShowEntries,
+    hasMoreEntries,
+    loadingMoreEntries,
+    handleLoadMoreEntries,
     handleAssistantClick,
     handleInsightsClick,
+    handleTrashClick,
     handleSettingsClick
 }) {
     const isDrawerVisuallyOpen = drawerOpen || hoverOpen;
@@ -126,6 +131,20 @@ function AppDrawer({
                             </Button>
                         </Box>
                     )}
+                    {isDrawerVisuallyOpen && !loading && showAllEntriesInDrawer && hasMoreEntries && (
+                        <Box sx={{ p: 1, pt: 0, display: 'flex', justifyContent: 'center' }}>
+                            <Button
+                                onClick={handleLoadMoreEntries}
+                                disabled={loadingMoreEntries}
+                                variant="text"
+                                size="small"
+                                endIcon={loadingMoreEntries ? <CircularProgress size={16} /> : <ExpandMoreIcon />}
+                                sx={{ textTransform: 'none', color: 'text.secondary' }}
+                            >
+                                Load Older Entries
+                            </Button>
+                        </Box>
+                    )}
                 </Box>
                 <Box sx={{ marginTop: 'auto', flexShrink: 0 }}>
                     <Divider />
@@ -133,6 +152,7 @@ function AppDrawer({
                         {[
                             { text: 'Assistant', icon: <AutoAwesomeIcon />, handler: handleAssistantClick, view: 'assistant' },
                             { text: 'Insights', icon: <ShowChartIcon />, handler: handleInsightsClick, view: 'insights' },
+                            { text: 'Trash', icon: <DeleteOutlineIcon />, handler: handleTrashClick, view: 'trash' },
                             { text: 'Settings', icon: <SettingsIcon />, handler: handleSettingsClick, view: 'settings' }
                         ].map((item) => (
                             <ListItem key={item.text} disablePadding>
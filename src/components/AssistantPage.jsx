@@ -369,7 +369,7 @@ function AssistantPage({ theme, setStatus, onBack }) {
                 onClose={handleCloseDeleteChatConfirm}
                 onConfirm={handleConfirmDeleteChat}
                 title="Confirm Deletion"
-                contentText="Are you sure you want to delete this chat session? This action cannot be undone."
+                contentText="Are you sure you want to delete this chat session? It will be moved to the trash, where it can be restored until the trash is emptied."
                 confirmButtonText="Delete"
                 confirmButtonColor="error"
                 ConfirmButtonIcon={DeleteIcon}
@@ -12,7 +12,11 @@ function ConfirmationDialog({
     confirmButtonText = "Confirm",
     confirmButtonColor = "primary",
     ConfirmButtonIcon,
-    cancelButtonText = "Cancel"
+    cancelButtonText = "Cancel",
+    // optional third choice shown between cancel and confirm
+    secondaryButtonText,
+    onSecondary,
+    SecondaryButtonIcon
 }) {
     return (
         <Dialog
@@ -31,6 +35,16 @@ function ConfirmationDialog({
                 <Button onClick={onClose} color="inherit" variant="outlined">
                     {cancelButtonText}
                 </Button>
+                {secondaryButtonText && onSecondary && (
+                    <Button
+                        onClick={onSecondary}
+                        color="primary"
+                        variant="outlined"
+                        startIcon={SecondaryButtonIcon ? <SecondaryButtonIcon /> : null}
+                    >
+                        {secondaryButtonText}
+                    </Button>
+                )}
                 <Button
                     onClick={onConfirm}
                     color={confirmButtonColor}
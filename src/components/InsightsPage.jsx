@@ -11,9 +11,10 @@ import VisibilityIcon from '@mui/icons-material/Visibility';
 import CloseIcon from '@mui/icons-material/Close';
 import ArticleIcon from '@mui/icons-material/Article';
 import AutoAwesomeIcon from '@mui/icons-material/AutoAwesome';
-import { scrollbarStyles, formatDate, getMainContent, extractEmotionFromContent, getEmotionColor } from '../utils';
+import { scrollbarStyles, formatDate, getEmotionColor } from '../utils';
 
-function InsightsPage({ entries, onBack, handleEntrySelect }) {
+// entries are the summaries loaded so far; older pages come in through onLoadMoreEntries
+function InsightsPage({ entries, hasMoreEntries, loadingMoreEntries, onLoadMoreEntries, onBack, handleEntrySelect }) {
     const theme = useTheme(); // Using useTheme as it's standard practice within components
     const [insightsViewMode, setInsightsViewMode] = useState("Informative");
     const [calendarGranularity, setCalendarGranularity] = useState("month");
@@ -62,7 +63,7 @@ function InsightsPage({ entries, onBack, handleEntrySelect }) {
     const emotionCounts = useMemo(() => {
         const counts = { sadness: 0, angry: 0, neutral: 0, joy: 0, disgust: 0, fear: 0, surprise: 0, unknown: 0 };
         entries.forEach(entry => {
-            const extractedEmotion = extractEmotionFromContent(entry.content)?.toLowerCase();
+            const extractedEmotion = entry.emotion?.toLowerCase();
             let targetKey = extractedEmotion === "anger" ? "angry" : extractedEmotion;
             if (targetKey && counts.hasOwnProperty(targetKey)) counts[targetKey]++;
             else if (extractedEmotion) counts.unknown++;
@@ -71,11 +72,7 @@ function InsightsPage({ entries, onBack, handleEntrySelect }) {
         return counts;
     }, [entries]);
 
-    const getEntryPreview = (content) => {
-        const main = getMainContent(content);
-        const lines = main.split('\n');
-        return lines.slice(0, 5).join('\n') + (lines.length > 5 ? '...' : '');
-    };
+    const getEntryPreview = (entry) => entry.protected ? "This entry is password protected." : entry.excerpt;
 
     const EmotionSummaryList = () => {
         const orderedEmotionKeys = ['angry', 'disgust', 'fear', 'joy', 'neutral', 'sadness', 'surprise'];
@@ -122,7 +119,7 @@ function InsightsPage({ entries, onBack, handleEntrySelect }) {
     };
 
     const renderDayCell = (dateStr, dayNumber, entryForDay) => {
-        const rawEmotion = entryForDay ? extractEmotionFromContent(entryForDay.content) : null;
+        const rawEmotion = entryForDay ? entryForDay.emotion : null;
         const squareColor = rawEmotion ? getEmotionColor(rawEmotion, theme) : (entryForDay ? (theme.palette.mode === 'light' && theme.palette.primary.main === '#DAA520' ? alpha(theme.palette.secondary.main, 0.5) : theme.palette.grey[700]) : alpha(theme.palette.background.paper, 0.8));
         let displayEmotionText = rawEmotion || (entryForDay ? "Unknown" : "No Entry");
         if (rawEmotion?.toLowerCase() === 'anger' || rawEmotion?.toLowerCase() === 'angry') displayEmotionText = 'Anger';
@@ -191,7 +188,7 @@ function InsightsPage({ entries, onBack, handleEntrySelect }) {
                             <Box sx={{ ...scrollbarStyles(theme), height: '100%' }}>
                                 <Box sx={{ p: theme.spacing(1.5) }}>
                                     {entries.map(entry => {
-                                        const rawEmotion = extractEmotionFromContent(entry.content);
+                                        const rawEmotion = entry.emotion;
                                         const cardEmotionColor = rawEmotion ? getEmotionColor(rawEmotion, theme) : theme.palette.text.disabled;
                                         let cardDisplayText = rawEmotion;
                                         if (rawEmotion?.toLowerCase() === 'anger' || rawEmotion?.toLowerCase() === 'angry') cardDisplayText = 'Anger';
@@ -254,13 +251,20 @@ function InsightsPage({ entries, onBack, handleEntrySelect }) {
                                                                 minHeight: '8em',
                                                             }}
                                                         >
-                                                            {getEntryPreview(entry.content)}
+                                                            {getEntryPreview(entry)}
                                                         </Typography>
                                                     </CardContent>
                                                 </CardActionArea>
                                             </Card>
                                         );
                                     })}
+                                    {hasMoreEntries && (
+                                        <Box sx={{ display: 'flex', justifyContent: 'center', mt: 2 }}>
+                                            <Button variant="text" onClick={onLoadMoreEntries} disabled={loadingMoreEntries}>
+                                                Load Older Entries
+                                            </Button>
+                                        </Box>
+                                    )}
                                 </Box>
                             </Box>
                         ) : <Typography sx={{ textAlign: 'center', mt: 3 }}>No journal entries to display.</Typography>
@@ -391,7 +395,7 @@ function InsightsPage({ entries, onBack, handleEntrySelect }) {
                                     {formatDate(selectedInsightEntry.date)}
                                 </Typography>
                                 {(() => {
-                                    const rawEmotion = extractEmotionFromContent(selectedInsightEntry.content);
+                                    const rawEmotion = selectedInsightEntry.emotion;
                                     const emotionColor = rawEmotion ? getEmotionColor(rawEmotion, theme) : theme.palette.text.disabled;
                                     let displayText = rawEmotion;
                                     if (rawEmotion?.toLowerCase() === 'anger' || rawEmotion?.toLowerCase() === 'angry') displayText = 'Anger';
@@ -422,7 +426,7 @@ function InsightsPage({ entries, onBack, handleEntrySelect }) {
                                         minHeight: '8em',
                                     }}
                                 >
-                                    {getMainContent(selectedInsightEntry.content)}
+                                    {getEntryPreview(selectedInsightEntry)}
                                 </Typography>
                                 <Box sx={{ display: 'flex', justifyContent: 'flex-end', gap: 1, mt: 'auto' }}>
                                     <Button
@@ -1,7 +1,7 @@
 import React, { useState, useEffect } from 'react';
 import {
     Box, Button, Paper, Typography, TextField, Card, CardActionArea, CardContent, Grid, IconButton,
-    Popover, FormGroup, FormControlLabel, Switch
+    Popover, FormGroup, FormControlLabel, Switch, Chip
 } from '@mui/material';
 import { alpha, useTheme } from '@mui/material/styles';
 import ArrowBackIcon from '@mui/icons-material/ArrowBack';
@@ -13,7 +13,7 @@ import ExpandMoreIcon from '@mui/icons-material/ExpandMore';
 import ExpandLessIcon from '@mui/icons-material/ExpandLess';
 import PhotoCameraIcon from '@mui/icons-material/PhotoCamera';
 import TuneIcon from '@mui/icons-material/Tune';
-import { convertFileSrc } from '@tauri-apps/api/core';
+import { convertFileSrc, invoke } from '@tauri-apps/api/core';
 import { appLocalDataDir, resolve as resolvePath } from '@tauri-apps/api/path';
 import ColorThief from 'colorthief';
 
@@ -23,7 +23,8 @@ import {
     extractEmotionFromContent,
     extractSuggestionFromContent,
     getEmotionColor,
-    parseSuggestions
+    parseSuggestions,
+    DELETE_ENTRY_CONFIRMATION
 } from '../utils';
 import ConfirmationDialog from './ConfirmationDialog';
 
@@ -97,6 +98,31 @@ function JournalEntryView({
     const [optionsAnchorEl, setOptionsAnchorEl] = useState(null);
     const [useImageAsBackground, setUseImageAsBackground] = useState(false);
     const [monetThemeEnabled, setMonetThemeEnabled] = useState(false);
+    const [entryTags, setEntryTags] = useState([]);
+    const [newTagText, setNewTagText] = useState("");
+
+    // #hashtags are picked up when the entry is saved, so the tags are reloaded whenever its content changes
+    useEffect(() => {
+        if (!selectedEntry?.date) { setEntryTags([]); return; }
+        invoke("get_entry_tags_cmd", { date: selectedEntry.date })
+            .then(tags => setEntryTags(tags || []))
+            .catch(err => { console.error("Error loading tags:", err); setEntryTags([]); });
+    }, [selectedEntry?.date, selectedEntry?.content]);
+
+    const handleAddTag = async () => {
+        const name = newTagText.trim();
+        if (!name || !selectedEntry) return;
+        try {
+            setEntryTags(await invoke("add_entry_tag_cmd", { date: selectedEntry.date, name }));
+            setNewTagText("");
+        } catch (err) { console.error("Error adding tag:", err); }
+    };
+
+    const handleRemoveTag = async (name) => {
+        if (!selectedEntry) return;
+        try { setEntryTags(await invoke("remove_entry_tag_cmd", { date: selectedEntry.date, name })); }
+        catch (err) { console.error("Error removing tag:", err); }
+    };
 
     useEffect(() => {
         if (selectedEntry?.image) {
@@ -359,6 +385,25 @@ function JournalEntryView({
                                     </Typography>
                                 </Paper>
                             </Box>
+                            <Box sx={{ flexShrink: 0, px: 1.5 }}>
+                                <Typography variant="overline" color="text.secondary" sx={{ display: 'block', mb: 0.5, textAlign: 'center' }}>
+                                    Tags
+                                </Typography>
+                                <Box sx={{ display: 'flex', flexWrap: 'wrap', gap: 0.5, justifyContent: 'center', mb: 1 }}>
+                                    {entryTags.map(tag => (
+                                        <Chip key={tag} label={`#${tag}`} size="small" onDelete={() => handleRemoveTag(tag)} disabled={saving} />
+                                    ))}
+                                </Box>
+                                <TextField
+                                    value={newTagText}
+                                    onChange={(e) => setNewTagText(e.target.value)}
+                                    onKeyDown={(e) => { if (e.key === 'Enter') { e.preventDefault(); handleAddTag(); } }}
+                                    size="small"
+                                    fullWidth
+                                    placeholder="Add a tag"
+                                    disabled={saving}
+                                />
+                            </Box>
                             <Box sx={{ flexGrow: 1, display: 'flex', flexDirection: 'column', minHeight: 0, p: 1.5, pt: 0 }}>
                                 <Typography variant="overline" color="text.secondary" sx={{ display: 'block', mb: 0.5, textAlign: 'center' }}>
                                     Feedback
@@ -465,7 +510,7 @@ function JournalEntryView({
                 onClose={onCloseDeleteConfirm}
                 onConfirm={onConfirmDeleteEntry}
                 title="Confirm Deletion"
-                contentText="Are you sure you want to delete this journal entry? This action cannot be undone."
+                contentText={DELETE_ENTRY_CONFIRMATION}
                 confirmButtonText="Delete"
                 confirmButtonColor="error"
                 ConfirmButtonIcon={DeleteIcon}
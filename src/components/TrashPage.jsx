@@ -0,0 +1,164 @@
+import React, { useState, useEffect, useCallback } from 'react';
+import { invoke } from "@tauri-apps/api/core";
+import {
+    Box, Button, Paper, Typography, CircularProgress, Divider,
+    List, ListItem, ListItemIcon, ListItemText
+} from '@mui/material';
+import { useTheme } from '@mui/material/styles';
+import ArrowBackIcon from '@mui/icons-material/ArrowBack';
+import ArticleIcon from '@mui/icons-material/Article';
+import ChatIcon from '@mui/icons-material/Chat';
+import RestoreIcon from '@mui/icons-material/Restore';
+import DeleteForeverIcon from '@mui/icons-material/DeleteForever';
+import { scrollbarStyles, formatDate, formatChatTimestamp } from '../utils';
+import ConfirmationDialog from './ConfirmationDialog';
+
+// deleted entries and chat sessions; purging removes an entry's images and attachments as well
+function TrashPage({ setStatus, onBack, onTrashChanged }) {
+    const theme = useTheme();
+    const [trash, setTrash] = useState({ entries: [], chatSessions: [] });
+    const [loading, setLoading] = useState(true);
+    const [busy, setBusy] = useState(false);
+    // { kind: 'entry' | 'chat' | 'all', id, label } while a purge waits for confirmation
+    const [pendingPurge, setPendingPurge] = useState(null);
+
+    const refreshTrash = useCallback(async () => {
+        setLoading(true);
+        try {
+            setTrash(await invoke("list_trash_cmd"));
+        } catch (err) {
+            console.error("Error loading the trash:", err);
+            setStatus({ message: `Error loading the trash: ${err.message || String(err)}`, severity: "error" });
+        } finally { setLoading(false); }
+    }, [setStatus]);
+
+    useEffect(() => { refreshTrash(); }, [refreshTrash]);
+
+    const runAction = async (action, successMessage) => {
+        setBusy(true);
+        try {
+            await action();
+            setStatus({ message: successMessage, severity: "success" });
+            await refreshTrash();
+            if (onTrashChanged) onTrashChanged();
+        } catch (err) {
+            setStatus({ message: `${err.message || String(err)}`, severity: "error" });
+        } finally { setBusy(false); }
+    };
+
+    const handleRestoreEntry = (date) =>
+        runAction(() => invoke("restore_entry_cmd", { date }), `Entry for ${formatDate(date)} restored.`);
+    const handleRestoreChat = (sessionId) =>
+        runAction(() => invoke("restore_chat_session_cmd", { sessionId }), "Chat session restored.");
+
+    const handleConfirmPurge = async () => {
+        const purge = pendingPurge;
+        setPendingPurge(null);
+        if (!purge) return;
+        if (purge.kind === 'entry') {
+            await runAction(() => invoke("purge_entry_cmd", { date: purge.id }), `Entry for ${formatDate(purge.id)} deleted for good.`);
+        } else if (purge.kind === 'chat') {
+            await runAction(() => invoke("purge_chat_session_cmd", { sessionId: purge.id }), "Chat session deleted for good.");
+        } else {
+            await runAction(() => invoke("empty_trash_cmd"), "Trash emptied.");
+        }
+    };
+
+    const isEmpty = trash.entries.length === 0 && trash.chatSessions.length === 0;
+
+    const renderItem = (key, icon, primary, secondary, onRestore, onPurge) => (
+        <ListItem
+            key={key}
+            sx={{ borderRadius: '8px', '&:hover': { bgcolor: 'action.hover' } }}
+            secondaryAction={
+                <Box sx={{ display: 'flex', gap: 1 }}>
+                    <Button size="small" variant="outlined" startIcon={<RestoreIcon />} onClick={onRestore} disabled={busy}>
+                        Restore
+                    </Button>
+                    <Button size="small" variant="outlined" color="error" startIcon={<DeleteForeverIcon />} onClick={onPurge} disabled={busy}>
+                        Delete
+                    </Button>
+                </Box>
+            }
+        >
+            <ListItemIcon>{icon}</ListItemIcon>
+            <ListItemText primary={primary} secondary={secondary} sx={{ pr: 24 }} />
+        </ListItem>
+    );
+
+    return (
+        <>
+            <Box sx={{ mb: 2, display: 'flex', justifyContent: 'space-between', alignItems: 'center', flexShrink: 0 }}>
+                <Button startIcon={<ArrowBackIcon />} onClick={onBack} variant="contained">Back to Journal</Button>
+                <Button
+                    color="error"
+                    variant="outlined"
+                    startIcon={<DeleteForeverIcon />}
+                    onClick={() => setPendingPurge({ kind: 'all' })}
+                    disabled={busy || loading || isEmpty}
+                >
+                    Empty Trash
+                </Button>
+            </Box>
+            <Paper sx={{ p: 1, width: '100%', flexGrow: 1, minWidth: 0, borderRadius: '16px', display: 'flex', flexDirection: 'column', overflow: 'hidden' }}>
+                <Box sx={{ ...scrollbarStyles(theme), height: '100%' }}>
+                    <Box sx={{ p: theme.spacing(1.5) }}>
+                        {loading ? (
+                            <Box sx={{ display: 'flex', justifyContent: 'center', mt: 3 }}><CircularProgress /></Box>
+                        ) : isEmpty ? (
+                            <Typography sx={{ textAlign: 'center', mt: 3 }} color="text.secondary">The trash is empty.</Typography>
+                        ) : (
+                            <>
+                                {trash.entries.length > 0 && (
+                                    <>
+                                        <Typography variant="subtitle1" sx={{ fontWeight: 'bold', px: 2 }}>Entries</Typography>
+                                        <List>
+                                            {trash.entries.map(entry => renderItem(
+                                                entry.date,
+                                                <ArticleIcon />,
+                                                `${formatDate(entry.date)}${entry.title ? ` · ${entry.title}` : ''}`,
+                                                `Deleted ${formatChatTimestamp(entry.deletedAt)}`,
+                                                () => handleRestoreEntry(entry.date),
+                                                () => setPendingPurge({ kind: 'entry', id: entry.date }),
+                                            ))}
+                                        </List>
+                                    </>
+                                )}
+                                {trash.entries.length > 0 && trash.chatSessions.length > 0 && <Divider sx={{ my: 1 }} />}
+                                {trash.chatSessions.length > 0 && (
+                                    <>
+                                        <Typography variant="subtitle1" sx={{ fontWeight: 'bold', px: 2 }}>Chat Sessions</Typography>
+                                        <List>
+                                            {trash.chatSessions.map(session => renderItem(
+                                                session.id,
+                                                <ChatIcon />,
+                                                session.title || "Untitled chat",
+                                                `Deleted ${formatChatTimestamp(session.deletedAt)}`,
+                                                () => handleRestoreChat(session.id),
+                                                () => setPendingPurge({ kind: 'chat', id: session.id }),
+                                            ))}
+                                        </List>
+                                    </>
+                                )}
+                            </>
+                        )}
+                    </Box>
+                </Box>
+            </Paper>
+            <ConfirmationDialog
+                open={!!pendingPurge}
+                onClose={() => setPendingPurge(null)}
+                onConfirm={handleConfirmPurge}
+                title={pendingPurge?.kind === 'all' ? "Empty Trash" : "Delete for Good"}
+                contentText={pendingPurge?.kind === 'all'
+                    ? "Everything in the trash, including the images and attachments of its entries, will be deleted for good. This cannot be undone."
+                    : "This will be deleted for good, including any images and attachments. This cannot be undone."}
+                confirmButtonText="Delete"
+                confirmButtonColor="error"
+                ConfirmButtonIcon={DeleteForeverIcon}
+            />
+        </>
+    );
+}
+
+export default TrashPage;
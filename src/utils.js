@@ -1,7 +1,13 @@
 import { alpha } from '@mui/material/styles';
 
 export const INITIAL_VISIBLE_ENTRIES = 5;
+// entries fetched per list_entries call
+export const ENTRY_PAGE_SIZE = 50;
 export const ALERT_TIMEOUT_DURATION = 10000;
+// draft slot of the new entry page, see drafts::NEW_ENTRY_SLOT
+export const NEW_ENTRY_DRAFT_SLOT = "new";
+// a new entry for the same day replaces a trashed one, see services::create_entry
+export const DELETE_ENTRY_CONFIRMATION = "Are you sure you want to delete this journal entry? It will be moved to the Trash, where you can restore it until the trash is emptied. While it is there, a new entry for the same day can only be saved after restoring it or deleting it for good.";
 
 export const getMainContent = (fullContent) => {
     if (!fullContent) return "";